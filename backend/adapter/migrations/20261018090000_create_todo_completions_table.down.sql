@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TRIGGER IF EXISTS todo_completions_updated_at_trigger ON todo_completions;
+DROP TABLE IF EXISTS todo_completions;
@@ -0,0 +1,29 @@
+-- Add up migration script here
+
+-- todo_completions テーブル（完了・再オープンの履歴）
+CREATE TABLE IF NOT EXISTS todo_completions (
+  id UUID PRIMARY KEY,
+  todo_id UUID NOT NULL,
+  user_id UUID NOT NULL,
+  completed_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  reopened_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+  FOREIGN KEY (todo_id) REFERENCES todos(id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE,
+  FOREIGN KEY (user_id) REFERENCES users(id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+-- 再オープンされていない完了は todo ごとに 1 件まで
+CREATE UNIQUE INDEX IF NOT EXISTS todo_completions_open_todo_id_idx
+  ON todo_completions (todo_id)
+  WHERE reopened_at IS NULL;
+
+-- todo_completions テーブルの updated_at を自動更新するためのトリガー
+CREATE TRIGGER todo_completions_updated_at_trigger
+  BEFORE UPDATE ON todo_completions FOR EACH ROW
+  EXECUTE FUNCTION set_updated_at();
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS todos_title_trgm_idx;
+DROP INDEX IF EXISTS todos_search_vector_idx;
+ALTER TABLE todos DROP COLUMN IF EXISTS search_vector;
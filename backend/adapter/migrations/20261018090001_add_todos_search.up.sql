@@ -0,0 +1,16 @@
+-- Add up migration script here
+
+-- 日本語は空白で分かち書きされないため、部分一致用に pg_trgm も使う
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- 全文検索用の tsvector。言語に依存しない simple 設定で語を切り出す
+ALTER TABLE todos
+  ADD COLUMN search_vector tsvector
+  GENERATED ALWAYS AS (to_tsvector('simple', coalesce(title, ''))) STORED;
+
+CREATE INDEX IF NOT EXISTS todos_search_vector_idx
+  ON todos USING GIN (search_vector);
+
+-- ILIKE による部分一致と similarity() のためのトライグラム索引
+CREATE INDEX IF NOT EXISTS todos_title_trgm_idx
+  ON todos USING GIN (title gin_trgm_ops);
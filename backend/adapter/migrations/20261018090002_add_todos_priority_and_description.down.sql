@@ -0,0 +1,14 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS todos_description_trgm_idx;
+DROP INDEX IF EXISTS todos_search_vector_idx;
+ALTER TABLE todos DROP COLUMN IF EXISTS search_vector;
+ALTER TABLE todos
+  ADD COLUMN search_vector tsvector
+  GENERATED ALWAYS AS (to_tsvector('simple', coalesce(title, ''))) STORED;
+CREATE INDEX IF NOT EXISTS todos_search_vector_idx
+  ON todos USING GIN (search_vector);
+
+ALTER TABLE todos
+  DROP COLUMN IF EXISTS description,
+  DROP COLUMN IF EXISTS priority;
+DROP TYPE IF EXISTS todo_priority;
@@ -0,0 +1,24 @@
+-- Add up migration script here
+
+-- todo の重要度
+CREATE TYPE todo_priority AS ENUM ('none', 'low', 'medium', 'high', 'urgent');
+
+ALTER TABLE todos
+  ADD COLUMN priority todo_priority NOT NULL DEFAULT 'none',
+  ADD COLUMN description TEXT;
+
+-- 説明文も全文検索の対象にする。生成列の式は変更できないため作り直す
+DROP INDEX IF EXISTS todos_search_vector_idx;
+ALTER TABLE todos DROP COLUMN search_vector;
+ALTER TABLE todos
+  ADD COLUMN search_vector tsvector
+  GENERATED ALWAYS AS (
+    setweight(to_tsvector('simple', coalesce(title, '')), 'A')
+      || setweight(to_tsvector('simple', coalesce(description, '')), 'B')
+  ) STORED;
+
+CREATE INDEX IF NOT EXISTS todos_search_vector_idx
+  ON todos USING GIN (search_vector);
+
+CREATE INDEX IF NOT EXISTS todos_description_trgm_idx
+  ON todos USING GIN (description gin_trgm_ops);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS todo_tags;
+DROP TRIGGER IF EXISTS tags_updated_at_trigger ON tags;
+DROP TABLE IF EXISTS tags;
@@ -0,0 +1,39 @@
+-- Add up migration script here
+
+-- tags テーブル（ユーザごとのラベル）
+CREATE TABLE IF NOT EXISTS tags (
+  id UUID PRIMARY KEY,
+  user_id UUID NOT NULL,
+  name VARCHAR(50) NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+  -- 同じユーザが同じ名前のタグを重複して作れないようにする
+  CONSTRAINT tags_name_key UNIQUE (user_id, name),
+  FOREIGN KEY (user_id) REFERENCES users(id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+-- todo_tags テーブル（todo とタグの多対多）
+CREATE TABLE IF NOT EXISTS todo_tags (
+  todo_id UUID NOT NULL,
+  tag_id UUID NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+  PRIMARY KEY (todo_id, tag_id),
+  FOREIGN KEY (todo_id) REFERENCES todos(id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE,
+  FOREIGN KEY (tag_id) REFERENCES tags(id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+-- タグから todo を引く絞り込み用
+CREATE INDEX IF NOT EXISTS todo_tags_tag_id_idx ON todo_tags (tag_id);
+
+-- tags テーブルの updated_at を自動更新するためのトリガー
+CREATE TRIGGER tags_updated_at_trigger
+  BEFORE UPDATE ON tags FOR EACH ROW
+  EXECUTE FUNCTION set_updated_at();
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS todos_project_id_idx;
+ALTER TABLE todos DROP COLUMN IF EXISTS project_id;
+DROP TRIGGER IF EXISTS projects_updated_at_trigger ON projects;
+DROP TABLE IF EXISTS projects;
@@ -0,0 +1,33 @@
+-- Add up migration script here
+
+-- projects テーブル（todo をまとめるユーザごとのリスト）
+CREATE TABLE IF NOT EXISTS projects (
+  id UUID PRIMARY KEY,
+  user_id UUID NOT NULL,
+  name VARCHAR(100) NOT NULL,
+  -- `#rrggbb` 形式の表示色
+  colour VARCHAR(7) NOT NULL DEFAULT '#808080',
+  archived BOOLEAN NOT NULL DEFAULT FALSE,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+  -- 同じユーザが同じ名前のプロジェクトを重複して作れないようにする
+  CONSTRAINT projects_name_key UNIQUE (user_id, name),
+  FOREIGN KEY (user_id) REFERENCES users(id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+-- projects テーブルの updated_at を自動更新するためのトリガー
+CREATE TRIGGER projects_updated_at_trigger
+  BEFORE UPDATE ON projects FOR EACH ROW
+  EXECUTE FUNCTION set_updated_at();
+
+-- todo の所属プロジェクト。NULL なら受信箱
+-- プロジェクト削除時の todo の扱い（削除か受信箱への移動か）はアプリケーション側で先に処理する
+ALTER TABLE todos
+  ADD COLUMN project_id UUID REFERENCES projects(id)
+    ON UPDATE CASCADE
+    ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS todos_project_id_idx ON todos (project_id);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS todos_parent_id_idx;
+ALTER TABLE todos DROP CONSTRAINT IF EXISTS todos_parent_id_check;
+ALTER TABLE todos DROP COLUMN IF EXISTS parent_id;
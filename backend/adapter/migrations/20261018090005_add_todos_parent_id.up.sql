@@ -0,0 +1,14 @@
+-- Add up migration script here
+
+-- 親の todo。NULL なら最上位。親を削除すると子孫もまとめて削除する
+ALTER TABLE todos
+  ADD COLUMN parent_id UUID REFERENCES todos(id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE;
+
+-- 自身を親にはできない（より深い循環はアプリケーション側で防ぐ）
+ALTER TABLE todos
+  ADD CONSTRAINT todos_parent_id_check CHECK (parent_id <> id);
+
+-- 子を辿る再帰クエリ用
+CREATE INDEX IF NOT EXISTS todos_parent_id_idx ON todos (parent_id);
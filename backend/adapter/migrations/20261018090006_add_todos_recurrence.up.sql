@@ -0,0 +1,4 @@
+-- Add up migration script here
+
+-- 繰り返しの規則（RFC 5545 の RRULE の一部）。NULL なら繰り返さない
+ALTER TABLE todos ADD COLUMN recurrence TEXT;
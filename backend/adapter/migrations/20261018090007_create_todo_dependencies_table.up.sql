@@ -0,0 +1,21 @@
+-- Add up migration script here
+
+-- todo_dependencies テーブル（blocker_id の todo が完了するまで todo_id の todo を完了できない）
+CREATE TABLE IF NOT EXISTS todo_dependencies (
+  todo_id UUID NOT NULL,
+  blocker_id UUID NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+  PRIMARY KEY (todo_id, blocker_id),
+  -- 自身には依存できない（より長い循環はアプリケーション側で防ぐ）
+  CONSTRAINT todo_dependencies_check CHECK (todo_id <> blocker_id),
+  FOREIGN KEY (todo_id) REFERENCES todos(id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE,
+  FOREIGN KEY (blocker_id) REFERENCES todos(id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+-- blocker から妨げている todo を引く用
+CREATE INDEX IF NOT EXISTS todo_dependencies_blocker_id_idx ON todo_dependencies (blocker_id);
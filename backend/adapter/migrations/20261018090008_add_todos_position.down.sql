@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS todos_user_id_position_idx;
+ALTER TABLE todos DROP COLUMN IF EXISTS position;
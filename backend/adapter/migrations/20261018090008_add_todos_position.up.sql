@@ -0,0 +1,31 @@
+-- Add up migration script here
+
+-- 手動の並び順でのキー（fractional indexing）。バイト列の辞書順で比較するため照合順序は "C" にする
+ALTER TABLE todos ADD COLUMN position TEXT COLLATE "C";
+
+-- 既存の todo には作成順にキーを振る。整数部が 4 桁の "d" 始まりのキーで、
+-- ユーザごとに 62^4 件まで表せる
+UPDATE todos
+SET position = ranked.position
+FROM (
+  SELECT
+    id,
+    'd'
+      || substr(digits, (n / 238328) % 62 + 1, 1)
+      || substr(digits, (n / 3844) % 62 + 1, 1)
+      || substr(digits, (n / 62) % 62 + 1, 1)
+      || substr(digits, n % 62 + 1, 1) AS position
+  FROM (
+    SELECT
+      id,
+      ROW_NUMBER() OVER (PARTITION BY user_id ORDER BY created_at, id)::INT AS n,
+      '0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz' AS digits
+    FROM todos
+  ) AS numbered
+) AS ranked
+WHERE todos.id = ranked.id;
+
+ALTER TABLE todos ALTER COLUMN position SET NOT NULL;
+
+-- 手動の並び順での一覧と、末尾のキーを引く用
+CREATE INDEX IF NOT EXISTS todos_user_id_position_idx ON todos (user_id, position);
@@ -0,0 +1,23 @@
+-- Add up migration script here
+
+-- personal_access_tokens テーブル（自動化用の長期間有効な API キー）
+CREATE TABLE IF NOT EXISTS personal_access_tokens (
+  id UUID PRIMARY KEY,
+  user_id UUID NOT NULL,
+  name VARCHAR(100) NOT NULL,
+  -- シークレットの SHA-256 の16進表記。平文は作成時に返すだけで保存しない
+  token_hash CHAR(64) NOT NULL,
+  -- `todos:read` / `todos:write` / `admin`
+  scopes TEXT[] NOT NULL,
+  -- NULL なら失効させるまで使える
+  expires_at TIMESTAMPTZ,
+  last_used_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+  CONSTRAINT personal_access_tokens_token_hash_key UNIQUE (token_hash),
+  -- 同じユーザが同じ名前のトークンを重複して作れないようにする
+  CONSTRAINT personal_access_tokens_name_key UNIQUE (user_id, name),
+  FOREIGN KEY (user_id) REFERENCES users(id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
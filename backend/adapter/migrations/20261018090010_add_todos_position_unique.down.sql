@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE todos DROP CONSTRAINT IF EXISTS todos_user_id_position_key;
+CREATE INDEX IF NOT EXISTS todos_user_id_position_idx ON todos (user_id, position);
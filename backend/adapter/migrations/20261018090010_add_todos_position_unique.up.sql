@@ -0,0 +1,29 @@
+-- Add up migration script here
+
+-- 同時に末尾へ追加したときに同じキーが振られていた場合は、そのユーザの todo に並び順を保ったままキーを振り直す
+UPDATE todos
+SET position = ranked.position
+FROM (
+  SELECT
+    id,
+    'd'
+      || substr(digits, (n / 238328) % 62 + 1, 1)
+      || substr(digits, (n / 3844) % 62 + 1, 1)
+      || substr(digits, (n / 62) % 62 + 1, 1)
+      || substr(digits, n % 62 + 1, 1) AS position
+  FROM (
+    SELECT
+      id,
+      ROW_NUMBER() OVER (PARTITION BY user_id ORDER BY position, created_at, id)::INT AS n,
+      '0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz' AS digits
+    FROM todos
+    WHERE user_id IN (
+      SELECT user_id FROM todos GROUP BY user_id, position HAVING COUNT(*) > 1
+    )
+  ) AS numbered
+) AS ranked
+WHERE todos.id = ranked.id;
+
+-- 一覧用のインデックスは一意制約のインデックスで兼ねる
+DROP INDEX IF EXISTS todos_user_id_position_idx;
+ALTER TABLE todos ADD CONSTRAINT todos_user_id_position_key UNIQUE (user_id, position);
@@ -0,0 +1,66 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use kernel::model::list::{Cursor, SortOrder};
+use shared::error::{AppError, AppResult};
+use sqlx::{Postgres, QueryBuilder};
+
+// 並び替えに使うカラム。カーソルに入れた値を元の型に戻してバインドするため型を持つ
+#[derive(Debug, Clone, Copy)]
+pub enum SortColumn {
+    Timestamp(&'static str),
+    Text(&'static str),
+}
+
+impl SortColumn {
+    fn name(&self) -> &'static str {
+        match self {
+            SortColumn::Timestamp(name) | SortColumn::Text(name) => name,
+        }
+    }
+}
+
+pub fn timestamp_key(value: DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+// `(sort_column, id)` の組で比較し、カーソル位置より後ろの行に絞り込む
+pub fn push_keyset_condition(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    column: SortColumn,
+    order: SortOrder,
+    cursor: &Cursor,
+) -> AppResult<()> {
+    let op = match order {
+        SortOrder::Asc => ">",
+        SortOrder::Desc => "<",
+    };
+    builder.push(format!("({}, id) {} (", column.name(), op));
+    match column {
+        SortColumn::Timestamp(_) => {
+            let key: DateTime<Utc> = cursor
+                .key
+                .parse()
+                .map_err(|_| AppError::ConversionEntityError("Invalid cursor".into()))?;
+            builder.push_bind(key);
+        }
+        SortColumn::Text(_) => {
+            builder.push_bind(cursor.key.clone());
+        }
+    }
+    builder.push(", ").push_bind(cursor.id).push(")");
+    Ok(())
+}
+
+pub fn push_order_by(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    column: SortColumn,
+    order: SortOrder,
+) {
+    let direction = match order {
+        SortOrder::Asc => "ASC",
+        SortOrder::Desc => "DESC",
+    };
+    builder.push(format!(
+        " ORDER BY {} {direction}, id {direction}",
+        column.name()
+    ));
+}
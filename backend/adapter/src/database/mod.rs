@@ -1,8 +1,17 @@
-use shared::config::DatabaseConfig;
-use sqlx::{PgPool, postgres::PgConnectOptions};
+use shared::{
+    config::DatabaseConfig,
+    error::{AppError, AppResult},
+};
+use sqlx::{
+    PgPool, Postgres, Transaction,
+    postgres::{PgConnectOptions, PgDatabaseError},
+};
 
+pub mod list;
 pub mod model;
 
+const UNIQUE_VIOLATION: &str = "23505";
+
 #[derive(Clone)]
 pub struct ConnectionPool(PgPool);
 
@@ -14,6 +23,10 @@ impl ConnectionPool {
     pub fn inner_ref(&self) -> &PgPool {
         &self.0
     }
+
+    pub async fn begin(&self) -> AppResult<Transaction<'_, Postgres>> {
+        self.0.begin().await.map_err(AppError::TransactionError)
+    }
 }
 
 pub fn connect_database_with(cfg: &DatabaseConfig) -> ConnectionPool {
@@ -28,3 +41,62 @@ fn make_pg_connect_options(cfg: &DatabaseConfig) -> PgConnectOptions {
         .password(&cfg.password)
         .database(&cfg.database)
 }
+
+// 一意制約違反は Conflict に、それ以外は SqlExecuteError に変換する
+pub fn map_sql_error(e: sqlx::Error) -> AppError {
+    let conflict = e
+        .as_database_error()
+        .and_then(|db_err| db_err.try_downcast_ref::<PgDatabaseError>())
+        .filter(|pg_err| pg_err.code() == UNIQUE_VIOLATION)
+        .and_then(|pg_err| {
+            pg_err
+                .constraint()
+                .map(|constraint| conflict_field(pg_err.table(), constraint))
+        });
+
+    match conflict {
+        Some(field) => AppError::Conflict(field),
+        None => AppError::SqlExecuteError(e),
+    }
+}
+
+// 制約名は Postgres の命名規則 `{table}_{column}_key`（インデックスなら `_idx`）に従う前提で、
+// テーブル名と接尾辞を取り除いてカラム名を得る
+fn conflict_field(table: Option<&str>, constraint: &str) -> String {
+    let column = table
+        .and_then(|table| constraint.strip_prefix(table))
+        .and_then(|rest| rest.strip_prefix('_'))
+        .unwrap_or(constraint);
+    let column = ["_key", "_idx"]
+        .iter()
+        .find_map(|suffix| column.strip_suffix(suffix))
+        .unwrap_or(column);
+    column.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn 制約名からカラム名を取り出す() {
+        assert_eq!(conflict_field(Some("users"), "users_email_key"), "email");
+        assert_eq!(
+            conflict_field(Some("tags"), "tags_user_id_name_key"),
+            "user_id_name"
+        );
+        assert_eq!(
+            conflict_field(
+                Some("todo_completions"),
+                "todo_completions_open_todo_id_idx"
+            ),
+            "open_todo_id"
+        );
+    }
+
+    #[test]
+    fn 命名規則に従わない制約名はそのまま返す() {
+        assert_eq!(conflict_field(None, "uniq_email"), "uniq_email");
+        assert_eq!(conflict_field(Some("users"), "uniq_email"), "uniq_email");
+    }
+}
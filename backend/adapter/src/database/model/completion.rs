@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use kernel::model::{
+    completion::{Completion, CompletionTodo},
+    id::{CompletionId, TodoId},
+};
+
+pub struct CompletionRow {
+    pub completion_id: CompletionId,
+    pub todo_id: TodoId,
+    pub title: String,
+    pub completed_at: DateTime<Utc>,
+    pub reopened_at: Option<DateTime<Utc>>,
+}
+
+impl From<CompletionRow> for Completion {
+    fn from(value: CompletionRow) -> Self {
+        let CompletionRow {
+            completion_id,
+            todo_id,
+            title,
+            completed_at,
+            reopened_at,
+        } = value;
+        Completion {
+            id: completion_id,
+            todo: CompletionTodo { id: todo_id, title },
+            completed_at,
+            reopened_at,
+        }
+    }
+}
@@ -1,2 +1,7 @@
 pub mod auth;
+pub mod completion;
+pub mod personal_access_token;
+pub mod project;
+pub mod tag;
+pub mod todo;
 pub mod user;
@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+use kernel::model::{
+    id::{PersonalAccessTokenId, UserId},
+    personal_access_token::PersonalAccessToken,
+};
+use shared::error::AppError;
+
+pub struct PersonalAccessTokenRow {
+    pub id: PersonalAccessTokenId,
+    pub user_id: UserId,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<PersonalAccessTokenRow> for PersonalAccessToken {
+    type Error = AppError;
+
+    fn try_from(value: PersonalAccessTokenRow) -> Result<Self, Self::Error> {
+        Ok(PersonalAccessToken {
+            id: value.id,
+            user_id: value.user_id,
+            name: value.name,
+            scopes: value
+                .scopes
+                .iter()
+                .map(|scope| scope.parse())
+                .collect::<Result<_, _>>()?,
+            expires_at: value.expires_at,
+            last_used_at: value.last_used_at,
+            created_at: value.created_at,
+        })
+    }
+}
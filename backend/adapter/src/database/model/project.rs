@@ -0,0 +1,43 @@
+use chrono::{DateTime, Utc};
+use kernel::model::{
+    id::{ProjectId, UserId},
+    project::{Project, ProjectProgress},
+};
+
+pub struct ProjectRow {
+    pub id: ProjectId,
+    pub user_id: UserId,
+    pub name: String,
+    pub colour: String,
+    pub archived: bool,
+    pub total: i64,
+    pub completed: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<ProjectRow> for Project {
+    fn from(value: ProjectRow) -> Self {
+        let ProjectRow {
+            id,
+            user_id,
+            name,
+            colour,
+            archived,
+            total,
+            completed,
+            created_at,
+            updated_at,
+        } = value;
+        Project {
+            id,
+            user_id,
+            name,
+            colour,
+            archived,
+            progress: ProjectProgress { total, completed },
+            created_at,
+            updated_at,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use kernel::model::{
+    id::{TagId, UserId},
+    tag::Tag,
+};
+
+pub struct TagRow {
+    pub id: TagId,
+    pub user_id: UserId,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<TagRow> for Tag {
+    fn from(value: TagRow) -> Self {
+        let TagRow {
+            id,
+            user_id,
+            name,
+            created_at,
+            updated_at,
+        } = value;
+        Tag {
+            id,
+            user_id,
+            name,
+            created_at,
+            updated_at,
+        }
+    }
+}
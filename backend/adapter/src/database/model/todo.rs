@@ -0,0 +1,67 @@
+use chrono::{DateTime, Utc};
+use kernel::model::{
+    id::{ProjectId, TodoId, UserId},
+    list::Cursor,
+    todo::{Priority, Todo, TodoSort},
+};
+use shared::error::AppError;
+
+use crate::database::list::timestamp_key;
+
+#[derive(sqlx::FromRow)]
+pub struct TodoRow {
+    pub id: TodoId,
+    pub user_id: UserId,
+    pub project_id: Option<ProjectId>,
+    pub parent_id: Option<TodoId>,
+    pub title: String,
+    pub description: Option<String>,
+    pub priority: Priority,
+    pub completed: bool,
+    pub due_at: Option<DateTime<Utc>>,
+    pub recurrence: Option<String>,
+    pub position: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<TodoRow> for Todo {
+    type Error = AppError;
+
+    fn try_from(value: TodoRow) -> Result<Self, Self::Error> {
+        Ok(Todo {
+            id: value.id,
+            user_id: value.user_id,
+            project_id: value.project_id,
+            parent_id: value.parent_id,
+            title: value.title,
+            description: value.description,
+            priority: value.priority,
+            completed: value.completed,
+            due_at: value.due_at,
+            recurrence: value.recurrence.as_deref().map(str::parse).transpose()?,
+            position: value.position.parse()?,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        })
+    }
+}
+
+impl TodoRow {
+    pub fn cursor(&self, sort: TodoSort) -> Cursor {
+        let key = match sort {
+            TodoSort::CreatedAt => timestamp_key(self.created_at),
+            TodoSort::UpdatedAt => timestamp_key(self.updated_at),
+            TodoSort::Title => self.title.clone(),
+            TodoSort::Manual => self.position.clone(),
+        };
+        Cursor::new(sort, key, self.id.raw())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct TodoSearchRow {
+    #[sqlx(flatten)]
+    pub todo: TodoRow,
+    pub rank: f32,
+}
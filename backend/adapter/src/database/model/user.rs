@@ -1,7 +1,14 @@
 use chrono::{DateTime, Utc};
-use kernel::model::{id::UserId, user::User};
+use kernel::model::{
+    id::UserId,
+    list::Cursor,
+    user::{User, UserSort},
+};
 use shared::error::AppError;
 
+use crate::database::list::timestamp_key;
+
+#[derive(sqlx::FromRow)]
 pub struct UserRow {
     pub id: UserId,
     pub name: String,
@@ -21,3 +28,14 @@ impl TryFrom<UserRow> for User {
         })
     }
 }
+
+impl UserRow {
+    pub fn cursor(&self, sort: UserSort) -> Cursor {
+        let key = match sort {
+            UserSort::CreatedAt => timestamp_key(self.created_at),
+            UserSort::Name => self.name.clone(),
+            UserSort::Email => self.email.clone(),
+        };
+        Cursor::new(sort, key, self.id.raw())
+    }
+}
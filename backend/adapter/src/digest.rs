@@ -0,0 +1,6 @@
+use sha2::{Digest, Sha256};
+
+// トークンは十分に長い乱数なので、総当たりを防ぐための鍵やソルトは付けずに SHA-256 を取るだけにする
+pub fn sha256_hex(value: &str) -> String {
+    hex::encode(Sha256::digest(value.as_bytes()))
+}
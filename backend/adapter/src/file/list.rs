@@ -0,0 +1,68 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use kernel::model::list::{Cursor, ListQuery, Page, SortKey, SortOrder};
+use shared::error::{AppError, AppResult};
+
+// 並び替えに使う値。Postgres 側の SortColumn と同じく型ごとに比較する
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SortValue {
+    Timestamp(DateTime<Utc>),
+    Text(String),
+}
+
+impl SortValue {
+    // ファイルの日時は Postgres と違ってナノ秒まで持つので、カーソルでも切り捨てずに表す
+    fn key(&self) -> String {
+        match self {
+            SortValue::Timestamp(value) => value.to_rfc3339_opts(SecondsFormat::Nanos, true),
+            SortValue::Text(value) => value.clone(),
+        }
+    }
+
+    // カーソルの文字列を自身と同じ型の値として読み直す
+    fn parse_key(&self, key: &str) -> AppResult<Self> {
+        match self {
+            SortValue::Timestamp(_) => key
+                .parse()
+                .map(SortValue::Timestamp)
+                .map_err(|_| AppError::ConversionEntityError("Invalid cursor".into())),
+            SortValue::Text(_) => Ok(SortValue::Text(key.to_string())),
+        }
+    }
+}
+
+// メモリ上のレコードを並び替え、カーソル位置の次から limit 件を返す
+pub fn paginate<R, T, S>(
+    mut records: Vec<&R>,
+    query: &ListQuery<S>,
+    sort_value: impl Fn(&R) -> SortValue,
+    id_of: impl Fn(&R) -> uuid::Uuid,
+) -> AppResult<Page<T>>
+where
+    T: for<'a> From<&'a R>,
+    S: SortKey,
+{
+    let key_of = |record: &R| (sort_value(record), id_of(record));
+    records.sort_by(|a, b| {
+        let ordering = key_of(a).cmp(&key_of(b));
+        match query.order {
+            SortOrder::Asc => ordering,
+            SortOrder::Desc => ordering.reverse(),
+        }
+    });
+
+    if let (Some(cursor), Some(first)) = (&query.cursor, records.first()) {
+        let position = (sort_value(first).parse_key(&cursor.key)?, cursor.id);
+        records.retain(|record| match query.order {
+            SortOrder::Asc => key_of(record) > position,
+            SortOrder::Desc => key_of(record) < position,
+        });
+    }
+    records.truncate(query.fetch_limit() as usize);
+
+    Page::from_rows(
+        records,
+        query.limit,
+        |record| Cursor::new(query.sort, sort_value(record).key(), id_of(record)),
+        |record| Ok(T::from(record)),
+    )
+}
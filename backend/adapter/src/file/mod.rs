@@ -0,0 +1,230 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{BufReader, BufWriter, ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+use fs4::fs_std::FileExt;
+use shared::{
+    config::FileConfig,
+    error::{AppError, AppResult},
+};
+
+use crate::file::model::FileData;
+
+pub mod list;
+pub mod model;
+pub mod repository;
+
+// 1 つの JSON ファイルに全データを保存する。読み書きのたびにファイル全体を読み直し、
+// 書き込みは一時ファイルへの書き出しと rename で置き換えることで途中状態を残さない。
+#[derive(Clone)]
+pub struct FileStore {
+    path: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(config: &FileConfig) -> Self {
+        Self {
+            path: config.path.clone(),
+        }
+    }
+
+    pub async fn read<F, T>(&self, f: F) -> AppResult<T>
+    where
+        F: FnOnce(&FileData) -> AppResult<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || {
+            let lock = open_lock_file(&path)?;
+            FileExt::lock_shared(&lock)?;
+            let data = load(&path)?;
+            f(&data)
+        })
+        .await
+        .map_err(|e| AppError::FileStoreError(std::io::Error::other(e)))?
+    }
+
+    pub async fn write<F, T>(&self, f: F) -> AppResult<T>
+    where
+        F: FnOnce(&mut FileData) -> AppResult<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || {
+            let lock = open_lock_file(&path)?;
+            FileExt::lock_exclusive(&lock)?;
+            let mut data = load(&path)?;
+            let result = f(&mut data)?;
+            save(&path, &data)?;
+            Ok(result)
+        })
+        .await
+        .map_err(|e| AppError::FileStoreError(std::io::Error::other(e)))?
+    }
+}
+
+// ロックはデータファイル自体ではなく隣に置いたロックファイルに対して取る。
+// データファイルは rename で置き換わるため、そちらをロックしても排他にならない。
+fn open_lock_file(path: &Path) -> AppResult<File> {
+    ensure_parent_dir(path)?;
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(with_suffix(path, ".lock"))?;
+    Ok(file)
+}
+
+fn load(path: &Path) -> AppResult<FileData> {
+    match File::open(path) {
+        Ok(file) => {
+            let mut data: FileData =
+                serde_json::from_reader(BufReader::new(file)).map_err(std::io::Error::from)?;
+            data.assign_missing_positions()?;
+            data.hash_legacy_refresh_tokens();
+            Ok(data)
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(FileData::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn save(path: &Path, data: &FileData) -> AppResult<()> {
+    // パスワードのハッシュやトークンを含むため、所有者だけが読める一時ファイルに書いてから置き換える。
+    // 前回の書き込みが途中で止まって残った一時ファイルは権限が違うかもしれないので作り直す
+    let tmp_path = with_suffix(path, ".tmp");
+    match fs::remove_file(&tmp_path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+        _ => {}
+    }
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let file = options.open(&tmp_path)?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer_pretty(&mut writer, data).map_err(std::io::Error::from)?;
+    writer.flush()?;
+    writer.get_ref().sync_all()?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+fn ensure_parent_dir(path: &Path) -> AppResult<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    Ok(())
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut s: OsString = path.as_os_str().to_owned();
+    s.push(suffix);
+    s.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::model::TokenRecord;
+    use chrono::Utc;
+    use kernel::model::id::UserId;
+
+    fn store_in(dir: &tempfile::TempDir) -> FileStore {
+        FileStore::new(&FileConfig {
+            path: dir.path().join("data").join("todo.json"),
+        })
+    }
+
+    #[tokio::test]
+    async fn ファイルがなければ空のデータとして読める() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let store = store_in(&dir);
+
+        let count = store
+            .read(|data| Ok(data.users.len()))
+            .await
+            .expect("読み込みが成功する");
+
+        assert_eq!(count, 0);
+    }
+
+    #[tokio::test]
+    async fn 書き込んだ内容は次の読み込みで取得できる() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let store = store_in(&dir);
+        let user_id = UserId::new();
+
+        store
+            .write(move |data| {
+                data.tokens.push(TokenRecord {
+                    token: "test-token".to_string(),
+                    user_id,
+                    expires_at: Utc::now(),
+                });
+                Ok(())
+            })
+            .await
+            .expect("書き込みが成功する");
+
+        let tokens = store
+            .read(|data| Ok(data.tokens.len()))
+            .await
+            .expect("読み込みが成功する");
+        assert_eq!(tokens, 1);
+        assert!(!dir.path().join("data").join("todo.json.tmp").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn データファイルは所有者だけが読める() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let store = store_in(&dir);
+        let path = dir.path().join("data").join("todo.json");
+        // 以前のバージョンが作った、他人からも読めるファイルを置き換える
+        fs::create_dir_all(path.parent().expect("親ディレクトリ")).expect("作成できる");
+        fs::write(&path, "{}").expect("書き込みが成功する");
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).expect("権限を変えられる");
+
+        store.write(|_| Ok(())).await.expect("書き込みが成功する");
+
+        let mode = fs::metadata(&path)
+            .expect("メタデータ")
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[tokio::test]
+    async fn 書き込み処理が失敗したら保存しない() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let store = store_in(&dir);
+        let user_id = UserId::new();
+
+        let result: AppResult<()> = store
+            .write(move |data| {
+                data.tokens.push(TokenRecord {
+                    token: "test-token".to_string(),
+                    user_id,
+                    expires_at: Utc::now(),
+                });
+                Err(AppError::UnprocessableEntity("abort".into()))
+            })
+            .await;
+        assert!(result.is_err());
+
+        let tokens = store
+            .read(|data| Ok(data.tokens.len()))
+            .await
+            .expect("読み込みが成功する");
+        assert_eq!(tokens, 0);
+    }
+}
@@ -0,0 +1,469 @@
+use chrono::{DateTime, Utc};
+use kernel::model::{
+    auth::{AccessTokenId, ClientInfo, Session, UserCredential},
+    completion::{Completion, CompletionTodo},
+    id::{CompletionId, PersonalAccessTokenId, ProjectId, SessionId, TagId, TodoId, UserId},
+    personal_access_token::{PersonalAccessToken, TokenScope},
+    project::{Project, ProjectProgress},
+    tag::Tag,
+    todo::{Priority, Todo, TodoSort, position::Position, recurrence::Recurrence},
+    user::{User, UserSort},
+};
+use serde::{Deserialize, Serialize};
+use shared::error::AppResult;
+
+use crate::{digest::sha256_hex, file::list::SortValue};
+
+// 入れ替え済みのリフレッシュトークンは、再利用の検出に使う直近のものだけを系列に残す
+pub(crate) const MAX_USED_TOKEN_DIGESTS: usize = 32;
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct FileData {
+    #[serde(default)]
+    pub users: Vec<UserRecord>,
+    #[serde(default)]
+    pub todos: Vec<TodoRecord>,
+    #[serde(default)]
+    pub completions: Vec<CompletionRecord>,
+    #[serde(default)]
+    pub tokens: Vec<TokenRecord>,
+    #[serde(default)]
+    pub tags: Vec<TagRecord>,
+    #[serde(default)]
+    pub todo_tags: Vec<TodoTagRecord>,
+    #[serde(default)]
+    pub projects: Vec<ProjectRecord>,
+    #[serde(default)]
+    pub dependencies: Vec<DependencyRecord>,
+    #[serde(default)]
+    pub refresh_families: Vec<RefreshFamilyRecord>,
+    #[serde(default)]
+    pub personal_access_tokens: Vec<PersonalAccessTokenRecord>,
+}
+
+impl FileData {
+    pub fn tag_ids_of(&self, todo_id: TodoId) -> Vec<TagId> {
+        self.todo_tags
+            .iter()
+            .filter(|link| link.todo_id == todo_id)
+            .map(|link| link.tag_id)
+            .collect()
+    }
+
+    // プロジェクトの指定がないか、指定したプロジェクトが user_id のものであれば true
+    pub fn owns_project(&self, project_id: Option<ProjectId>, user_id: UserId) -> bool {
+        project_id.is_none_or(|project_id| {
+            self.projects
+                .iter()
+                .any(|project| project.id == project_id && project.user_id == user_id)
+        })
+    }
+
+    // 親の指定がないか、指定した親の todo が user_id のものであれば true
+    pub fn owns_parent(&self, parent_id: Option<TodoId>, user_id: UserId) -> bool {
+        parent_id.is_none_or(|parent_id| {
+            self.todos
+                .iter()
+                .any(|todo| todo.id == parent_id && todo.user_id == user_id)
+        })
+    }
+
+    // 未完了の blocker がひとつでもあれば true
+    pub fn is_blocked(&self, todo_id: TodoId) -> bool {
+        self.dependencies
+            .iter()
+            .filter(|dependency| dependency.todo_id == todo_id)
+            .any(|dependency| {
+                self.todos
+                    .iter()
+                    .any(|todo| todo.id == dependency.blocker_id && !todo.completed)
+            })
+    }
+
+    // from から blocker を辿って to に行き着けば true
+    pub fn blocks_transitively(&self, from: TodoId, to: TodoId) -> bool {
+        let mut visited = vec![from];
+        let mut next = 0;
+        while next < visited.len() {
+            let todo_id = visited[next];
+            if todo_id == to {
+                return true;
+            }
+            let blockers: Vec<TodoId> = self
+                .dependencies
+                .iter()
+                .filter(|dependency| {
+                    dependency.todo_id == todo_id && !visited.contains(&dependency.blocker_id)
+                })
+                .map(|dependency| dependency.blocker_id)
+                .collect();
+            visited.extend(blockers);
+            next += 1;
+        }
+        false
+    }
+
+    // user_id の todo の末尾に追加するときのキー
+    pub fn append_position(&self, user_id: UserId) -> AppResult<Position> {
+        let last = self
+            .todos
+            .iter()
+            .filter(|todo| todo.user_id == user_id)
+            .filter_map(|todo| todo.position.as_ref())
+            .max();
+        Position::between(last, None)
+    }
+
+    // ダイジェストを保存する前のファイルの系列を読み込んだら、トークンをダイジェストに置き換える
+    pub fn hash_legacy_refresh_tokens(&mut self) {
+        for family in &mut self.refresh_families {
+            family.hash_legacy_tokens();
+        }
+    }
+
+    // 手動の並び順が入る前のファイルの todo に、ユーザごとに作成順でキーを振る
+    pub fn assign_missing_positions(&mut self) -> AppResult<()> {
+        let mut missing: Vec<(DateTime<Utc>, TodoId)> = self
+            .todos
+            .iter()
+            .filter(|todo| todo.position.is_none())
+            .map(|todo| (todo.created_at, todo.id))
+            .collect();
+        missing.sort_by_key(|(created_at, id)| (*created_at, id.raw()));
+        for (_, id) in missing {
+            let Some(index) = self.todos.iter().position(|todo| todo.id == id) else {
+                continue;
+            };
+            let position = self.append_position(self.todos[index].user_id)?;
+            self.todos[index].position = Some(position);
+        }
+        Ok(())
+    }
+
+    pub fn project_of(&self, record: &ProjectRecord) -> Project {
+        let mut progress = ProjectProgress::default();
+        for todo in self
+            .todos
+            .iter()
+            .filter(|todo| todo.project_id == Some(record.id))
+        {
+            progress.total += 1;
+            progress.completed += i64::from(todo.completed);
+        }
+        Project {
+            id: record.id,
+            user_id: record.user_id,
+            name: record.name.clone(),
+            colour: record.colour.clone(),
+            archived: record.archived,
+            progress,
+            created_at: record.created_at,
+            updated_at: record.updated_at,
+        }
+    }
+
+    // roots とその子孫の ID を、親から子の順に返す
+    pub fn with_descendants(&self, roots: Vec<TodoId>) -> Vec<TodoId> {
+        let mut ids = roots;
+        let mut next = 0;
+        while next < ids.len() {
+            let parent_id = ids[next];
+            let children: Vec<TodoId> = self
+                .todos
+                .iter()
+                .filter(|todo| todo.parent_id == Some(parent_id) && !ids.contains(&todo.id))
+                .map(|todo| todo.id)
+                .collect();
+            ids.extend(children);
+            next += 1;
+        }
+        ids
+    }
+
+    // Postgres 側の ON DELETE CASCADE に合わせて、子孫の todo と紐づくデータも削除する
+    pub fn remove_todos(&mut self, remove: impl Fn(&TodoRecord) -> bool) {
+        let roots = self
+            .todos
+            .iter()
+            .filter(|todo| remove(todo))
+            .map(|todo| todo.id)
+            .collect();
+        let removed = self.with_descendants(roots);
+        self.todos.retain(|todo| !removed.contains(&todo.id));
+        self.completions
+            .retain(|completion| !removed.contains(&completion.todo_id));
+        self.todo_tags
+            .retain(|link| !removed.contains(&link.todo_id));
+        self.dependencies.retain(|dependency| {
+            !removed.contains(&dependency.todo_id) && !removed.contains(&dependency.blocker_id)
+        });
+    }
+
+    pub fn find_completion(&self, record: &CompletionRecord) -> Option<Completion> {
+        let todo = self.todos.iter().find(|todo| todo.id == record.todo_id)?;
+        Some(Completion {
+            id: record.id,
+            todo: CompletionTodo {
+                id: todo.id,
+                title: todo.title.clone(),
+            },
+            completed_at: record.completed_at,
+            reopened_at: record.reopened_at,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserRecord {
+    pub id: UserId,
+    pub name: String,
+    pub email: String,
+    pub password_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UserRecord {
+    pub fn sort_value(&self, sort: UserSort) -> SortValue {
+        match sort {
+            UserSort::CreatedAt => SortValue::Timestamp(self.created_at),
+            UserSort::Name => SortValue::Text(self.name.clone()),
+            UserSort::Email => SortValue::Text(self.email.clone()),
+        }
+    }
+}
+
+impl From<&UserRecord> for User {
+    fn from(value: &UserRecord) -> Self {
+        User {
+            id: value.id,
+            name: value.name.clone(),
+            email: value.email.clone(),
+        }
+    }
+}
+
+impl From<&UserRecord> for UserCredential {
+    fn from(value: &UserRecord) -> Self {
+        UserCredential {
+            id: value.id,
+            email: value.email.clone(),
+            password_hash: value.password_hash.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TodoRecord {
+    pub id: TodoId,
+    pub user_id: UserId,
+    #[serde(default)]
+    pub project_id: Option<ProjectId>,
+    #[serde(default)]
+    pub parent_id: Option<TodoId>,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub priority: Priority,
+    pub completed: bool,
+    pub due_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    // 手動の並び順が入る前のファイルでは None。読み込むときに振り直すので、それ以外では常に Some
+    #[serde(default)]
+    pub position: Option<Position>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TodoRecord {
+    pub fn sort_value(&self, sort: TodoSort) -> SortValue {
+        match sort {
+            TodoSort::CreatedAt => SortValue::Timestamp(self.created_at),
+            TodoSort::UpdatedAt => SortValue::Timestamp(self.updated_at),
+            TodoSort::Title => SortValue::Text(self.title.clone()),
+            TodoSort::Manual => SortValue::Text(
+                self.position
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+}
+
+impl From<&TodoRecord> for Todo {
+    fn from(value: &TodoRecord) -> Self {
+        Todo {
+            id: value.id,
+            user_id: value.user_id,
+            project_id: value.project_id,
+            parent_id: value.parent_id,
+            title: value.title.clone(),
+            description: value.description.clone(),
+            priority: value.priority,
+            completed: value.completed,
+            due_at: value.due_at,
+            recurrence: value.recurrence.clone(),
+            position: value.position.clone().unwrap_or_else(Position::first),
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CompletionRecord {
+    pub id: CompletionId,
+    pub todo_id: TodoId,
+    pub user_id: UserId,
+    pub completed_at: DateTime<Utc>,
+    pub reopened_at: Option<DateTime<Utc>>,
+}
+
+// 許可リストに載っているアクセストークン。token には JWT の jti を保存する
+#[derive(Serialize, Deserialize)]
+pub struct TokenRecord {
+    pub token: String,
+    pub user_id: UserId,
+    pub expires_at: DateTime<Utc>,
+}
+
+// ログインごとのリフレッシュトークンの系列。トークンは SHA-256 のダイジェストだけを保存し、
+// 入れ替え済みのものは再利用の検出のために残す。
+// セッションとしての ID と端末の情報は後から加えたため、古いファイルでは省略できる
+#[derive(Serialize, Deserialize)]
+pub struct RefreshFamilyRecord {
+    #[serde(default)]
+    pub id: SessionId,
+    pub user_id: UserId,
+    #[serde(default)]
+    pub refresh_token_digest: String,
+    pub access_token_id: String,
+    #[serde(default)]
+    pub used_token_digests: Vec<String>,
+    // ダイジェストを保存する前のファイルは、トークンをそのまま持っている
+    #[serde(default, rename = "refresh_token", skip_serializing)]
+    pub legacy_refresh_token: Option<String>,
+    #[serde(default, rename = "used_tokens", skip_serializing)]
+    pub legacy_used_tokens: Vec<String>,
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    #[serde(default)]
+    pub ip_address: Option<String>,
+    #[serde(default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
+    #[serde(default = "Utc::now")]
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl RefreshFamilyRecord {
+    // 現在のトークンを入れ替え済みとして残し、新しいトークンのダイジェストに置き換える
+    pub fn rotate(&mut self, refresh_token: &str) {
+        let used = std::mem::replace(&mut self.refresh_token_digest, sha256_hex(refresh_token));
+        self.used_token_digests.push(used);
+        let overflow = self
+            .used_token_digests
+            .len()
+            .saturating_sub(MAX_USED_TOKEN_DIGESTS);
+        self.used_token_digests.drain(..overflow);
+    }
+
+    // トークンをそのまま持っている古い系列を、ダイジェストに置き換える
+    fn hash_legacy_tokens(&mut self) {
+        if let Some(token) = self.legacy_refresh_token.take() {
+            self.refresh_token_digest = sha256_hex(&token);
+        }
+        let used = std::mem::take(&mut self.legacy_used_tokens);
+        self.used_token_digests
+            .extend(used.iter().map(|token| sha256_hex(token)));
+    }
+}
+
+impl From<&RefreshFamilyRecord> for Session {
+    fn from(record: &RefreshFamilyRecord) -> Self {
+        Session {
+            id: record.id,
+            access_token_id: AccessTokenId(record.access_token_id.clone()),
+            client: ClientInfo {
+                user_agent: record.user_agent.clone(),
+                ip_address: record.ip_address.clone(),
+            },
+            created_at: record.created_at,
+            last_used_at: record.last_used_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TagRecord {
+    pub id: TagId,
+    pub user_id: UserId,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&TagRecord> for Tag {
+    fn from(value: &TagRecord) -> Self {
+        Tag {
+            id: value.id,
+            user_id: value.user_id,
+            name: value.name.clone(),
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+// token_hash にはシークレットの SHA-256 だけを保存する
+#[derive(Serialize, Deserialize)]
+pub struct PersonalAccessTokenRecord {
+    pub id: PersonalAccessTokenId,
+    pub user_id: UserId,
+    pub name: String,
+    pub token_hash: String,
+    pub scopes: Vec<TokenScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&PersonalAccessTokenRecord> for PersonalAccessToken {
+    fn from(value: &PersonalAccessTokenRecord) -> Self {
+        PersonalAccessToken {
+            id: value.id,
+            user_id: value.user_id,
+            name: value.name.clone(),
+            scopes: value.scopes.clone(),
+            expires_at: value.expires_at,
+            last_used_at: value.last_used_at,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TodoTagRecord {
+    pub todo_id: TodoId,
+    pub tag_id: TagId,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DependencyRecord {
+    pub todo_id: TodoId,
+    pub blocker_id: TodoId,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ProjectRecord {
+    pub id: ProjectId,
+    pub user_id: UserId,
+    pub name: String,
+    pub colour: String,
+    pub archived: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,596 @@
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use derive_new::new;
+use kernel::{
+    model::{
+        auth::{
+            AccessTokenId, Session, UserCredential,
+            event::{DeleteSession, RotateToken, StoreToken},
+        },
+        id::{SessionId, UserId},
+    },
+    repository::auth::AuthRepository,
+};
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    digest::sha256_hex,
+    file::{
+        FileStore,
+        model::{FileData, RefreshFamilyRecord, TokenRecord},
+    },
+};
+
+#[derive(new)]
+pub struct FileAuthRepositoryImpl {
+    store: FileStore,
+    ttl: u64,
+    refresh_ttl: u64,
+}
+
+// Redis の TTL の代わりに、書き込みのついでに期限切れのトークンを掃除する
+fn remove_expired(data: &mut FileData) {
+    let now = Utc::now();
+    data.tokens.retain(|record| record.expires_at > now);
+    data.refresh_families
+        .retain(|record| record.expires_at > now);
+}
+
+#[async_trait]
+impl AuthRepository for FileAuthRepositoryImpl {
+    async fn find_by_email(&self, email: String) -> AppResult<Option<UserCredential>> {
+        self.store
+            .read(move |data| {
+                Ok(data
+                    .users
+                    .iter()
+                    .find(|user| user.email == email)
+                    .map(UserCredential::from))
+            })
+            .await
+    }
+
+    async fn fetch_user_id_from_token(
+        &self,
+        access_token_id: &AccessTokenId,
+    ) -> AppResult<Option<UserId>> {
+        let token = access_token_id.0.clone();
+        self.store
+            .read(move |data| {
+                let now = Utc::now();
+                Ok(data
+                    .tokens
+                    .iter()
+                    .find(|record| record.token == token && record.expires_at > now)
+                    .map(|record| record.user_id))
+            })
+            .await
+    }
+
+    async fn store_token(&self, event: StoreToken) -> AppResult<()> {
+        let now = Utc::now();
+        let expires_at = now + Duration::seconds(self.ttl as i64);
+        let refresh_expires_at = now + Duration::seconds(self.refresh_ttl as i64);
+        self.store
+            .write(move |data| {
+                remove_expired(data);
+                data.tokens.push(TokenRecord {
+                    token: event.access_token_id.0.clone(),
+                    user_id: event.user_id,
+                    expires_at,
+                });
+                data.refresh_families.push(RefreshFamilyRecord {
+                    id: SessionId::new(),
+                    user_id: event.user_id,
+                    refresh_token_digest: sha256_hex(&event.refresh_token.0),
+                    access_token_id: event.access_token_id.0,
+                    used_token_digests: Vec::new(),
+                    legacy_refresh_token: None,
+                    legacy_used_tokens: Vec::new(),
+                    user_agent: event.client.user_agent,
+                    ip_address: event.client.ip_address,
+                    created_at: now,
+                    last_used_at: now,
+                    expires_at: refresh_expires_at,
+                });
+                Ok(())
+            })
+            .await
+    }
+
+    async fn rotate_token(&self, event: RotateToken) -> AppResult<UserId> {
+        let now = Utc::now();
+        let expires_at = now + Duration::seconds(self.ttl as i64);
+        let refresh_expires_at = now + Duration::seconds(self.refresh_ttl as i64);
+        let rotated = self
+            .store
+            .write(move |data| {
+                remove_expired(data);
+                let digest = sha256_hex(&event.refresh_token.0);
+                let Some(index) = data.refresh_families.iter().position(|record| {
+                    record.refresh_token_digest == digest
+                        || record.used_token_digests.contains(&digest)
+                }) else {
+                    return Err(AppError::Unauthorized("Invalid refresh token".into()));
+                };
+                let mut family = data.refresh_families.remove(index);
+                data.tokens
+                    .retain(|record| record.token != family.access_token_id);
+                if family.refresh_token_digest != digest {
+                    // 入れ替え済みのトークンが再び使われたら漏洩とみなし、系列ごと失効させる。
+                    // 失効を保存するため、エラーはファイルへの書き込み後に返す
+                    return Ok(None);
+                }
+                family.rotate(&event.new_refresh_token.0);
+                family.access_token_id = event.access_token_id.0.clone();
+                family.user_agent = event.client.user_agent;
+                family.ip_address = event.client.ip_address;
+                family.last_used_at = now;
+                family.expires_at = refresh_expires_at;
+                let user_id = family.user_id;
+                data.refresh_families.push(family);
+                data.tokens.push(TokenRecord {
+                    token: event.access_token_id.0,
+                    user_id,
+                    expires_at,
+                });
+                Ok(Some(user_id))
+            })
+            .await?;
+        rotated
+            .ok_or_else(|| AppError::Unauthorized("The refresh token has already been used".into()))
+    }
+
+    async fn delete_token(&self, access_token_id: AccessTokenId) -> AppResult<()> {
+        self.store
+            .write(move |data| {
+                let before = data.tokens.len();
+                data.tokens
+                    .retain(|record| record.token != access_token_id.0);
+                let deleted = before != data.tokens.len();
+                data.refresh_families
+                    .retain(|record| record.access_token_id != access_token_id.0);
+                remove_expired(data);
+                if !deleted {
+                    return Err(AppError::Unauthorized("Invalid token".into()));
+                }
+                Ok(())
+            })
+            .await
+    }
+
+    async fn find_sessions(&self, user_id: UserId) -> AppResult<Vec<Session>> {
+        self.store
+            .read(move |data| {
+                let now = Utc::now();
+                let mut sessions: Vec<Session> = data
+                    .refresh_families
+                    .iter()
+                    .filter(|record| record.user_id == user_id && record.expires_at > now)
+                    .map(Session::from)
+                    .collect();
+                sessions.sort_by(|a, b| b.last_used_at.cmp(&a.last_used_at));
+                Ok(sessions)
+            })
+            .await
+    }
+
+    async fn delete_session(&self, event: DeleteSession) -> AppResult<()> {
+        self.store
+            .write(move |data| {
+                remove_expired(data);
+                let Some(index) = data
+                    .refresh_families
+                    .iter()
+                    .position(|record| record.id == event.id && record.user_id == event.user_id)
+                else {
+                    return Err(AppError::EntityNotFoundError(
+                        "The session was not found".into(),
+                    ));
+                };
+                let family = data.refresh_families.remove(index);
+                data.tokens
+                    .retain(|record| record.token != family.access_token_id);
+                Ok(())
+            })
+            .await
+    }
+
+    async fn delete_other_tokens(&self, user_id: UserId, keep: &AccessTokenId) -> AppResult<()> {
+        let keep = keep.0.clone();
+        self.store
+            .write(move |data| {
+                data.tokens
+                    .retain(|record| record.user_id != user_id || record.token == keep);
+                data.refresh_families
+                    .retain(|record| record.user_id != user_id || record.access_token_id == keep);
+                Ok(())
+            })
+            .await
+    }
+
+    fn token_ttl(&self) -> u64 {
+        self.ttl
+    }
+
+    fn refresh_token_ttl(&self) -> u64 {
+        self.refresh_ttl
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::{model::MAX_USED_TOKEN_DIGESTS, repository::user::FileUserRepositoryImpl};
+    use kernel::model::auth::{ClientInfo, RefreshToken};
+    use kernel::{model::user::event::CreateUser, repository::user::UserRepository};
+    use shared::config::FileConfig;
+
+    fn store_in(dir: &tempfile::TempDir) -> FileStore {
+        FileStore::new(&FileConfig {
+            path: dir.path().join("todo.json"),
+        })
+    }
+
+    async fn create_user(store: &FileStore) -> UserId {
+        FileUserRepositoryImpl::new(store.clone())
+            .create(CreateUser {
+                name: "Alice".to_string(),
+                email: "alice@example.com".to_string(),
+                password: "password123".to_string(),
+            })
+            .await
+            .expect("ユーザ作成が成功する")
+            .id
+    }
+
+    #[tokio::test]
+    async fn 認証情報はメール指定で取得できる() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let store = store_in(&dir);
+        let user_id = create_user(&store).await;
+        let repo = FileAuthRepositoryImpl::new(store, 3600, 86400);
+
+        let credential = repo
+            .find_by_email("alice@example.com".to_string())
+            .await
+            .expect("取得が成功する")
+            .expect("認証情報が存在する");
+
+        assert_eq!(credential.id, user_id);
+        assert!(credential.verify_password("password123").expect("検証"));
+    }
+
+    #[tokio::test]
+    async fn 保存したトークンからユーザを引けてログアウトで無効になる() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let store = store_in(&dir);
+        let user_id = create_user(&store).await;
+        let repo = FileAuthRepositoryImpl::new(store, 3600, 86400);
+
+        let token = AccessTokenId::generate();
+        repo.store_token(StoreToken {
+            user_id,
+            access_token_id: token.clone(),
+            refresh_token: RefreshToken::generate(),
+            client: ClientInfo::default(),
+        })
+        .await
+        .expect("保存が成功する");
+        let found = repo
+            .fetch_user_id_from_token(&token)
+            .await
+            .expect("取得が成功する");
+        assert_eq!(found, Some(user_id));
+
+        repo.delete_token(token.clone())
+            .await
+            .expect("削除が成功する");
+        let found = repo
+            .fetch_user_id_from_token(&token)
+            .await
+            .expect("取得が成功する");
+        assert_eq!(found, None);
+
+        let err = repo
+            .delete_token(token)
+            .await
+            .expect_err("削除済みトークンは失敗する");
+        assert!(matches!(err, AppError::Unauthorized(_)));
+    }
+
+    #[tokio::test]
+    async fn 期限切れのトークンは無効になる() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let store = store_in(&dir);
+        let user_id = create_user(&store).await;
+        let repo = FileAuthRepositoryImpl::new(store, 0, 86400);
+
+        let token = AccessTokenId::generate();
+        repo.store_token(StoreToken {
+            user_id,
+            access_token_id: token.clone(),
+            refresh_token: RefreshToken::generate(),
+            client: ClientInfo::default(),
+        })
+        .await
+        .expect("保存が成功する");
+
+        let found = repo
+            .fetch_user_id_from_token(&token)
+            .await
+            .expect("取得が成功する");
+        assert_eq!(found, None);
+    }
+
+    #[tokio::test]
+    async fn 他のトークンだけが削除される() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let store = store_in(&dir);
+        let user_id = create_user(&store).await;
+        let repo = FileAuthRepositoryImpl::new(store, 3600, 86400);
+
+        let keep = AccessTokenId::generate();
+        repo.store_token(StoreToken {
+            user_id,
+            access_token_id: keep.clone(),
+            refresh_token: RefreshToken::generate(),
+            client: ClientInfo::default(),
+        })
+        .await
+        .expect("保存が成功する");
+        let other = AccessTokenId::generate();
+        repo.store_token(StoreToken {
+            user_id,
+            access_token_id: other.clone(),
+            refresh_token: RefreshToken::generate(),
+            client: ClientInfo::default(),
+        })
+        .await
+        .expect("保存が成功する");
+
+        repo.delete_other_tokens(user_id, &keep)
+            .await
+            .expect("削除が成功する");
+
+        assert_eq!(
+            repo.fetch_user_id_from_token(&keep).await.expect("取得"),
+            Some(user_id)
+        );
+        assert_eq!(
+            repo.fetch_user_id_from_token(&other).await.expect("取得"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn 使用済みのリフレッシュトークンを使うと系列ごと失効する() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let store = store_in(&dir);
+        let user_id = create_user(&store).await;
+        let repo = FileAuthRepositoryImpl::new(store, 3600, 86400);
+
+        let old_refresh = RefreshToken::generate();
+        let old_access = AccessTokenId::generate();
+        repo.store_token(StoreToken {
+            user_id,
+            access_token_id: old_access.clone(),
+            refresh_token: old_refresh.clone(),
+            client: ClientInfo::default(),
+        })
+        .await
+        .expect("保存が成功する");
+        let new_access = AccessTokenId::generate();
+        let new_refresh = RefreshToken::generate();
+        let rotated = repo
+            .rotate_token(RotateToken {
+                refresh_token: old_refresh.clone(),
+                access_token_id: new_access.clone(),
+                new_refresh_token: new_refresh.clone(),
+                client: ClientInfo::default(),
+            })
+            .await
+            .expect("入れ替えが成功する");
+        assert_eq!(rotated, user_id);
+        assert_eq!(
+            repo.fetch_user_id_from_token(&old_access)
+                .await
+                .expect("取得"),
+            None
+        );
+        assert_eq!(
+            repo.fetch_user_id_from_token(&new_access)
+                .await
+                .expect("取得"),
+            Some(user_id)
+        );
+
+        let err = repo
+            .rotate_token(RotateToken {
+                refresh_token: old_refresh,
+                access_token_id: AccessTokenId::generate(),
+                new_refresh_token: RefreshToken::generate(),
+                client: ClientInfo::default(),
+            })
+            .await
+            .expect_err("使用済みのトークンは失敗する");
+        assert!(matches!(err, AppError::Unauthorized(_)));
+
+        assert_eq!(
+            repo.fetch_user_id_from_token(&new_access)
+                .await
+                .expect("取得"),
+            None
+        );
+        let err = repo
+            .rotate_token(RotateToken {
+                refresh_token: new_refresh,
+                access_token_id: AccessTokenId::generate(),
+                new_refresh_token: RefreshToken::generate(),
+                client: ClientInfo::default(),
+            })
+            .await
+            .expect_err("失効した系列のトークンは失敗する");
+        assert!(matches!(err, AppError::Unauthorized(_)));
+    }
+
+    #[tokio::test]
+    async fn リフレッシュトークンはダイジェストだけを保存し入れ替え済みの数を制限する() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let store = store_in(&dir);
+        let user_id = create_user(&store).await;
+        let repo = FileAuthRepositoryImpl::new(store.clone(), 3600, 86400);
+
+        let mut refresh = RefreshToken::generate();
+        repo.store_token(StoreToken {
+            user_id,
+            access_token_id: AccessTokenId::generate(),
+            refresh_token: refresh.clone(),
+            client: ClientInfo::default(),
+        })
+        .await
+        .expect("保存が成功する");
+        let first = refresh.clone();
+        for _ in 0..MAX_USED_TOKEN_DIGESTS + 1 {
+            let next = RefreshToken::generate();
+            repo.rotate_token(RotateToken {
+                refresh_token: refresh,
+                access_token_id: AccessTokenId::generate(),
+                new_refresh_token: next.clone(),
+                client: ClientInfo::default(),
+            })
+            .await
+            .expect("入れ替えが成功する");
+            refresh = next;
+        }
+
+        let content = std::fs::read_to_string(dir.path().join("todo.json")).expect("読み込み");
+        assert!(!content.contains(&first.0));
+        assert!(!content.contains(&refresh.0));
+        assert!(content.contains(&sha256_hex(&refresh.0)));
+        let used = store
+            .read(|data| Ok(data.refresh_families[0].used_token_digests.clone()))
+            .await
+            .expect("取得");
+        assert_eq!(used.len(), MAX_USED_TOKEN_DIGESTS);
+        assert!(!used.contains(&sha256_hex(&first.0)));
+    }
+
+    #[tokio::test]
+    async fn トークンをそのまま保存した古い系列も読み込んでダイジェストに置き換える() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let store = store_in(&dir);
+        let user_id = create_user(&store).await;
+        let repo = FileAuthRepositoryImpl::new(store, 3600, 86400);
+        let used = RefreshToken::generate();
+        let current = RefreshToken::generate();
+        repo.store_token(StoreToken {
+            user_id,
+            access_token_id: AccessTokenId::generate(),
+            refresh_token: current.clone(),
+            client: ClientInfo::default(),
+        })
+        .await
+        .expect("保存が成功する");
+
+        let path = dir.path().join("todo.json");
+        let mut data: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).expect("読み込み")).expect("JSON");
+        let family = data["refresh_families"][0]
+            .as_object_mut()
+            .expect("系列がある");
+        family.remove("refresh_token_digest");
+        family.remove("used_token_digests");
+        family.insert("refresh_token".into(), current.0.clone().into());
+        family.insert("used_tokens".into(), serde_json::json!([used.0]));
+        std::fs::write(&path, data.to_string()).expect("書き込み");
+
+        let next = RefreshToken::generate();
+        repo.rotate_token(RotateToken {
+            refresh_token: current.clone(),
+            access_token_id: AccessTokenId::generate(),
+            new_refresh_token: next.clone(),
+            client: ClientInfo::default(),
+        })
+        .await
+        .expect("古い系列のトークンで入れ替えられる");
+        let content = std::fs::read_to_string(&path).expect("読み込み");
+        assert!(!content.contains(&current.0));
+        assert!(!content.contains(&used.0));
+
+        // 古い系列で入れ替え済みだったトークンも再利用として検出する
+        let err = repo
+            .rotate_token(RotateToken {
+                refresh_token: used,
+                access_token_id: AccessTokenId::generate(),
+                new_refresh_token: RefreshToken::generate(),
+                client: ClientInfo::default(),
+            })
+            .await
+            .expect_err("使用済みのトークンは失敗する");
+        assert!(matches!(err, AppError::Unauthorized(_)));
+        let err = repo
+            .rotate_token(RotateToken {
+                refresh_token: next,
+                access_token_id: AccessTokenId::generate(),
+                new_refresh_token: RefreshToken::generate(),
+                client: ClientInfo::default(),
+            })
+            .await
+            .expect_err("失効した系列のトークンは失敗する");
+        assert!(matches!(err, AppError::Unauthorized(_)));
+    }
+
+    #[tokio::test]
+    async fn セッションは一覧でき個別に失効できる() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let store = store_in(&dir);
+        let user_id = create_user(&store).await;
+        let repo = FileAuthRepositoryImpl::new(store, 3600, 86400);
+
+        let access = AccessTokenId::generate();
+        let client = ClientInfo {
+            user_agent: Some("todo-cli/0.1.0".to_string()),
+            ip_address: None,
+        };
+        repo.store_token(StoreToken {
+            user_id,
+            access_token_id: access.clone(),
+            refresh_token: RefreshToken::generate(),
+            client: client.clone(),
+        })
+        .await
+        .expect("保存が成功する");
+
+        let sessions = repo.find_sessions(user_id).await.expect("取得が成功する");
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].access_token_id, access);
+        assert_eq!(sessions[0].client, client);
+
+        let err = repo
+            .delete_session(DeleteSession {
+                id: sessions[0].id,
+                user_id: UserId::new(),
+            })
+            .await
+            .expect_err("他のユーザのセッションは失効できない");
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+
+        repo.delete_session(DeleteSession {
+            id: sessions[0].id,
+            user_id,
+        })
+        .await
+        .expect("失効が成功する");
+        assert_eq!(
+            repo.fetch_user_id_from_token(&access).await.expect("取得"),
+            None
+        );
+        assert!(
+            repo.find_sessions(user_id)
+                .await
+                .expect("取得が成功する")
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,548 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use derive_new::new;
+use kernel::{
+    model::{
+        completion::{
+            Completion,
+            event::{CreateCompletion, UpdateReopened},
+        },
+        id::{CompletionId, TodoId, UserId},
+    },
+    repository::completion::CompletionRepository,
+};
+use shared::error::{AppError, AppResult};
+
+use crate::file::{
+    FileStore,
+    model::{CompletionRecord, TodoRecord, TodoTagRecord},
+};
+
+#[derive(new)]
+pub struct FileCompletionRepositoryImpl {
+    store: FileStore,
+}
+
+#[async_trait]
+impl CompletionRepository for FileCompletionRepositoryImpl {
+    async fn create(&self, event: CreateCompletion) -> AppResult<Completion> {
+        self.store
+            .write(move |data| {
+                let blocked = !event.force && data.is_blocked(event.todo_id);
+                // 次の回を作る場合は、手動の並び順で末尾に置く
+                let next_position = data.append_position(event.user_id)?;
+                let todo = data
+                    .todos
+                    .iter_mut()
+                    .find(|todo| todo.id == event.todo_id && todo.user_id == event.user_id)
+                    .ok_or_else(|| {
+                        AppError::EntityNotFoundError("The todo was not found".into())
+                    })?;
+
+                if todo.completed {
+                    return Err(AppError::UnprocessableEntity(
+                        "The todo has already been completed".into(),
+                    ));
+                }
+                if blocked {
+                    return Err(AppError::StateConflict(
+                        "The todo is blocked by open todos".into(),
+                    ));
+                }
+                todo.completed = true;
+
+                let completed_at = Utc::now();
+                let next = todo
+                    .recurrence
+                    .as_ref()
+                    .and_then(|rule| rule.next_occurrence(todo.due_at, completed_at))
+                    .map(|(due_at, recurrence)| TodoRecord {
+                        id: TodoId::new(),
+                        user_id: todo.user_id,
+                        project_id: todo.project_id,
+                        parent_id: todo.parent_id,
+                        title: todo.title.clone(),
+                        description: todo.description.clone(),
+                        priority: todo.priority,
+                        completed: false,
+                        due_at: Some(due_at),
+                        recurrence: Some(recurrence),
+                        position: Some(next_position),
+                        created_at: completed_at,
+                        updated_at: completed_at,
+                    });
+                if next.is_some() {
+                    // 繰り返しの規則は次の回へ移す。再開してから完了し直しても次の回を重ねて作らない
+                    todo.recurrence = None;
+                }
+
+                if event.include_subtasks {
+                    let descendants = data.with_descendants(vec![event.todo_id]);
+                    for subtask in data
+                        .todos
+                        .iter_mut()
+                        .filter(|todo| todo.id != event.todo_id && !todo.completed)
+                        .filter(|todo| descendants.contains(&todo.id))
+                    {
+                        subtask.completed = true;
+                        data.completions.push(CompletionRecord {
+                            id: CompletionId::new(),
+                            todo_id: subtask.id,
+                            user_id: event.user_id,
+                            completed_at,
+                            reopened_at: None,
+                        });
+                    }
+                }
+
+                if let Some(next) = next {
+                    // 次の回は内容とタグを引き継いだ新しい todo として作る
+                    let tag_ids = data.tag_ids_of(event.todo_id);
+                    data.todo_tags
+                        .extend(tag_ids.into_iter().map(|tag_id| TodoTagRecord {
+                            todo_id: next.id,
+                            tag_id,
+                        }));
+                    data.todos.push(next);
+                }
+
+                let record = CompletionRecord {
+                    id: CompletionId::new(),
+                    todo_id: event.todo_id,
+                    user_id: event.user_id,
+                    completed_at,
+                    reopened_at: None,
+                };
+                let completion = data.find_completion(&record);
+                data.completions.push(record);
+                completion
+                    .ok_or_else(|| AppError::EntityNotFoundError("The todo was not found".into()))
+            })
+            .await
+    }
+
+    async fn update_reopened(&self, event: UpdateReopened) -> AppResult<()> {
+        self.store
+            .write(move |data| {
+                let completion = data
+                    .completions
+                    .iter_mut()
+                    .find(|completion| {
+                        completion.id == event.completion_id
+                            && completion.todo_id == event.todo_id
+                            && completion.user_id == event.user_id
+                    })
+                    .ok_or_else(|| {
+                        AppError::EntityNotFoundError("The completion was not found".into())
+                    })?;
+
+                if completion.reopened_at.is_some() {
+                    return Err(AppError::UnprocessableEntity(
+                        "The completion has already been reopened".into(),
+                    ));
+                }
+                completion.reopened_at = Some(Utc::now());
+
+                if let Some(todo) = data.todos.iter_mut().find(|todo| todo.id == event.todo_id) {
+                    todo.completed = false;
+                }
+                Ok(())
+            })
+            .await
+    }
+
+    async fn find_completed_all(&self, user_id: UserId) -> AppResult<Vec<Completion>> {
+        self.store
+            .read(move |data| {
+                let mut records: Vec<&CompletionRecord> = data
+                    .completions
+                    .iter()
+                    .filter(|c| c.user_id == user_id && c.reopened_at.is_none())
+                    .collect();
+                records.sort_by(|a, b| b.completed_at.cmp(&a.completed_at));
+                Ok(records
+                    .into_iter()
+                    .filter_map(|record| data.find_completion(record))
+                    .collect())
+            })
+            .await
+    }
+
+    async fn find_history_by_todo_id(
+        &self,
+        todo_id: TodoId,
+        user_id: UserId,
+    ) -> AppResult<Vec<Completion>> {
+        self.store
+            .read(move |data| {
+                let mut records: Vec<&CompletionRecord> = data
+                    .completions
+                    .iter()
+                    .filter(|c| c.todo_id == todo_id && c.user_id == user_id)
+                    .collect();
+                records.sort_by(|a, b| b.completed_at.cmp(&a.completed_at));
+                Ok(records
+                    .into_iter()
+                    .filter_map(|record| data.find_completion(record))
+                    .collect())
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::repository::{
+        dependency::FileDependencyRepositoryImpl, todo::FileTodoRepositoryImpl,
+        user::FileUserRepositoryImpl,
+    };
+    use chrono::Duration;
+    use kernel::{
+        model::{
+            dependency::event::AddDependency,
+            list::ListQuery,
+            todo::{Priority, event::CreateTodo, filter::TodoFilter},
+            user::event::CreateUser,
+        },
+        repository::{
+            dependency::DependencyRepository, todo::TodoRepository, user::UserRepository,
+        },
+    };
+    use shared::config::FileConfig;
+
+    async fn setup(dir: &tempfile::TempDir) -> (FileStore, UserId, TodoId) {
+        let store = FileStore::new(&FileConfig {
+            path: dir.path().join("todo.json"),
+        });
+        let user = FileUserRepositoryImpl::new(store.clone())
+            .create(CreateUser {
+                name: "Alice".to_string(),
+                email: "alice@example.com".to_string(),
+                password: "password123".to_string(),
+            })
+            .await
+            .expect("ユーザ作成が成功する");
+        let todo = FileTodoRepositoryImpl::new(store.clone())
+            .create(CreateTodo {
+                user_id: user.id,
+                project_id: None,
+                parent_id: None,
+                title: "牛乳を買う".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("todo作成が成功する");
+        (store, user.id, todo.id)
+    }
+
+    #[tokio::test]
+    async fn todoを完了すると完了一覧に含まれる() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (store, user_id, todo_id) = setup(&dir).await;
+        let repo = FileCompletionRepositoryImpl::new(store.clone());
+
+        let completion = repo
+            .create(CreateCompletion {
+                todo_id,
+                user_id,
+                include_subtasks: false,
+                force: false,
+            })
+            .await
+            .expect("完了が成功する");
+        assert_eq!(completion.todo.id, todo_id);
+
+        let completed = repo.find_completed_all(user_id).await.expect("一覧取得");
+        assert_eq!(completed.len(), 1);
+
+        let todo = FileTodoRepositoryImpl::new(store)
+            .find_by_id(todo_id, user_id)
+            .await
+            .expect("取得が成功する")
+            .expect("todoが存在する");
+        assert!(todo.completed);
+
+        let err = repo
+            .create(CreateCompletion {
+                todo_id,
+                user_id,
+                include_subtasks: false,
+                force: false,
+            })
+            .await
+            .expect_err("二重完了は失敗する");
+        assert!(matches!(err, AppError::UnprocessableEntity(_)));
+    }
+
+    #[tokio::test]
+    async fn 再オープンすると履歴に残り未完了に戻る() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (store, user_id, todo_id) = setup(&dir).await;
+        let repo = FileCompletionRepositoryImpl::new(store);
+
+        let completion = repo
+            .create(CreateCompletion {
+                todo_id,
+                user_id,
+                include_subtasks: false,
+                force: false,
+            })
+            .await
+            .expect("完了が成功する");
+        repo.update_reopened(UpdateReopened {
+            completion_id: completion.id,
+            todo_id,
+            user_id,
+        })
+        .await
+        .expect("再オープンが成功する");
+        repo.create(CreateCompletion {
+            todo_id,
+            user_id,
+            include_subtasks: false,
+            force: false,
+        })
+        .await
+        .expect("再度の完了が成功する");
+
+        let history = repo
+            .find_history_by_todo_id(todo_id, user_id)
+            .await
+            .expect("履歴取得");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].id, completion.id);
+        assert!(history[1].reopened_at.is_some());
+
+        let err = repo
+            .update_reopened(UpdateReopened {
+                completion_id: completion.id,
+                todo_id,
+                user_id,
+            })
+            .await
+            .expect_err("再オープン済みの完了は再オープンできない");
+        assert!(matches!(err, AppError::UnprocessableEntity(_)));
+    }
+
+    #[tokio::test]
+    async fn 子孫もまとめて完了にできる() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (store, user_id, todo_id) = setup(&dir).await;
+        let todos = FileTodoRepositoryImpl::new(store.clone());
+        let subtask = todos
+            .create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: Some(todo_id),
+                title: "牛乳の銘柄を決める".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("todo作成が成功する");
+
+        FileCompletionRepositoryImpl::new(store)
+            .create(CreateCompletion {
+                todo_id,
+                user_id,
+                include_subtasks: true,
+                force: false,
+            })
+            .await
+            .expect("完了が成功する");
+
+        let subtask = todos
+            .find_by_id(subtask.id, user_id)
+            .await
+            .expect("取得")
+            .expect("todoが存在する");
+        assert!(subtask.completed);
+    }
+
+    #[tokio::test]
+    async fn 繰り返しのtodoを完了すると次の回が作られる() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (store, user_id, _) = setup(&dir).await;
+        let todos = FileTodoRepositoryImpl::new(store.clone());
+        let due_at = Utc::now();
+        let todo = todos
+            .create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: "ゴミ出し".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: Some(due_at),
+                recurrence: Some("FREQ=WEEKLY;COUNT=2".parse().expect("正しい規則")),
+            })
+            .await
+            .expect("todo作成が成功する");
+        let repo = FileCompletionRepositoryImpl::new(store);
+        let open_todos = || async {
+            todos
+                .find_all(
+                    user_id,
+                    TodoFilter {
+                        completed: Some(false),
+                        title: Some("ゴミ出し".to_string()),
+                        ..Default::default()
+                    },
+                    ListQuery::default(),
+                )
+                .await
+                .expect("一覧取得")
+                .items
+        };
+
+        repo.create(CreateCompletion {
+            todo_id: todo.id,
+            user_id,
+            include_subtasks: false,
+            force: false,
+        })
+        .await
+        .expect("完了が成功する");
+
+        let next = open_todos().await;
+        assert_eq!(next.len(), 1);
+        assert_eq!(next[0].due_at, Some(due_at + Duration::weeks(1)));
+
+        repo.create(CreateCompletion {
+            todo_id: next[0].id,
+            user_id,
+            include_subtasks: false,
+            force: false,
+        })
+        .await
+        .expect("完了が成功する");
+        assert!(open_todos().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn 繰り返しのtodoを再オープンして完了し直しても次の回は増えない() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (store, user_id, _) = setup(&dir).await;
+        let todos = FileTodoRepositoryImpl::new(store.clone());
+        let todo = todos
+            .create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: "水やり".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: Some(Utc::now()),
+                recurrence: Some("FREQ=DAILY;COUNT=3".parse().expect("正しい規則")),
+            })
+            .await
+            .expect("todo作成が成功する");
+        let repo = FileCompletionRepositoryImpl::new(store);
+        let complete = || {
+            repo.create(CreateCompletion {
+                todo_id: todo.id,
+                user_id,
+                include_subtasks: false,
+                force: false,
+            })
+        };
+
+        let completion = complete().await.expect("完了が成功する");
+        repo.update_reopened(UpdateReopened {
+            completion_id: completion.id,
+            todo_id: todo.id,
+            user_id,
+        })
+        .await
+        .expect("再オープンが成功する");
+        complete().await.expect("完了し直しが成功する");
+
+        let open = todos
+            .find_all(
+                user_id,
+                TodoFilter {
+                    completed: Some(false),
+                    title: Some("水やり".to_string()),
+                    ..Default::default()
+                },
+                ListQuery::default(),
+            )
+            .await
+            .expect("一覧取得")
+            .items;
+        assert_eq!(open.len(), 1);
+        assert_eq!(
+            open[0].recurrence.as_ref().and_then(|rule| rule.count),
+            Some(2)
+        );
+        let completed = todos
+            .find_by_id(todo.id, user_id)
+            .await
+            .expect("取得")
+            .expect("todoが存在する");
+        assert!(completed.recurrence.is_none());
+    }
+
+    #[tokio::test]
+    async fn 未完了のblockerがあると強制しない限り完了できない() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (store, user_id, todo_id) = setup(&dir).await;
+        let todos = FileTodoRepositoryImpl::new(store.clone());
+        let repo = FileCompletionRepositoryImpl::new(store.clone());
+        let blocker = todos
+            .create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: "先にやること".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("todo作成が成功する");
+        FileDependencyRepositoryImpl::new(store)
+            .add(AddDependency {
+                todo_id,
+                blocker_id: blocker.id,
+                user_id,
+            })
+            .await
+            .expect("依存を張れる");
+        let blocked = todos
+            .find_all(
+                user_id,
+                TodoFilter {
+                    blocked: Some(true),
+                    ..Default::default()
+                },
+                ListQuery::default(),
+            )
+            .await
+            .expect("一覧取得");
+        assert_eq!(blocked.items.len(), 1);
+        assert_eq!(blocked.items[0].id, todo_id);
+
+        let complete = |force| CreateCompletion {
+            todo_id,
+            user_id,
+            include_subtasks: false,
+            force,
+        };
+        let err = repo
+            .create(complete(false))
+            .await
+            .expect_err("blockerが未完了なので完了できない");
+        assert!(matches!(err, AppError::StateConflict(_)));
+        repo.create(complete(true))
+            .await
+            .expect("強制すれば完了できる");
+    }
+}
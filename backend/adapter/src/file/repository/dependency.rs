@@ -0,0 +1,205 @@
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        dependency::{
+            DependencyTodo, TodoDependencies,
+            event::{AddDependency, RemoveDependency},
+        },
+        id::{TodoId, UserId},
+    },
+    repository::dependency::DependencyRepository,
+};
+use shared::error::{AppError, AppResult};
+
+use crate::file::{
+    FileStore,
+    model::{DependencyRecord, FileData},
+};
+
+#[derive(new)]
+pub struct FileDependencyRepositoryImpl {
+    store: FileStore,
+}
+
+fn dependency_todo(data: &FileData, id: TodoId, user_id: UserId) -> Option<DependencyTodo> {
+    data.todos
+        .iter()
+        .find(|todo| todo.id == id && todo.user_id == user_id)
+        .map(|todo| DependencyTodo {
+            id: todo.id,
+            title: todo.title.clone(),
+            completed: todo.completed,
+        })
+}
+
+#[async_trait]
+impl DependencyRepository for FileDependencyRepositoryImpl {
+    async fn add(&self, event: AddDependency) -> AppResult<()> {
+        self.store
+            .write(move |data| {
+                if event.todo_id == event.blocker_id {
+                    return Err(AppError::UnprocessableEntity(
+                        "A todo cannot block itself".into(),
+                    ));
+                }
+                let owns = |id| {
+                    data.todos
+                        .iter()
+                        .any(|todo| todo.id == id && todo.user_id == event.user_id)
+                };
+                if !owns(event.todo_id) || !owns(event.blocker_id) {
+                    return Err(AppError::EntityNotFoundError(
+                        "The todo or blocker was not found".into(),
+                    ));
+                }
+                if data.blocks_transitively(event.blocker_id, event.todo_id) {
+                    return Err(AppError::UnprocessableEntity(
+                        "The dependency would create a cycle".into(),
+                    ));
+                }
+
+                if !data.dependencies.iter().any(|dependency| {
+                    dependency.todo_id == event.todo_id && dependency.blocker_id == event.blocker_id
+                }) {
+                    data.dependencies.push(DependencyRecord {
+                        todo_id: event.todo_id,
+                        blocker_id: event.blocker_id,
+                    });
+                }
+                Ok(())
+            })
+            .await
+    }
+
+    async fn remove(&self, event: RemoveDependency) -> AppResult<()> {
+        self.store
+            .write(move |data| {
+                let owns_todo = data
+                    .todos
+                    .iter()
+                    .any(|todo| todo.id == event.todo_id && todo.user_id == event.user_id);
+                let before = data.dependencies.len();
+                if owns_todo {
+                    data.dependencies.retain(|dependency| {
+                        !(dependency.todo_id == event.todo_id
+                            && dependency.blocker_id == event.blocker_id)
+                    });
+                }
+                if data.dependencies.len() == before {
+                    return Err(AppError::EntityNotFoundError(
+                        "The todo is not blocked by the blocker".into(),
+                    ));
+                }
+                Ok(())
+            })
+            .await
+    }
+
+    async fn find_by_todo_id(
+        &self,
+        todo_id: TodoId,
+        user_id: UserId,
+    ) -> AppResult<TodoDependencies> {
+        self.store
+            .read(move |data| {
+                let blockers = data
+                    .dependencies
+                    .iter()
+                    .filter(|dependency| dependency.todo_id == todo_id)
+                    .filter_map(|dependency| dependency_todo(data, dependency.blocker_id, user_id))
+                    .collect();
+                let dependents = data
+                    .dependencies
+                    .iter()
+                    .filter(|dependency| dependency.blocker_id == todo_id)
+                    .filter_map(|dependency| dependency_todo(data, dependency.todo_id, user_id))
+                    .collect();
+                Ok(TodoDependencies {
+                    blockers,
+                    dependents,
+                })
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::repository::{todo::FileTodoRepositoryImpl, user::FileUserRepositoryImpl};
+    use kernel::{
+        model::{
+            todo::{
+                Priority,
+                event::{CreateTodo, DeleteTodo},
+            },
+            user::event::CreateUser,
+        },
+        repository::{todo::TodoRepository, user::UserRepository},
+    };
+    use shared::config::FileConfig;
+
+    #[tokio::test]
+    async fn 循環する依存は拒否しtodoの削除で依存も消える() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let store = FileStore::new(&FileConfig {
+            path: dir.path().join("todo.json"),
+        });
+        let user_id = FileUserRepositoryImpl::new(store.clone())
+            .create(CreateUser {
+                name: "Alice".to_string(),
+                email: "alice@example.com".to_string(),
+                password: "password123".to_string(),
+            })
+            .await
+            .expect("ユーザ作成が成功する")
+            .id;
+        let todos = FileTodoRepositoryImpl::new(store.clone());
+        let repo = FileDependencyRepositoryImpl::new(store);
+        let mut ids = Vec::new();
+        for title in ["設計", "実装", "リリース"] {
+            let todo = todos
+                .create(CreateTodo {
+                    user_id,
+                    project_id: None,
+                    parent_id: None,
+                    title: title.to_string(),
+                    description: None,
+                    priority: Priority::None,
+                    due_at: None,
+                    recurrence: None,
+                })
+                .await
+                .expect("作成が成功する");
+            ids.push(todo.id);
+        }
+        let (design, build, release) = (ids[0], ids[1], ids[2]);
+        let add = |todo_id, blocker_id| AddDependency {
+            todo_id,
+            blocker_id,
+            user_id,
+        };
+
+        repo.add(add(build, design)).await.expect("依存を張れる");
+        repo.add(add(release, build)).await.expect("依存を張れる");
+        let err = repo
+            .add(add(design, release))
+            .await
+            .expect_err("循環する依存は張れない");
+        assert!(matches!(err, AppError::UnprocessableEntity(_)));
+
+        todos
+            .delete(DeleteTodo { id: build, user_id })
+            .await
+            .expect("削除が成功する");
+        let deps = repo
+            .find_by_todo_id(release, user_id)
+            .await
+            .expect("取得が成功する");
+        assert!(deps.blockers.is_empty());
+        repo.add(add(design, release))
+            .await
+            .expect("循環しなくなったので張れる");
+    }
+}
@@ -0,0 +1,17 @@
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::repository::health::HealthCheckRepository;
+
+use crate::file::FileStore;
+
+#[derive(new)]
+pub struct FileHealthCheckRepositoryImpl {
+    store: FileStore,
+}
+
+#[async_trait]
+impl HealthCheckRepository for FileHealthCheckRepositoryImpl {
+    async fn check_db(&self) -> bool {
+        self.store.read(|_| Ok(())).await.is_ok()
+    }
+}
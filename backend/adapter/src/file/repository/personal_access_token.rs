@@ -0,0 +1,235 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use derive_new::new;
+use kernel::{
+    model::{
+        id::{PersonalAccessTokenId, UserId},
+        personal_access_token::{
+            PersonalAccessToken, PersonalAccessTokenSecret,
+            event::{CreatePersonalAccessToken, DeletePersonalAccessToken},
+        },
+    },
+    repository::personal_access_token::PersonalAccessTokenRepository,
+};
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    digest::sha256_hex,
+    file::{FileStore, model::PersonalAccessTokenRecord},
+};
+
+#[derive(new)]
+pub struct FilePersonalAccessTokenRepositoryImpl {
+    store: FileStore,
+}
+
+#[async_trait]
+impl PersonalAccessTokenRepository for FilePersonalAccessTokenRepositoryImpl {
+    async fn create(&self, event: CreatePersonalAccessToken) -> AppResult<PersonalAccessToken> {
+        self.store
+            .write(move |data| {
+                if data
+                    .personal_access_tokens
+                    .iter()
+                    .any(|token| token.user_id == event.user_id && token.name == event.name)
+                {
+                    return Err(AppError::Conflict("name".into()));
+                }
+
+                let record = PersonalAccessTokenRecord {
+                    id: PersonalAccessTokenId::new(),
+                    user_id: event.user_id,
+                    name: event.name,
+                    token_hash: sha256_hex(&event.secret.0),
+                    scopes: event.scopes,
+                    expires_at: event.expires_at,
+                    last_used_at: None,
+                    created_at: Utc::now(),
+                };
+                let token = PersonalAccessToken::from(&record);
+                data.personal_access_tokens.push(record);
+                Ok(token)
+            })
+            .await
+    }
+
+    async fn find_all(&self, user_id: UserId) -> AppResult<Vec<PersonalAccessToken>> {
+        self.store
+            .read(move |data| {
+                let mut tokens: Vec<PersonalAccessToken> = data
+                    .personal_access_tokens
+                    .iter()
+                    .filter(|token| token.user_id == user_id)
+                    .map(PersonalAccessToken::from)
+                    .collect();
+                tokens.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+                Ok(tokens)
+            })
+            .await
+    }
+
+    async fn delete(&self, event: DeletePersonalAccessToken) -> AppResult<()> {
+        self.store
+            .write(move |data| {
+                let before = data.personal_access_tokens.len();
+                data.personal_access_tokens
+                    .retain(|token| token.id != event.id || token.user_id != event.user_id);
+                if data.personal_access_tokens.len() == before {
+                    return Err(AppError::EntityNotFoundError(
+                        "The personal access token was not found".into(),
+                    ));
+                }
+                Ok(())
+            })
+            .await
+    }
+
+    async fn authenticate(
+        &self,
+        secret: &PersonalAccessTokenSecret,
+    ) -> AppResult<Option<PersonalAccessToken>> {
+        let token_hash = sha256_hex(&secret.0);
+        self.store
+            .write(move |data| {
+                let now = Utc::now();
+                let Some(record) = data.personal_access_tokens.iter_mut().find(|token| {
+                    token.token_hash == token_hash
+                        && token.expires_at.is_none_or(|expires_at| expires_at > now)
+                }) else {
+                    return Ok(None);
+                };
+                record.last_used_at = Some(now);
+                Ok(Some(PersonalAccessToken::from(&*record)))
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::repository::user::FileUserRepositoryImpl;
+    use chrono::Duration;
+    use kernel::{
+        model::{personal_access_token::TokenScope, user::event::CreateUser},
+        repository::user::UserRepository,
+    };
+    use shared::config::FileConfig;
+
+    fn store_in(dir: &tempfile::TempDir) -> FileStore {
+        FileStore::new(&FileConfig {
+            path: dir.path().join("todo.json"),
+        })
+    }
+
+    async fn create_user(store: &FileStore) -> UserId {
+        FileUserRepositoryImpl::new(store.clone())
+            .create(CreateUser {
+                name: "Alice".to_string(),
+                email: "alice@example.com".to_string(),
+                password: "password123".to_string(),
+            })
+            .await
+            .expect("ユーザ作成が成功する")
+            .id
+    }
+
+    fn create_event(
+        user_id: UserId,
+        name: &str,
+        secret: &PersonalAccessTokenSecret,
+    ) -> CreatePersonalAccessToken {
+        CreatePersonalAccessToken {
+            user_id,
+            name: name.to_string(),
+            scopes: vec![TokenScope::TodosRead],
+            expires_at: None,
+            secret: secret.clone(),
+        }
+    }
+
+    #[tokio::test]
+    async fn シークレットで認証でき最終利用日時が記録される() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let store = store_in(&dir);
+        let user_id = create_user(&store).await;
+        let repo = FilePersonalAccessTokenRepositoryImpl::new(store);
+
+        let secret = PersonalAccessTokenSecret::generate();
+        let created = repo
+            .create(create_event(user_id, "ci", &secret))
+            .await
+            .expect("作成が成功する");
+        assert_eq!(created.last_used_at, None);
+        let saved = std::fs::read_to_string(dir.path().join("todo.json")).expect("読み込み");
+        assert!(!saved.contains(&secret.0));
+
+        let token = repo
+            .authenticate(&secret)
+            .await
+            .expect("認証が成功する")
+            .expect("トークンが見つかる");
+        assert_eq!(token.id, created.id);
+        assert_eq!(token.user_id, user_id);
+        assert!(token.last_used_at.is_some());
+
+        let err = repo
+            .create(create_event(
+                user_id,
+                "ci",
+                &PersonalAccessTokenSecret::generate(),
+            ))
+            .await
+            .expect_err("同じ名前は作れない");
+        assert!(matches!(err, AppError::Conflict(_)));
+    }
+
+    #[tokio::test]
+    async fn 期限切れか失効したトークンでは認証できない() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let store = store_in(&dir);
+        let user_id = create_user(&store).await;
+        let repo = FilePersonalAccessTokenRepositoryImpl::new(store);
+
+        let expired = PersonalAccessTokenSecret::generate();
+        repo.create(CreatePersonalAccessToken {
+            expires_at: Some(Utc::now() - Duration::seconds(1)),
+            ..create_event(user_id, "expired", &expired)
+        })
+        .await
+        .expect("作成が成功する");
+        assert!(
+            repo.authenticate(&expired)
+                .await
+                .expect("認証を試せる")
+                .is_none()
+        );
+
+        let revoked = PersonalAccessTokenSecret::generate();
+        let token = repo
+            .create(create_event(user_id, "revoked", &revoked))
+            .await
+            .expect("作成が成功する");
+        let err = repo
+            .delete(DeletePersonalAccessToken {
+                id: token.id,
+                user_id: UserId::new(),
+            })
+            .await
+            .expect_err("他のユーザのトークンは失効できない");
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+        repo.delete(DeletePersonalAccessToken {
+            id: token.id,
+            user_id,
+        })
+        .await
+        .expect("失効が成功する");
+        assert!(
+            repo.authenticate(&revoked)
+                .await
+                .expect("認証を試せる")
+                .is_none()
+        );
+        assert_eq!(repo.find_all(user_id).await.expect("取得").len(), 1);
+    }
+}
@@ -0,0 +1,303 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use derive_new::new;
+use kernel::{
+    model::{
+        id::{ProjectId, UserId},
+        project::{
+            Project,
+            event::{CreateProject, DeleteProject, DeleteProjectMode, UpdateProject},
+        },
+    },
+    repository::project::ProjectRepository,
+};
+use shared::error::{AppError, AppResult};
+
+use crate::file::{FileStore, model::ProjectRecord};
+
+#[derive(new)]
+pub struct FileProjectRepositoryImpl {
+    store: FileStore,
+}
+
+#[async_trait]
+impl ProjectRepository for FileProjectRepositoryImpl {
+    async fn create(&self, event: CreateProject) -> AppResult<Project> {
+        self.store
+            .write(move |data| {
+                if data
+                    .projects
+                    .iter()
+                    .any(|project| project.user_id == event.user_id && project.name == event.name)
+                {
+                    return Err(AppError::Conflict("name".into()));
+                }
+
+                let now = Utc::now();
+                let record = ProjectRecord {
+                    id: ProjectId::new(),
+                    user_id: event.user_id,
+                    name: event.name,
+                    colour: event.colour,
+                    archived: false,
+                    created_at: now,
+                    updated_at: now,
+                };
+                let project = data.project_of(&record);
+                data.projects.push(record);
+                Ok(project)
+            })
+            .await
+    }
+
+    async fn find_all(&self, user_id: UserId, include_archived: bool) -> AppResult<Vec<Project>> {
+        self.store
+            .read(move |data| {
+                let mut projects: Vec<Project> = data
+                    .projects
+                    .iter()
+                    .filter(|project| {
+                        project.user_id == user_id && (include_archived || !project.archived)
+                    })
+                    .map(|project| data.project_of(project))
+                    .collect();
+                projects.sort_by(|a, b| a.name.cmp(&b.name));
+                Ok(projects)
+            })
+            .await
+    }
+
+    async fn find_by_id(&self, id: ProjectId, user_id: UserId) -> AppResult<Option<Project>> {
+        self.store
+            .read(move |data| {
+                Ok(data
+                    .projects
+                    .iter()
+                    .find(|project| project.id == id && project.user_id == user_id)
+                    .map(|project| data.project_of(project)))
+            })
+            .await
+    }
+
+    async fn update(&self, event: UpdateProject) -> AppResult<()> {
+        self.store
+            .write(move |data| {
+                if data.projects.iter().any(|project| {
+                    project.user_id == event.user_id
+                        && project.name == event.name
+                        && project.id != event.id
+                }) {
+                    return Err(AppError::Conflict("name".into()));
+                }
+
+                let project = data
+                    .projects
+                    .iter_mut()
+                    .find(|project| project.id == event.id && project.user_id == event.user_id)
+                    .ok_or_else(|| {
+                        AppError::EntityNotFoundError("No project has been updated".into())
+                    })?;
+
+                project.name = event.name;
+                project.colour = event.colour;
+                project.archived = event.archived;
+                project.updated_at = Utc::now();
+                Ok(())
+            })
+            .await
+    }
+
+    async fn delete(&self, event: DeleteProject) -> AppResult<()> {
+        self.store
+            .write(move |data| {
+                let before = data.projects.len();
+                data.projects.retain(|project| {
+                    !(project.id == event.id && project.user_id == event.user_id)
+                });
+                if data.projects.len() == before {
+                    return Err(AppError::EntityNotFoundError(
+                        "No project has been deleted".into(),
+                    ));
+                }
+
+                match event.mode {
+                    DeleteProjectMode::Cascade => {
+                        data.remove_todos(|todo| todo.project_id == Some(event.id));
+                    }
+                    DeleteProjectMode::MoveToInbox => {
+                        for todo in data
+                            .todos
+                            .iter_mut()
+                            .filter(|todo| todo.project_id == Some(event.id))
+                        {
+                            todo.project_id = None;
+                        }
+                    }
+                }
+                Ok(())
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::repository::{todo::FileTodoRepositoryImpl, user::FileUserRepositoryImpl};
+    use kernel::{
+        model::{
+            project::DEFAULT_COLOUR,
+            todo::{Priority, event::CreateTodo},
+            user::event::CreateUser,
+        },
+        repository::{todo::TodoRepository, user::UserRepository},
+    };
+    use shared::config::FileConfig;
+
+    async fn setup(
+        dir: &tempfile::TempDir,
+    ) -> (FileProjectRepositoryImpl, FileTodoRepositoryImpl, UserId) {
+        let store = FileStore::new(&FileConfig {
+            path: dir.path().join("todo.json"),
+        });
+        let user = FileUserRepositoryImpl::new(store.clone())
+            .create(CreateUser {
+                name: "Alice".to_string(),
+                email: "alice@example.com".to_string(),
+                password: "password123".to_string(),
+            })
+            .await
+            .expect("ユーザ作成が成功する");
+        (
+            FileProjectRepositoryImpl::new(store.clone()),
+            FileTodoRepositoryImpl::new(store),
+            user.id,
+        )
+    }
+
+    async fn create_todo(repo: &FileTodoRepositoryImpl, user_id: UserId, project_id: ProjectId) {
+        repo.create(CreateTodo {
+            user_id,
+            project_id: Some(project_id),
+            parent_id: None,
+            title: "牛乳を買う".to_string(),
+            description: None,
+            priority: Priority::None,
+            due_at: None,
+            recurrence: None,
+        })
+        .await
+        .expect("作成が成功する");
+    }
+
+    #[tokio::test]
+    async fn プロジェクトの件数と完了数を返しアーカイブは除外できる() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (repo, todos, user_id) = setup(&dir).await;
+        let project = repo
+            .create(CreateProject {
+                user_id,
+                name: "買い物".to_string(),
+                colour: DEFAULT_COLOUR.to_string(),
+            })
+            .await
+            .expect("作成が成功する");
+        create_todo(&todos, user_id, project.id).await;
+        create_todo(&todos, user_id, project.id).await;
+
+        let found = repo
+            .find_by_id(project.id, user_id)
+            .await
+            .expect("取得")
+            .expect("プロジェクトが存在する");
+        assert_eq!(found.progress.total, 2);
+        assert_eq!(found.progress.completed, 0);
+
+        repo.update(UpdateProject {
+            id: project.id,
+            user_id,
+            name: "買い物".to_string(),
+            colour: "#ff0000".to_string(),
+            archived: true,
+        })
+        .await
+        .expect("更新が成功する");
+        assert!(
+            repo.find_all(user_id, false)
+                .await
+                .expect("一覧")
+                .is_empty()
+        );
+        assert_eq!(repo.find_all(user_id, true).await.expect("一覧").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn プロジェクト削除はtodoを削除するか受信箱に移す() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (repo, todos, user_id) = setup(&dir).await;
+        let repo = &repo;
+        let create = move |name: &'static str| async move {
+            repo.create(CreateProject {
+                user_id,
+                name: name.to_string(),
+                colour: DEFAULT_COLOUR.to_string(),
+            })
+            .await
+            .expect("作成が成功する")
+        };
+        let moved = create("移す").await;
+        let cascaded = create("消す").await;
+        create_todo(&todos, user_id, moved.id).await;
+        create_todo(&todos, user_id, cascaded.id).await;
+
+        for (project, mode) in [
+            (&moved, DeleteProjectMode::MoveToInbox),
+            (&cascaded, DeleteProjectMode::Cascade),
+        ] {
+            repo.delete(DeleteProject {
+                id: project.id,
+                user_id,
+                mode,
+            })
+            .await
+            .expect("削除が成功する");
+        }
+
+        let remaining = todos
+            .find_all(user_id, Default::default(), Default::default())
+            .await
+            .expect("一覧取得")
+            .items;
+        assert_eq!(remaining.len(), 1);
+        assert!(remaining[0].project_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn 他人のプロジェクトにはtodoを追加できない() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (repo, todos, user_id) = setup(&dir).await;
+        let others = repo
+            .create(CreateProject {
+                user_id: UserId::new(),
+                name: "買い物".to_string(),
+                colour: DEFAULT_COLOUR.to_string(),
+            })
+            .await
+            .expect("作成が成功する");
+
+        let err = todos
+            .create(CreateTodo {
+                user_id,
+                project_id: Some(others.id),
+                parent_id: None,
+                title: "牛乳を買う".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect_err("他人のプロジェクトには追加できない");
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+    }
+}
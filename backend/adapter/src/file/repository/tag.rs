@@ -0,0 +1,297 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use derive_new::new;
+use kernel::{
+    model::{
+        id::{TagId, UserId},
+        tag::{
+            Tag,
+            event::{AttachTag, CreateTag, DeleteTag, DetachTag, UpdateTag},
+        },
+    },
+    repository::tag::TagRepository,
+};
+use shared::error::{AppError, AppResult};
+
+use crate::file::{
+    FileStore,
+    model::{TagRecord, TodoTagRecord},
+};
+
+#[derive(new)]
+pub struct FileTagRepositoryImpl {
+    store: FileStore,
+}
+
+#[async_trait]
+impl TagRepository for FileTagRepositoryImpl {
+    async fn create(&self, event: CreateTag) -> AppResult<Tag> {
+        self.store
+            .write(move |data| {
+                if data
+                    .tags
+                    .iter()
+                    .any(|tag| tag.user_id == event.user_id && tag.name == event.name)
+                {
+                    return Err(AppError::Conflict("name".into()));
+                }
+
+                let now = Utc::now();
+                let record = TagRecord {
+                    id: TagId::new(),
+                    user_id: event.user_id,
+                    name: event.name,
+                    created_at: now,
+                    updated_at: now,
+                };
+                let tag = Tag::from(&record);
+                data.tags.push(record);
+                Ok(tag)
+            })
+            .await
+    }
+
+    async fn find_all(&self, user_id: UserId) -> AppResult<Vec<Tag>> {
+        self.store
+            .read(move |data| {
+                let mut tags: Vec<Tag> = data
+                    .tags
+                    .iter()
+                    .filter(|tag| tag.user_id == user_id)
+                    .map(Tag::from)
+                    .collect();
+                tags.sort_by(|a, b| a.name.cmp(&b.name));
+                Ok(tags)
+            })
+            .await
+    }
+
+    async fn find_by_id(&self, id: TagId, user_id: UserId) -> AppResult<Option<Tag>> {
+        self.store
+            .read(move |data| {
+                Ok(data
+                    .tags
+                    .iter()
+                    .find(|tag| tag.id == id && tag.user_id == user_id)
+                    .map(Tag::from))
+            })
+            .await
+    }
+
+    async fn update(&self, event: UpdateTag) -> AppResult<()> {
+        self.store
+            .write(move |data| {
+                if data.tags.iter().any(|tag| {
+                    tag.user_id == event.user_id && tag.name == event.name && tag.id != event.id
+                }) {
+                    return Err(AppError::Conflict("name".into()));
+                }
+
+                let tag = data
+                    .tags
+                    .iter_mut()
+                    .find(|tag| tag.id == event.id && tag.user_id == event.user_id)
+                    .ok_or_else(|| {
+                        AppError::EntityNotFoundError("No tag has been updated".into())
+                    })?;
+
+                tag.name = event.name;
+                tag.updated_at = Utc::now();
+                Ok(())
+            })
+            .await
+    }
+
+    async fn delete(&self, event: DeleteTag) -> AppResult<()> {
+        self.store
+            .write(move |data| {
+                let before = data.tags.len();
+                data.tags
+                    .retain(|tag| !(tag.id == event.id && tag.user_id == event.user_id));
+                if data.tags.len() == before {
+                    return Err(AppError::EntityNotFoundError(
+                        "No tag has been deleted".into(),
+                    ));
+                }
+
+                data.todo_tags.retain(|link| link.tag_id != event.id);
+                Ok(())
+            })
+            .await
+    }
+
+    async fn attach(&self, event: AttachTag) -> AppResult<()> {
+        self.store
+            .write(move |data| {
+                let owns_todo = data
+                    .todos
+                    .iter()
+                    .any(|todo| todo.id == event.todo_id && todo.user_id == event.user_id);
+                let owns_tag = data
+                    .tags
+                    .iter()
+                    .any(|tag| tag.id == event.tag_id && tag.user_id == event.user_id);
+                if !owns_todo || !owns_tag {
+                    return Err(AppError::EntityNotFoundError(
+                        "The todo or tag was not found".into(),
+                    ));
+                }
+
+                if !data
+                    .todo_tags
+                    .iter()
+                    .any(|link| link.todo_id == event.todo_id && link.tag_id == event.tag_id)
+                {
+                    data.todo_tags.push(TodoTagRecord {
+                        todo_id: event.todo_id,
+                        tag_id: event.tag_id,
+                    });
+                }
+                Ok(())
+            })
+            .await
+    }
+
+    async fn detach(&self, event: DetachTag) -> AppResult<()> {
+        self.store
+            .write(move |data| {
+                let owns_tag = data
+                    .tags
+                    .iter()
+                    .any(|tag| tag.id == event.tag_id && tag.user_id == event.user_id);
+                let before = data.todo_tags.len();
+                if owns_tag {
+                    data.todo_tags.retain(|link| {
+                        !(link.todo_id == event.todo_id && link.tag_id == event.tag_id)
+                    });
+                }
+                if data.todo_tags.len() == before {
+                    return Err(AppError::EntityNotFoundError(
+                        "The tag is not attached to the todo".into(),
+                    ));
+                }
+                Ok(())
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::repository::{todo::FileTodoRepositoryImpl, user::FileUserRepositoryImpl};
+    use kernel::{
+        model::{
+            list::ListQuery,
+            todo::{
+                Priority,
+                event::{CreateTodo, DeleteTodo},
+                filter::TodoFilter,
+            },
+            user::event::CreateUser,
+        },
+        repository::{todo::TodoRepository, user::UserRepository},
+    };
+    use shared::config::FileConfig;
+
+    async fn setup(
+        dir: &tempfile::TempDir,
+    ) -> (FileTagRepositoryImpl, FileTodoRepositoryImpl, UserId) {
+        let store = FileStore::new(&FileConfig {
+            path: dir.path().join("todo.json"),
+        });
+        let user = FileUserRepositoryImpl::new(store.clone())
+            .create(CreateUser {
+                name: "Alice".to_string(),
+                email: "alice@example.com".to_string(),
+                password: "password123".to_string(),
+            })
+            .await
+            .expect("ユーザ作成が成功する");
+        (
+            FileTagRepositoryImpl::new(store.clone()),
+            FileTodoRepositoryImpl::new(store),
+            user.id,
+        )
+    }
+
+    #[tokio::test]
+    async fn 同じ名前のタグは作成できない() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (repo, _, user_id) = setup(&dir).await;
+        let create = || CreateTag {
+            user_id,
+            name: "bug".to_string(),
+        };
+
+        repo.create(create()).await.expect("作成が成功する");
+        let err = repo
+            .create(create())
+            .await
+            .expect_err("重複したタグは作成できない");
+
+        assert!(matches!(err, AppError::Conflict(ref field) if field == "name"));
+    }
+
+    #[tokio::test]
+    async fn タグで絞り込みtodoの削除でタグ付けも消える() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (repo, todos, user_id) = setup(&dir).await;
+        let bug = repo
+            .create(CreateTag {
+                user_id,
+                name: "bug".to_string(),
+            })
+            .await
+            .expect("作成が成功する");
+        let todo = todos
+            .create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: "直す".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("作成が成功する");
+
+        for _ in 0..2 {
+            repo.attach(AttachTag {
+                tag_id: bug.id,
+                todo_id: todo.id,
+                user_id,
+            })
+            .await
+            .expect("タグ付けが成功する");
+        }
+        let filter = TodoFilter {
+            tags_all: vec![bug.id],
+            ..Default::default()
+        };
+        let page = todos
+            .find_all(user_id, filter.clone(), ListQuery::default())
+            .await
+            .expect("一覧取得");
+        assert_eq!(page.items.len(), 1);
+
+        todos
+            .delete(DeleteTodo {
+                id: todo.id,
+                user_id,
+            })
+            .await
+            .expect("削除が成功する");
+        let err = repo
+            .detach(DetachTag {
+                tag_id: bug.id,
+                todo_id: todo.id,
+                user_id,
+            })
+            .await
+            .expect_err("タグ付けは残っていない");
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+    }
+}
@@ -0,0 +1,789 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use derive_new::new;
+use kernel::{
+    model::{
+        id::{TodoId, UserId},
+        list::{ListQuery, Page},
+        todo::{
+            Todo, TodoSort,
+            event::{CreateTodo, DeleteTodo, ReorderTodo, UpdateTodo},
+            filter::TodoFilter,
+            position::Position,
+            search::{TodoSearch, TodoSearchHit},
+            tree::TodoTree,
+        },
+    },
+    repository::todo::TodoRepository,
+};
+use shared::error::{AppError, AppResult};
+
+use crate::file::{FileStore, list::paginate, model::TodoRecord};
+
+#[derive(new)]
+pub struct FileTodoRepositoryImpl {
+    store: FileStore,
+}
+
+#[async_trait]
+impl TodoRepository for FileTodoRepositoryImpl {
+    async fn create(&self, event: CreateTodo) -> AppResult<Todo> {
+        self.store
+            .write(move |data| {
+                if !data.users.iter().any(|user| user.id == event.user_id) {
+                    return Err(AppError::EntityNotFoundError(
+                        "The user was not found".into(),
+                    ));
+                }
+                if !data.owns_project(event.project_id, event.user_id)
+                    || !data.owns_parent(event.parent_id, event.user_id)
+                {
+                    return Err(AppError::EntityNotFoundError(
+                        "The project or parent todo was not found".into(),
+                    ));
+                }
+
+                let now = Utc::now();
+                let position = data.append_position(event.user_id)?;
+                let record = TodoRecord {
+                    id: TodoId::new(),
+                    user_id: event.user_id,
+                    project_id: event.project_id,
+                    parent_id: event.parent_id,
+                    title: event.title,
+                    description: event.description,
+                    priority: event.priority,
+                    completed: false,
+                    due_at: event.due_at,
+                    recurrence: event.recurrence,
+                    position: Some(position),
+                    created_at: now,
+                    updated_at: now,
+                };
+                let todo = Todo::from(&record);
+                data.todos.push(record);
+                Ok(todo)
+            })
+            .await
+    }
+
+    async fn find_all(
+        &self,
+        user_id: UserId,
+        filter: TodoFilter,
+        query: ListQuery<TodoSort>,
+    ) -> AppResult<Page<Todo>> {
+        let now = Utc::now();
+        self.store
+            .read(move |data| {
+                paginate(
+                    data.todos
+                        .iter()
+                        .filter(|todo| {
+                            todo.user_id == user_id
+                                && filter.matches(
+                                    &Todo::from(*todo),
+                                    &data.tag_ids_of(todo.id),
+                                    data.is_blocked(todo.id),
+                                    now,
+                                )
+                        })
+                        .collect(),
+                    &query,
+                    |todo| todo.sort_value(query.sort),
+                    |todo| todo.id.raw(),
+                )
+            })
+            .await
+    }
+
+    async fn search(&self, user_id: UserId, search: TodoSearch) -> AppResult<Vec<TodoSearchHit>> {
+        self.store
+            .read(move |data| {
+                let mut hits: Vec<TodoSearchHit> = data
+                    .todos
+                    .iter()
+                    .filter(|todo| todo.user_id == user_id)
+                    .filter_map(|todo| {
+                        let description = todo.description.as_deref();
+                        let rank = search.score(&todo.title, description)?;
+                        Some(TodoSearchHit {
+                            todo: Todo::from(todo),
+                            rank,
+                            snippet: search.snippet(&todo.title, description),
+                        })
+                    })
+                    .collect();
+                hits.sort_by(|a, b| {
+                    b.rank
+                        .total_cmp(&a.rank)
+                        .then_with(|| a.todo.id.raw().cmp(&b.todo.id.raw()))
+                });
+                hits.truncate(search.limit as usize);
+                Ok(hits)
+            })
+            .await
+    }
+
+    async fn find_by_id(&self, id: TodoId, user_id: UserId) -> AppResult<Option<Todo>> {
+        self.store
+            .read(move |data| {
+                Ok(data
+                    .todos
+                    .iter()
+                    .find(|todo| todo.id == id && todo.user_id == user_id)
+                    .map(Todo::from))
+            })
+            .await
+    }
+
+    async fn find_tree(&self, id: TodoId, user_id: UserId) -> AppResult<Option<TodoTree>> {
+        self.store
+            .read(move |data| {
+                let Some(root) = data
+                    .todos
+                    .iter()
+                    .find(|todo| todo.id == id && todo.user_id == user_id)
+                else {
+                    return Ok(None);
+                };
+                let ids = data.with_descendants(vec![id]);
+                let descendants = data
+                    .todos
+                    .iter()
+                    .filter(|todo| todo.id != id && ids.contains(&todo.id))
+                    .map(Todo::from)
+                    .collect();
+                Ok(Some(TodoTree::build(Todo::from(root), descendants)))
+            })
+            .await
+    }
+
+    async fn update(&self, event: UpdateTodo) -> AppResult<()> {
+        self.store
+            .write(move |data| {
+                if !data.owns_project(event.project_id, event.user_id)
+                    || !data.owns_parent(event.parent_id, event.user_id)
+                {
+                    return Err(AppError::EntityNotFoundError(
+                        "No todo has been updated".into(),
+                    ));
+                }
+                if event.parent_id.is_some_and(|parent_id| {
+                    data.with_descendants(vec![event.id]).contains(&parent_id)
+                }) {
+                    return Err(AppError::UnprocessableEntity(
+                        "A todo cannot be moved under itself or its subtasks".into(),
+                    ));
+                }
+                let todo = data
+                    .todos
+                    .iter_mut()
+                    .find(|todo| todo.id == event.id && todo.user_id == event.user_id)
+                    .ok_or_else(|| {
+                        AppError::EntityNotFoundError("No todo has been updated".into())
+                    })?;
+
+                todo.project_id = event.project_id;
+                todo.parent_id = event.parent_id;
+                todo.title = event.title;
+                todo.description = event.description;
+                todo.priority = event.priority;
+                todo.due_at = event.due_at;
+                todo.recurrence = event.recurrence;
+                todo.updated_at = Utc::now();
+                Ok(())
+            })
+            .await
+    }
+
+    async fn reorder(&self, event: ReorderTodo) -> AppResult<Todo> {
+        self.store
+            .write(move |data| {
+                if event.after == Some(event.id) || event.before == Some(event.id) {
+                    return Err(AppError::UnprocessableEntity(
+                        "A todo cannot be placed next to itself".into(),
+                    ));
+                }
+                let position_of = |id: TodoId| {
+                    data.todos
+                        .iter()
+                        .find(|todo| todo.id == id && todo.user_id == event.user_id)
+                        .and_then(|todo| todo.position.clone())
+                };
+                if position_of(event.id).is_none() {
+                    return Err(AppError::EntityNotFoundError(
+                        "The todo was not found".into(),
+                    ));
+                }
+                let neighbour = |id: Option<TodoId>| {
+                    id.map(|id| {
+                        position_of(id).ok_or_else(|| {
+                            AppError::EntityNotFoundError(
+                                "The neighbouring todo was not found".into(),
+                            )
+                        })
+                    })
+                    .transpose()
+                };
+                let mut after = neighbour(event.after)?;
+                let mut before = neighbour(event.before)?;
+
+                // 片方しか指定がなければ、もう片方は今そこに隣り合っている todo にする
+                let others: Vec<&Position> = data
+                    .todos
+                    .iter()
+                    .filter(|todo| todo.user_id == event.user_id && todo.id != event.id)
+                    .filter_map(|todo| todo.position.as_ref())
+                    .collect();
+                if event.before.is_none() {
+                    before = after.as_ref().and_then(|after| {
+                        others
+                            .iter()
+                            .copied()
+                            .filter(|position| *position > after)
+                            .min()
+                            .cloned()
+                    });
+                }
+                if event.after.is_none() {
+                    after = others
+                        .iter()
+                        .copied()
+                        .filter(|position| before.as_ref().is_none_or(|before| *position < before))
+                        .max()
+                        .cloned();
+                }
+                let position = Position::between(after.as_ref(), before.as_ref())?;
+
+                let todo = data
+                    .todos
+                    .iter_mut()
+                    .find(|todo| todo.id == event.id)
+                    .ok_or_else(|| {
+                        AppError::EntityNotFoundError("The todo was not found".into())
+                    })?;
+                todo.position = Some(position);
+                todo.updated_at = Utc::now();
+                Ok(Todo::from(&*todo))
+            })
+            .await
+    }
+
+    async fn delete(&self, event: DeleteTodo) -> AppResult<()> {
+        self.store
+            .write(move |data| {
+                let before = data.todos.len();
+                data.remove_todos(|todo| todo.id == event.id && todo.user_id == event.user_id);
+                if data.todos.len() == before {
+                    return Err(AppError::EntityNotFoundError(
+                        "No todo has been deleted".into(),
+                    ));
+                }
+                Ok(())
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::repository::user::FileUserRepositoryImpl;
+    use chrono::Duration;
+    use kernel::{
+        model::{list::SortOrder, todo::Priority, user::event::CreateUser},
+        repository::user::UserRepository,
+    };
+    use shared::config::FileConfig;
+
+    async fn setup(dir: &tempfile::TempDir) -> (FileTodoRepositoryImpl, UserId) {
+        let store = FileStore::new(&FileConfig {
+            path: dir.path().join("todo.json"),
+        });
+        let user = FileUserRepositoryImpl::new(store.clone())
+            .create(CreateUser {
+                name: "Alice".to_string(),
+                email: "alice@example.com".to_string(),
+                password: "password123".to_string(),
+            })
+            .await
+            .expect("ユーザ作成が成功する");
+        (FileTodoRepositoryImpl::new(store), user.id)
+    }
+
+    #[tokio::test]
+    async fn todoを作成して取得できる() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (repo, user_id) = setup(&dir).await;
+
+        let todo = repo
+            .create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: "牛乳を買う".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("作成が成功する");
+        assert!(!todo.completed);
+
+        let found = repo
+            .find_by_id(todo.id, user_id)
+            .await
+            .expect("取得が成功する")
+            .expect("todoが存在する");
+        assert_eq!(found.title, "牛乳を買う");
+
+        let all = repo
+            .find_all(user_id, TodoFilter::default(), ListQuery::default())
+            .await
+            .expect("一覧取得");
+        assert_eq!(all.items.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn todo一覧はカーソルで続きを取得できる() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (repo, user_id) = setup(&dir).await;
+        for title in ["a", "b", "c"] {
+            repo.create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: title.to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("作成が成功する");
+        }
+        let query = |cursor: Option<&str>| {
+            ListQuery::new(
+                Some(2),
+                cursor,
+                Some(TodoSort::Title),
+                Some(SortOrder::Desc),
+            )
+            .expect("条件が正しい")
+        };
+
+        let first = repo
+            .find_all(user_id, TodoFilter::default(), query(None))
+            .await
+            .expect("一覧取得");
+        let titles: Vec<&str> = first.items.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["c", "b"]);
+
+        let cursor = first.next_cursor.expect("次ページがある").encode();
+        let second = repo
+            .find_all(user_id, TodoFilter::default(), query(Some(&cursor)))
+            .await
+            .expect("一覧取得");
+        assert_eq!(second.items.len(), 1);
+        assert_eq!(second.items[0].title, "a");
+        assert!(second.next_cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn 作成日時のカーソルでは行が重複も欠落もしない() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (repo, user_id) = setup(&dir).await;
+        let mut ids = Vec::new();
+        for title in ["a", "b", "c", "d", "e"] {
+            let todo = repo
+                .create(CreateTodo {
+                    user_id,
+                    project_id: None,
+                    parent_id: None,
+                    title: title.to_string(),
+                    description: None,
+                    priority: Priority::None,
+                    due_at: None,
+                    recurrence: None,
+                })
+                .await
+                .expect("作成が成功する");
+            ids.push(todo.id);
+        }
+
+        for order in [SortOrder::Asc, SortOrder::Desc] {
+            let mut seen = Vec::new();
+            let mut cursor: Option<String> = None;
+            loop {
+                let query = ListQuery::new(
+                    Some(2),
+                    cursor.as_deref(),
+                    Some(TodoSort::CreatedAt),
+                    Some(order),
+                )
+                .expect("条件が正しい");
+                let page = repo
+                    .find_all(user_id, TodoFilter::default(), query)
+                    .await
+                    .expect("一覧取得");
+                seen.extend(page.items.iter().map(|todo| todo.id));
+                match page.next_cursor {
+                    Some(next) => cursor = Some(next.encode()),
+                    None => break,
+                }
+            }
+            let mut expected = ids.clone();
+            if order == SortOrder::Desc {
+                expected.reverse();
+            }
+            assert_eq!(seen, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn todo一覧は期限切れで未完了のものに絞り込める() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (repo, user_id) = setup(&dir).await;
+        let now = Utc::now();
+        for (title, due_at) in [
+            ("期限切れ", Some(now - Duration::days(1))),
+            ("期限前", Some(now + Duration::days(1))),
+            ("期限なし", None),
+        ] {
+            repo.create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: title.to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at,
+                recurrence: None,
+            })
+            .await
+            .expect("作成が成功する");
+        }
+
+        let page = repo
+            .find_all(
+                user_id,
+                TodoFilter {
+                    overdue: true,
+                    ..Default::default()
+                },
+                ListQuery::default(),
+            )
+            .await
+            .expect("一覧取得");
+
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].title, "期限切れ");
+    }
+
+    #[tokio::test]
+    async fn todoをタイトルの部分一致で検索できる() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (repo, user_id) = setup(&dir).await;
+        for title in ["牛乳 (Milk) を買う", "牛乳パックを捨てる", "Buy eggs"] {
+            repo.create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: title.to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("作成が成功する");
+        }
+
+        let hits = repo
+            .search(
+                user_id,
+                TodoSearch::new("milk 牛乳", None).expect("検索語が正しい"),
+            )
+            .await
+            .expect("検索が成功する");
+
+        assert_eq!(hits.len(), 1);
+        assert_eq!(
+            hits[0].snippet,
+            "<mark>牛乳</mark> (<mark>Milk</mark>) を買う"
+        );
+    }
+
+    #[tokio::test]
+    async fn 他人のtodoは取得も更新も削除もできない() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (repo, user_id) = setup(&dir).await;
+        let other = UserId::new();
+
+        let todo = repo
+            .create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: "牛乳を買う".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("作成が成功する");
+
+        let found = repo.find_by_id(todo.id, other).await.expect("取得");
+        assert!(found.is_none());
+
+        let err = repo
+            .update(UpdateTodo {
+                id: todo.id,
+                user_id: other,
+                project_id: None,
+                parent_id: None,
+                title: "卵を買う".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect_err("他人のtodoは更新できない");
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+
+        let err = repo
+            .delete(DeleteTodo {
+                id: todo.id,
+                user_id: other,
+            })
+            .await
+            .expect_err("他人のtodoは削除できない");
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+    }
+
+    #[tokio::test]
+    async fn todoを更新して削除できる() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (repo, user_id) = setup(&dir).await;
+
+        let todo = repo
+            .create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: "牛乳を買う".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("作成が成功する");
+
+        repo.update(UpdateTodo {
+            id: todo.id,
+            user_id,
+            project_id: None,
+            parent_id: None,
+            title: "卵を買う".to_string(),
+            description: None,
+            priority: Priority::None,
+            due_at: None,
+            recurrence: None,
+        })
+        .await
+        .expect("更新が成功する");
+        let found = repo
+            .find_by_id(todo.id, user_id)
+            .await
+            .expect("取得")
+            .expect("todoが存在する");
+        assert_eq!(found.title, "卵を買う");
+
+        repo.delete(DeleteTodo {
+            id: todo.id,
+            user_id,
+        })
+        .await
+        .expect("削除が成功する");
+        assert!(
+            repo.find_all(user_id, TodoFilter::default(), ListQuery::default())
+                .await
+                .expect("一覧取得")
+                .items
+                .is_empty()
+        );
+    }
+
+    async fn create_subtask(
+        repo: &FileTodoRepositoryImpl,
+        user_id: UserId,
+        parent_id: Option<TodoId>,
+        title: &str,
+    ) -> Todo {
+        repo.create(CreateTodo {
+            user_id,
+            project_id: None,
+            parent_id,
+            title: title.to_string(),
+            description: None,
+            priority: Priority::None,
+            due_at: None,
+            recurrence: None,
+        })
+        .await
+        .expect("作成が成功する")
+    }
+
+    #[tokio::test]
+    async fn 子孫を木構造で取得し循環する付け替えは拒否する() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (repo, user_id) = setup(&dir).await;
+        let root = create_subtask(&repo, user_id, None, "親").await;
+        let child = create_subtask(&repo, user_id, Some(root.id), "子").await;
+        let grandchild = create_subtask(&repo, user_id, Some(child.id), "孫").await;
+
+        let tree = repo
+            .find_tree(root.id, user_id)
+            .await
+            .expect("取得が成功する")
+            .expect("todoが存在する");
+        assert_eq!(tree.progress.total, 2);
+        assert_eq!(tree.children[0].children[0].todo.id, grandchild.id);
+
+        let err = repo
+            .update(UpdateTodo {
+                id: root.id,
+                user_id,
+                project_id: None,
+                parent_id: Some(grandchild.id),
+                title: "親".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect_err("循環する付け替えは失敗する");
+        assert!(matches!(err, AppError::UnprocessableEntity(_)));
+
+        repo.delete(DeleteTodo {
+            id: root.id,
+            user_id,
+        })
+        .await
+        .expect("削除が成功する");
+        assert!(
+            repo.find_by_id(grandchild.id, user_id)
+                .await
+                .expect("取得")
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn 手動の並び順で並べ替えられる() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (repo, user_id) = setup(&dir).await;
+        let mut ids = Vec::new();
+        for title in ["a", "b", "c"] {
+            let todo = repo
+                .create(CreateTodo {
+                    user_id,
+                    project_id: None,
+                    parent_id: None,
+                    title: title.to_string(),
+                    description: None,
+                    priority: Priority::None,
+                    due_at: None,
+                    recurrence: None,
+                })
+                .await
+                .expect("作成が成功する");
+            ids.push(todo.id);
+        }
+        let (a, b, c) = (ids[0], ids[1], ids[2]);
+        let repo = &repo;
+        let titles = move || async move {
+            let query =
+                ListQuery::new(None, None, Some(TodoSort::Manual), None).expect("条件が正しい");
+            repo.find_all(user_id, TodoFilter::default(), query)
+                .await
+                .expect("一覧取得")
+                .items
+                .into_iter()
+                .map(|todo| todo.title)
+                .collect::<Vec<_>>()
+        };
+        let reorder = |id, after, before| ReorderTodo {
+            id,
+            user_id,
+            after,
+            before,
+        };
+        assert_eq!(titles().await, vec!["a", "b", "c"]);
+
+        repo.reorder(reorder(c, Some(a), Some(b)))
+            .await
+            .expect("並べ替えが成功する");
+        assert_eq!(titles().await, vec!["a", "c", "b"]);
+        repo.reorder(reorder(a, None, None))
+            .await
+            .expect("並べ替えが成功する");
+        assert_eq!(titles().await, vec!["c", "b", "a"]);
+        repo.reorder(reorder(b, None, Some(c)))
+            .await
+            .expect("並べ替えが成功する");
+        assert_eq!(titles().await, vec!["b", "c", "a"]);
+
+        let err = repo
+            .reorder(reorder(a, Some(a), None))
+            .await
+            .expect_err("自身の隣には動かせない");
+        assert!(matches!(err, AppError::UnprocessableEntity(_)));
+    }
+
+    #[tokio::test]
+    async fn 並び順のキーがない古いファイルは作成順にキーを振る() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let (repo, user_id) = setup(&dir).await;
+        let path = dir.path().join("todo.json");
+        let mut data: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).expect("読み込み")).expect("JSON");
+        let now = chrono::Utc::now();
+        data["todos"] = serde_json::json!([
+            {
+                "id": TodoId::new(),
+                "user_id": user_id,
+                "title": "後",
+                "completed": false,
+                "due_at": null,
+                "created_at": now,
+                "updated_at": now,
+            },
+            {
+                "id": TodoId::new(),
+                "user_id": user_id,
+                "title": "先",
+                "completed": false,
+                "due_at": null,
+                "created_at": now - Duration::hours(1),
+                "updated_at": now,
+            },
+        ]);
+        std::fs::write(&path, data.to_string()).expect("書き込み");
+
+        let query = ListQuery::new(None, None, Some(TodoSort::Manual), None).expect("条件が正しい");
+        let page = repo
+            .find_all(user_id, TodoFilter::default(), query)
+            .await
+            .expect("一覧取得");
+        let titles: Vec<&str> = page.items.iter().map(|todo| todo.title.as_str()).collect();
+        assert_eq!(titles, vec!["先", "後"]);
+        assert!(page.items[0].position < page.items[1].position);
+    }
+}
@@ -0,0 +1,222 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use derive_new::new;
+use kernel::{
+    model::{
+        id::UserId,
+        list::{ListQuery, Page},
+        user::{
+            User, UserSort,
+            event::{CreateUser, DeleteUser, UpdateUserPassword},
+        },
+    },
+    repository::user::UserRepository,
+};
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    file::{FileStore, list::paginate, model::UserRecord},
+    repository::user::hash_password,
+};
+
+#[derive(new)]
+pub struct FileUserRepositoryImpl {
+    store: FileStore,
+}
+
+#[async_trait]
+impl UserRepository for FileUserRepositoryImpl {
+    async fn create(&self, event: CreateUser) -> AppResult<User> {
+        let password_hash = hash_password(&event.password)?;
+
+        self.store
+            .write(move |data| {
+                if data.users.iter().any(|user| user.email == event.email) {
+                    return Err(AppError::Conflict("email".into()));
+                }
+
+                let now = Utc::now();
+                let record = UserRecord {
+                    id: UserId::new(),
+                    name: event.name,
+                    email: event.email,
+                    password_hash,
+                    created_at: now,
+                    updated_at: now,
+                };
+                let user = User::from(&record);
+                data.users.push(record);
+                Ok(user)
+            })
+            .await
+    }
+
+    async fn find_by_id(&self, id: UserId) -> AppResult<Option<User>> {
+        self.store
+            .read(move |data| Ok(data.users.iter().find(|user| user.id == id).map(User::from)))
+            .await
+    }
+
+    async fn find_all(&self, query: ListQuery<UserSort>) -> AppResult<Page<User>> {
+        self.store
+            .read(move |data| {
+                paginate(
+                    data.users.iter().collect(),
+                    &query,
+                    |user| user.sort_value(query.sort),
+                    |user| user.id.raw(),
+                )
+            })
+            .await
+    }
+
+    async fn update_password(&self, event: UpdateUserPassword) -> AppResult<()> {
+        let new_password_hash = hash_password(&event.new_password)?;
+
+        self.store
+            .write(move |data| {
+                let user = data
+                    .users
+                    .iter_mut()
+                    .find(|user| user.id == event.user_id)
+                    .ok_or_else(|| {
+                        AppError::EntityNotFoundError("The user was not found".into())
+                    })?;
+
+                if !bcrypt::verify(&event.current_password, &user.password_hash)? {
+                    return Err(AppError::Unauthorized(
+                        "The current password is incorrect".into(),
+                    ));
+                }
+
+                user.password_hash = new_password_hash;
+                user.updated_at = Utc::now();
+                Ok(())
+            })
+            .await
+    }
+
+    async fn delete(&self, event: DeleteUser) -> AppResult<()> {
+        self.store
+            .write(move |data| {
+                let before = data.users.len();
+                data.users.retain(|user| user.id != event.id);
+                if data.users.len() == before {
+                    return Err(AppError::EntityNotFoundError(
+                        "No user has been deleted".into(),
+                    ));
+                }
+
+                // Postgres 側の ON DELETE CASCADE に合わせて関連データも削除する
+                data.remove_todos(|todo| todo.user_id == event.id);
+                data.completions
+                    .retain(|completion| completion.user_id != event.id);
+                data.tokens.retain(|token| token.user_id != event.id);
+                data.tags.retain(|tag| tag.user_id != event.id);
+                data.projects.retain(|project| project.user_id != event.id);
+                data.personal_access_tokens
+                    .retain(|token| token.user_id != event.id);
+                Ok(())
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::config::FileConfig;
+
+    fn repo_in(dir: &tempfile::TempDir) -> FileUserRepositoryImpl {
+        FileUserRepositoryImpl::new(FileStore::new(&FileConfig {
+            path: dir.path().join("todo.json"),
+        }))
+    }
+
+    fn create_user(email: &str) -> CreateUser {
+        CreateUser {
+            name: "Alice".to_string(),
+            email: email.to_string(),
+            password: "password123".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn ユーザが作成され取得できる() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let repo = repo_in(&dir);
+
+        let user = repo
+            .create(create_user("alice@example.com"))
+            .await
+            .expect("作成が成功する");
+
+        let found = repo
+            .find_by_id(user.id)
+            .await
+            .expect("取得が成功する")
+            .expect("ユーザが存在する");
+        assert_eq!(found.name, "Alice");
+        assert_eq!(found.email, "alice@example.com");
+
+        let all = repo.find_all(ListQuery::default()).await.expect("一覧取得");
+        assert_eq!(all.items.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn ユーザ作成は同一メールで失敗する() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let repo = repo_in(&dir);
+
+        repo.create(create_user("alice@example.com"))
+            .await
+            .expect("初回作成");
+        let err = repo
+            .create(create_user("alice@example.com"))
+            .await
+            .expect_err("重複は失敗");
+
+        assert!(matches!(err, AppError::Conflict(ref field) if field == "email"));
+    }
+
+    #[tokio::test]
+    async fn パスワード更新は現在のパスワードを検証する() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let repo = repo_in(&dir);
+        let user = repo
+            .create(create_user("alice@example.com"))
+            .await
+            .expect("作成が成功する");
+
+        let err = repo
+            .update_password(UpdateUserPassword {
+                user_id: user.id,
+                current_password: "wrong-password".to_string(),
+                new_password: "new-password456".to_string(),
+            })
+            .await
+            .expect_err("不一致は失敗する");
+        assert!(matches!(err, AppError::Unauthorized(_)));
+
+        repo.update_password(UpdateUserPassword {
+            user_id: user.id,
+            current_password: "password123".to_string(),
+            new_password: "new-password456".to_string(),
+        })
+        .await
+        .expect("更新が成功する");
+    }
+
+    #[tokio::test]
+    async fn 存在しないユーザは削除できない() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let repo = repo_in(&dir);
+
+        let err = repo
+            .delete(DeleteUser { id: UserId::new() })
+            .await
+            .expect_err("存在しないため失敗する");
+
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+    }
+}
@@ -1,3 +1,5 @@
 pub mod database;
+pub mod digest;
+pub mod file;
 pub mod redis;
 pub mod repository;
@@ -1,7 +1,10 @@
-use redis::{AsyncCommands, Client};
+use redis::{AsyncCommands, Client, aio::MultiplexedConnection};
 use shared::{config::RedisConfig, error::AppResult};
 
-use crate::redis::model::{RedisKey, RedisValue};
+use crate::{
+    digest::sha256_hex,
+    redis::model::{RedisKey, RedisValue},
+};
 
 pub mod model;
 
@@ -15,27 +18,221 @@ impl RedisClient {
         Ok(Self { client })
     }
 
+    // Redis 上のキー名。トークンを含むキーはダイジェストに置き換え、Redis を読めてもトークンが分からないようにする
+    fn key<T: RedisKey>(key: &T) -> String {
+        if T::HASHED {
+            format!("digest:{}", sha256_hex(&key.inner()))
+        } else {
+            key.inner()
+        }
+    }
+
+    // ダイジェストにする前に保存されたキーを、残りの期限ごとダイジェストのキーへ移して値を返す
+    async fn migrate_legacy_key<T: RedisKey>(
+        conn: &mut MultiplexedConnection,
+        key: &T,
+    ) -> AppResult<Option<String>> {
+        let legacy_key = key.inner();
+        let ttl: i64 = conn.ttl(&legacy_key).await?;
+        let value: Option<String> = conn.get_del(&legacy_key).await?;
+        if let Some(value) = &value {
+            match u64::try_from(ttl) {
+                Ok(ttl) if ttl > 0 => conn.set_ex::<_, _, ()>(Self::key(key), value, ttl).await?,
+                _ => conn.set::<_, _, ()>(Self::key(key), value).await?,
+            }
+        }
+        Ok(value)
+    }
+
     pub async fn set_ex<T: RedisKey>(&self, key: &T, value: &T::Value, ttl: u64) -> AppResult<()> {
         let mut conn = self.client.get_multiplexed_async_connection().await?;
-        let _: () = conn.set_ex(key.inner(), value.inner(), ttl).await?;
+        let _: () = conn.set_ex(Self::key(key), value.inner(), ttl).await?;
         Ok(())
     }
 
     pub async fn get<T: RedisKey>(&self, key: &T) -> AppResult<Option<T::Value>> {
         let mut conn = self.client.get_multiplexed_async_connection().await?;
-        let result: Option<String> = conn.get(key.inner()).await?;
+        let mut result: Option<String> = conn.get(Self::key(key)).await?;
+        if result.is_none() && T::HASHED {
+            result = Self::migrate_legacy_key(&mut conn, key).await?;
+        }
+        result.map(T::Value::try_from).transpose()
+    }
+
+    // 取得と削除を一度に行う。同じキーを同時に取り出せるのは 1 つのリクエストだけになる
+    pub async fn get_delete<T: RedisKey>(&self, key: &T) -> AppResult<Option<T::Value>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut result: Option<String> = conn.get_del(Self::key(key)).await?;
+        if result.is_none() && T::HASHED {
+            result = conn.get_del(key.inner()).await?;
+        }
         result.map(T::Value::try_from).transpose()
     }
 
     pub async fn ttl<T: RedisKey>(&self, key: &T) -> AppResult<i64> {
         let mut conn = self.client.get_multiplexed_async_connection().await?;
-        let ttl: i64 = conn.ttl(key.inner()).await?;
+        let ttl: i64 = conn.ttl(Self::key(key)).await?;
         Ok(ttl)
     }
 
+    pub async fn sadd_ex<T: RedisKey>(
+        &self,
+        key: &T,
+        member: &T::Value,
+        ttl: u64,
+    ) -> AppResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let _: () = redis::pipe()
+            .atomic()
+            .sadd(Self::key(key), member.inner())
+            .ignore()
+            .expire(Self::key(key), ttl as i64)
+            .ignore()
+            .query_async(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn smembers<T: RedisKey>(&self, key: &T) -> AppResult<Vec<T::Value>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let members: Vec<String> = conn.smembers(Self::key(key)).await?;
+        members.into_iter().map(T::Value::try_from).collect()
+    }
+
+    pub async fn srem<T: RedisKey>(&self, key: &T, member: &T::Value) -> AppResult<i64> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let removed_count: i64 = conn.srem(Self::key(key), member.inner()).await?;
+        Ok(removed_count)
+    }
+
     pub async fn delete<T: RedisKey>(&self, key: &T) -> AppResult<i64> {
         let mut conn = self.client.get_multiplexed_async_connection().await?;
-        let deleted_count: i64 = conn.del(key.inner()).await?;
+        // ダイジェストにする前に保存されたキーが残っていれば、あわせて削除する
+        let keys = if T::HASHED {
+            vec![Self::key(key), key.inner()]
+        } else {
+            vec![Self::key(key)]
+        };
+        let deleted_count: i64 = conn.del(keys).await?;
         Ok(deleted_count)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::{config::AppConfig, error::AppError};
+
+    // トークンを含むキーに見立てたテスト用のキー
+    struct SecretKey(String);
+
+    struct PlainValue(String);
+
+    impl RedisKey for SecretKey {
+        type Value = PlainValue;
+        const HASHED: bool = true;
+
+        fn inner(&self) -> String {
+            format!("test_secret:{}", self.0)
+        }
+    }
+
+    impl RedisValue for PlainValue {
+        fn inner(&self) -> String {
+            self.0.clone()
+        }
+    }
+
+    impl TryFrom<String> for PlainValue {
+        type Error = AppError;
+
+        fn try_from(s: String) -> AppResult<Self> {
+            Ok(Self(s))
+        }
+    }
+
+    fn client() -> RedisClient {
+        let cfg = AppConfig::new().expect("REDIS_* 環境変数が必要");
+        RedisClient::new(&cfg.redis).expect("Redis接続が成功する")
+    }
+
+    fn secret_key() -> SecretKey {
+        SecretKey(uuid::Uuid::new_v4().simple().to_string())
+    }
+
+    async fn raw_exists(client: &RedisClient, key: String) -> bool {
+        let mut conn = client
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .expect("接続");
+        conn.exists(key).await.expect("存在確認")
+    }
+
+    #[tokio::test]
+    async fn トークンを含むキーはダイジェストで保存して透過的に取得できる() {
+        let client = client();
+        let key = secret_key();
+
+        client
+            .set_ex(&key, &PlainValue("value".to_string()), 60)
+            .await
+            .expect("保存が成功する");
+
+        assert!(!raw_exists(&client, key.inner()).await);
+        assert!(raw_exists(&client, RedisClient::key(&key)).await);
+        assert!(!RedisClient::key(&key).contains(&key.0));
+        let value = client.get(&key).await.expect("取得が成功する");
+        assert_eq!(value.map(|value| value.0), Some("value".to_string()));
+
+        assert_eq!(client.delete(&key).await.expect("削除が成功する"), 1);
+        assert!(client.get(&key).await.expect("取得が成功する").is_none());
+    }
+
+    #[tokio::test]
+    async fn ダイジェスト導入前のキーは読んだときに期限ごと移される() {
+        let client = client();
+        let key = secret_key();
+        let mut conn = client
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .expect("接続");
+        let _: () = conn
+            .set_ex(key.inner(), "legacy", 60)
+            .await
+            .expect("旧形式での保存");
+
+        let value = client.get(&key).await.expect("取得が成功する");
+
+        assert_eq!(value.map(|value| value.0), Some("legacy".to_string()));
+        assert!(!raw_exists(&client, key.inner()).await);
+        let ttl = client.ttl(&key).await.expect("ttl取得");
+        assert!(ttl > 0 && ttl <= 60);
+    }
+
+    #[tokio::test]
+    async fn ダイジェスト導入前のキーも取り出しと削除ができる() {
+        let client = client();
+        let mut conn = client
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .expect("接続");
+        let taken = secret_key();
+        let deleted = secret_key();
+        for key in [&taken, &deleted] {
+            let _: () = conn
+                .set_ex(key.inner(), "legacy", 60)
+                .await
+                .expect("旧形式での保存");
+        }
+
+        let value = client.get_delete(&taken).await.expect("取り出しが成功する");
+        assert_eq!(value.map(|value| value.0), Some("legacy".to_string()));
+        assert!(!raw_exists(&client, taken.inner()).await);
+
+        assert_eq!(client.delete(&deleted).await.expect("削除が成功する"), 1);
+        assert!(!raw_exists(&client, deleted.inner()).await);
+    }
+}
@@ -1,40 +1,205 @@
+use chrono::{DateTime, Utc};
 use kernel::model::{
-    auth::{AccessToken, event::StoreToken},
-    id::UserId,
+    auth::{AccessTokenId, ClientInfo, RefreshToken, Session, event::StoreToken},
+    id::{SessionId, UserId},
 };
+use serde::{Deserialize, Serialize};
 use shared::error::{AppError, AppResult};
 use std::str::FromStr;
 
-use crate::redis::model::{RedisKey, RedisValue};
+use crate::{
+    digest::sha256_hex,
+    redis::model::{RedisKey, RedisValue},
+};
 
-pub struct AuthorizationKey(AccessToken);
+// 許可リストのキー。JWT の jti から引く
+pub struct AuthorizationKey(AccessTokenId);
 
 pub struct AuthorizationUserId(UserId);
 
+impl AuthorizationUserId {
+    pub fn into_inner(self) -> UserId {
+        self.0
+    }
+}
+
+// ユーザごとに発行済みのアクセストークンを束ねる集合
+pub struct UserTokensKey(UserId);
+
+pub struct UserToken(AccessTokenId);
+
+// ログインごとに作る、リフレッシュトークンの系列の識別子。セッションの ID として公開する
+#[derive(Clone, Copy)]
+pub struct RefreshFamilyId(SessionId);
+
+impl RefreshFamilyId {
+    pub fn into_inner(self) -> SessionId {
+        self.0
+    }
+}
+
+// リフレッシュトークンから系列を引く。入れ替え済みのトークンも期限まで残し、再利用を検出する
+pub struct RefreshTokenKey(RefreshToken);
+
+pub struct RefreshFamilyKey(RefreshFamilyId);
+
+// 系列のうち現在有効なリフレッシュトークンのダイジェストと、それと組で発行したアクセストークン。
+// 端末の情報と日時はセッション一覧に使う（導入前に作られた系列にはないため省略できる）
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshFamily {
+    pub user_id: UserId,
+    #[serde(default)]
+    refresh_token_digest: String,
+    // ダイジェストを保存する前に作られた系列は、トークンをそのまま持っている
+    #[serde(default, rename = "refreshToken", skip_serializing)]
+    legacy_refresh_token: Option<String>,
+    access_token_id: String,
+    #[serde(default)]
+    user_agent: Option<String>,
+    #[serde(default)]
+    ip_address: Option<String>,
+    #[serde(default = "Utc::now")]
+    created_at: DateTime<Utc>,
+    #[serde(default = "Utc::now")]
+    last_used_at: DateTime<Utc>,
+}
+
+impl RefreshFamily {
+    pub fn new(
+        user_id: UserId,
+        refresh_token: RefreshToken,
+        access_token_id: AccessTokenId,
+        client: ClientInfo,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            user_id,
+            refresh_token_digest: sha256_hex(&refresh_token.0),
+            legacy_refresh_token: None,
+            access_token_id: access_token_id.0,
+            user_agent: client.user_agent,
+            ip_address: client.ip_address,
+            created_at: now,
+            last_used_at: now,
+        }
+    }
+
+    // 入れ替え後の系列。作成日時は引き継ぎ、端末の情報は最後に使われたときのものにする
+    pub fn rotate(
+        self,
+        refresh_token: RefreshToken,
+        access_token_id: AccessTokenId,
+        client: ClientInfo,
+    ) -> Self {
+        Self {
+            created_at: self.created_at,
+            ..Self::new(self.user_id, refresh_token, access_token_id, client)
+        }
+    }
+
+    pub fn into_session(self, id: RefreshFamilyId) -> Session {
+        Session {
+            id: id.into_inner(),
+            access_token_id: AccessTokenId(self.access_token_id),
+            client: ClientInfo {
+                user_agent: self.user_agent,
+                ip_address: self.ip_address,
+            },
+            created_at: self.created_at,
+            last_used_at: self.last_used_at,
+        }
+    }
+
+    // 系列のうち現在有効なリフレッシュトークンかどうか
+    pub fn is_current(&self, refresh_token: &RefreshToken) -> bool {
+        match &self.legacy_refresh_token {
+            Some(legacy) => legacy == &refresh_token.0,
+            None => self.refresh_token_digest == sha256_hex(&refresh_token.0),
+        }
+    }
+
+    pub fn access_token_id(&self) -> AccessTokenId {
+        AccessTokenId(self.access_token_id.clone())
+    }
+}
+
+// ユーザごとの系列を束ねる集合。パスワード変更やログアウトで系列を失効させるのに使う
+pub struct UserRefreshFamiliesKey(UserId);
+
 pub fn from(event: StoreToken) -> (AuthorizationKey, AuthorizationUserId) {
     (
-        AuthorizationKey(event.access_token),
+        AuthorizationKey(event.access_token_id),
         AuthorizationUserId(event.user_id),
     )
 }
 
-impl From<AuthorizationKey> for AccessToken {
+impl From<AuthorizationKey> for AccessTokenId {
     fn from(key: AuthorizationKey) -> Self {
         key.0
     }
 }
 
-impl From<AccessToken> for AuthorizationKey {
-    fn from(token: AccessToken) -> Self {
+impl From<AccessTokenId> for AuthorizationKey {
+    fn from(token: AccessTokenId) -> Self {
         Self(token)
     }
 }
 
+impl From<UserId> for AuthorizationUserId {
+    fn from(user_id: UserId) -> Self {
+        Self(user_id)
+    }
+}
+
+impl From<UserId> for UserTokensKey {
+    fn from(user_id: UserId) -> Self {
+        Self(user_id)
+    }
+}
+
+impl From<AccessTokenId> for UserToken {
+    fn from(token: AccessTokenId) -> Self {
+        Self(token)
+    }
+}
+
+impl From<UserToken> for AccessTokenId {
+    fn from(token: UserToken) -> Self {
+        token.0
+    }
+}
+
+impl From<RefreshToken> for RefreshTokenKey {
+    fn from(token: RefreshToken) -> Self {
+        Self(token)
+    }
+}
+
+impl From<SessionId> for RefreshFamilyId {
+    fn from(id: SessionId) -> Self {
+        Self(id)
+    }
+}
+
+impl From<RefreshFamilyId> for RefreshFamilyKey {
+    fn from(id: RefreshFamilyId) -> Self {
+        Self(id)
+    }
+}
+
+impl From<UserId> for UserRefreshFamiliesKey {
+    fn from(user_id: UserId) -> Self {
+        Self(user_id)
+    }
+}
+
 impl RedisKey for AuthorizationKey {
     type Value = AuthorizationUserId;
+    const HASHED: bool = true;
 
     fn inner(&self) -> String {
-        self.0 .0.clone()
+        self.0.0.clone()
     }
 }
 
@@ -53,3 +218,81 @@ impl TryFrom<String> for AuthorizationUserId {
         })?))
     }
 }
+
+impl RedisKey for UserTokensKey {
+    type Value = UserToken;
+
+    fn inner(&self) -> String {
+        format!("user_tokens:{}", self.0)
+    }
+}
+
+impl RedisValue for UserToken {
+    fn inner(&self) -> String {
+        self.0.0.clone()
+    }
+}
+
+impl TryFrom<String> for UserToken {
+    type Error = AppError;
+
+    fn try_from(s: String) -> AppResult<Self> {
+        Ok(Self(AccessTokenId(s)))
+    }
+}
+
+impl RedisKey for RefreshTokenKey {
+    type Value = RefreshFamilyId;
+    const HASHED: bool = true;
+
+    fn inner(&self) -> String {
+        format!("refresh_token:{}", self.0.0)
+    }
+}
+
+impl RedisValue for RefreshFamilyId {
+    fn inner(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+impl TryFrom<String> for RefreshFamilyId {
+    type Error = AppError;
+
+    fn try_from(s: String) -> AppResult<Self> {
+        Ok(Self(SessionId::from_str(&s).map_err(|e| {
+            AppError::ConversionEntityError(e.to_string())
+        })?))
+    }
+}
+
+impl RedisKey for RefreshFamilyKey {
+    type Value = RefreshFamily;
+
+    fn inner(&self) -> String {
+        format!("refresh_family:{}", self.0.0)
+    }
+}
+
+impl RedisValue for RefreshFamily {
+    fn inner(&self) -> String {
+        // 文字列と UUID、日時だけなので直列化は失敗しない
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+impl TryFrom<String> for RefreshFamily {
+    type Error = AppError;
+
+    fn try_from(s: String) -> AppResult<Self> {
+        serde_json::from_str(&s).map_err(|e| AppError::ConversionEntityError(e.to_string()))
+    }
+}
+
+impl RedisKey for UserRefreshFamiliesKey {
+    type Value = RefreshFamilyId;
+
+    fn inner(&self) -> String {
+        format!("user_refresh_families:{}", self.0)
+    }
+}
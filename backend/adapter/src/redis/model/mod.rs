@@ -4,6 +4,8 @@ pub mod auth;
 
 pub trait RedisKey {
     type Value: RedisValue + TryFrom<String, Error = AppError>;
+    // トークンそのものを含むキーは true にする。Redis にはキーのダイジェストだけを置く
+    const HASHED: bool = false;
     fn inner(&self) -> String;
 }
 
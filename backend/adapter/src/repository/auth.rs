@@ -8,11 +8,20 @@ use crate::{
     database::{ConnectionPool, model::auth::UserCredentialRow},
     redis::{
         RedisClient,
-        model::auth::{AuthorizationKey, from},
+        model::auth::{
+            AuthorizationKey, AuthorizationUserId, RefreshFamily, RefreshFamilyId,
+            RefreshFamilyKey, RefreshTokenKey, UserRefreshFamiliesKey, UserToken, UserTokensKey,
+        },
     },
 };
 use kernel::{
-    model::auth::{AccessToken, UserCredential, event::StoreToken},
+    model::{
+        auth::{
+            AccessTokenId, RefreshToken, Session, UserCredential,
+            event::{DeleteSession, RotateToken, StoreToken},
+        },
+        id::{SessionId, UserId},
+    },
     repository::auth::AuthRepository,
 };
 
@@ -21,6 +30,111 @@ pub struct AuthRepositoryImpl {
     db: ConnectionPool,
     kv_store: Arc<RedisClient>,
     ttl: u64,
+    refresh_ttl: u64,
+}
+
+impl AuthRepositoryImpl {
+    async fn store_access_token(
+        &self,
+        user_id: UserId,
+        access_token_id: AccessTokenId,
+    ) -> AppResult<()> {
+        let tokens_key = UserTokensKey::from(user_id);
+        let token = UserToken::from(access_token_id.clone());
+        let key = AuthorizationKey::from(access_token_id);
+        let value = AuthorizationUserId::from(user_id);
+        self.kv_store.set_ex(&key, &value, self.ttl).await?;
+        self.kv_store.sadd_ex(&tokens_key, &token, self.ttl).await?;
+        Ok(())
+    }
+
+    async fn delete_access_token(
+        &self,
+        user_id: UserId,
+        access_token_id: AccessTokenId,
+    ) -> AppResult<i64> {
+        let key: AuthorizationKey = access_token_id.clone().into();
+        let deleted_count = self.kv_store.delete(&key).await?;
+        self.kv_store
+            .srem(
+                &UserTokensKey::from(user_id),
+                &UserToken::from(access_token_id),
+            )
+            .await?;
+        Ok(deleted_count)
+    }
+
+    async fn store_family(
+        &self,
+        family_id: RefreshFamilyId,
+        refresh_token: RefreshToken,
+        family: RefreshFamily,
+    ) -> AppResult<()> {
+        self.kv_store
+            .set_ex(
+                &RefreshTokenKey::from(refresh_token),
+                &family_id,
+                self.refresh_ttl,
+            )
+            .await?;
+        self.kv_store
+            .sadd_ex(
+                &UserRefreshFamiliesKey::from(family.user_id),
+                &family_id,
+                self.refresh_ttl,
+            )
+            .await?;
+        self.kv_store
+            .set_ex(
+                &RefreshFamilyKey::from(family_id),
+                &family,
+                self.refresh_ttl,
+            )
+            .await
+    }
+
+    // 系列と、それと組で発行したアクセストークンを失効させる
+    async fn revoke_family(
+        &self,
+        user_id: UserId,
+        family_id: RefreshFamilyId,
+        family: Option<RefreshFamily>,
+    ) -> AppResult<()> {
+        if let Some(family) = family {
+            self.delete_access_token(user_id, family.access_token_id())
+                .await?;
+        }
+        self.kv_store
+            .delete(&RefreshFamilyKey::from(family_id))
+            .await?;
+        self.kv_store
+            .srem(&UserRefreshFamiliesKey::from(user_id), &family_id)
+            .await?;
+        Ok(())
+    }
+
+    // ユーザの系列のうち、アクセストークンが条件に合うものを失効させる
+    async fn revoke_families(
+        &self,
+        user_id: UserId,
+        revoke: impl Fn(&AccessTokenId) -> bool,
+    ) -> AppResult<()> {
+        let families_key = UserRefreshFamiliesKey::from(user_id);
+        for family_id in self.kv_store.smembers(&families_key).await? {
+            let family = self
+                .kv_store
+                .get(&RefreshFamilyKey::from(family_id))
+                .await?;
+            if family
+                .as_ref()
+                .is_some_and(|family| !revoke(&family.access_token_id()))
+            {
+                continue;
+            }
+            self.revoke_family(user_id, family_id, family).await?;
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -47,19 +161,136 @@ impl AuthRepository for AuthRepositoryImpl {
         }
     }
 
-    async fn store_token(&self, event: StoreToken) -> AppResult<AccessToken> {
-        let (key, value) = from(event);
-        self.kv_store.set_ex(&key, &value, self.ttl).await?;
-        Ok(key.into())
+    async fn fetch_user_id_from_token(
+        &self,
+        access_token_id: &AccessTokenId,
+    ) -> AppResult<Option<UserId>> {
+        let key: AuthorizationKey = access_token_id.clone().into();
+        let value = self.kv_store.get(&key).await?;
+        Ok(value.map(|value| value.into_inner()))
     }
 
-    async fn delete_token(&self, access_token: AccessToken) -> AppResult<()> {
-        let key: AuthorizationKey = access_token.into();
-        let deleted_count = self.kv_store.delete(&key).await?;
+    async fn store_token(&self, event: StoreToken) -> AppResult<()> {
+        self.store_access_token(event.user_id, event.access_token_id.clone())
+            .await?;
+        let family = RefreshFamily::new(
+            event.user_id,
+            event.refresh_token.clone(),
+            event.access_token_id.clone(),
+            event.client,
+        );
+        self.store_family(SessionId::new().into(), event.refresh_token, family)
+            .await
+    }
+
+    async fn rotate_token(&self, event: RotateToken) -> AppResult<UserId> {
+        let invalid = || AppError::Unauthorized("Invalid refresh token".into());
+        let family_id = self
+            .kv_store
+            .get(&RefreshTokenKey::from(event.refresh_token.clone()))
+            .await?
+            .ok_or_else(invalid)?;
+        // 取り出しと削除を一度に行い、同じトークンでの同時の入れ替えを 1 つだけ通す
+        let family = self
+            .kv_store
+            .get_delete(&RefreshFamilyKey::from(family_id))
+            .await?
+            .ok_or_else(invalid)?;
+        self.delete_access_token(family.user_id, family.access_token_id())
+            .await?;
+        if !family.is_current(&event.refresh_token) {
+            // 入れ替え済みのトークンが再び使われたら漏洩とみなし、系列ごと失効させたままにする
+            self.kv_store
+                .srem(&UserRefreshFamiliesKey::from(family.user_id), &family_id)
+                .await?;
+            return Err(AppError::Unauthorized(
+                "The refresh token has already been used".into(),
+            ));
+        }
+
+        self.store_access_token(family.user_id, event.access_token_id.clone())
+            .await?;
+        let user_id = family.user_id;
+        let rotated = family.rotate(
+            event.new_refresh_token.clone(),
+            event.access_token_id,
+            event.client,
+        );
+        self.store_family(family_id, event.new_refresh_token, rotated)
+            .await?;
+        Ok(user_id)
+    }
+
+    async fn delete_token(&self, access_token_id: AccessTokenId) -> AppResult<()> {
+        let key: AuthorizationKey = access_token_id.clone().into();
+        let user_id = self
+            .kv_store
+            .get(&key)
+            .await?
+            .map(|value| value.into_inner())
+            .ok_or_else(|| AppError::Unauthorized("Invalid token".into()))?;
+        let deleted_count = self
+            .delete_access_token(user_id, access_token_id.clone())
+            .await?;
         if deleted_count == 0 {
             return Err(AppError::Unauthorized("Invalid token".into()));
         }
-        Ok(())
+        self.revoke_families(user_id, |token| token == &access_token_id)
+            .await
+    }
+
+    async fn find_sessions(&self, user_id: UserId) -> AppResult<Vec<Session>> {
+        let families_key = UserRefreshFamiliesKey::from(user_id);
+        let mut sessions = Vec::new();
+        for family_id in self.kv_store.smembers(&families_key).await? {
+            match self
+                .kv_store
+                .get(&RefreshFamilyKey::from(family_id))
+                .await?
+            {
+                Some(family) => sessions.push(family.into_session(family_id)),
+                // 期限切れで消えた系列は索引からも外す
+                None => {
+                    self.kv_store.srem(&families_key, &family_id).await?;
+                }
+            }
+        }
+        sessions.sort_by(|a, b| b.last_used_at.cmp(&a.last_used_at));
+        Ok(sessions)
+    }
+
+    async fn delete_session(&self, event: DeleteSession) -> AppResult<()> {
+        let family_id = RefreshFamilyId::from(event.id);
+        let not_found = || AppError::EntityNotFoundError("The session was not found".into());
+        // 他のユーザのセッションは存在しないものとして扱う
+        let family = self
+            .kv_store
+            .get(&RefreshFamilyKey::from(family_id))
+            .await?
+            .filter(|family| family.user_id == event.user_id)
+            .ok_or_else(not_found)?;
+        self.revoke_family(event.user_id, family_id, Some(family))
+            .await
+    }
+
+    async fn delete_other_tokens(&self, user_id: UserId, keep: &AccessTokenId) -> AppResult<()> {
+        let tokens_key = UserTokensKey::from(user_id);
+        for token in self.kv_store.smembers(&tokens_key).await? {
+            let token: AccessTokenId = token.into();
+            if &token == keep {
+                continue;
+            }
+            self.delete_access_token(user_id, token).await?;
+        }
+        self.revoke_families(user_id, |token| token != keep).await
+    }
+
+    fn token_ttl(&self) -> u64 {
+        self.ttl
+    }
+
+    fn refresh_token_ttl(&self) -> u64 {
+        self.refresh_ttl
     }
 }
 
@@ -67,8 +298,9 @@ impl AuthRepository for AuthRepositoryImpl {
 mod tests {
     use super::*;
     use crate::database::connect_database_with;
-    use crate::redis::model::RedisValue;
+    use crate::redis::model::{RedisValue, auth::from};
     use crate::repository::user::UserRepositoryImpl;
+    use kernel::model::auth::{ClientInfo, RefreshToken};
     use kernel::{
         model::{id::UserId, user::event::CreateUser},
         repository::user::UserRepository,
@@ -83,7 +315,8 @@ mod tests {
         let pool = connect_database_with(&cfg.database);
         let kv_store = Arc::new(RedisClient::new(&cfg.redis).expect("Redis接続が成功する"));
         let user_repo = UserRepositoryImpl::new(pool.clone());
-        let auth_repo = AuthRepositoryImpl::new(pool.clone(), kv_store, cfg.auth.ttl);
+        let auth_repo =
+            AuthRepositoryImpl::new(pool.clone(), kv_store, cfg.auth.ttl, cfg.auth.refresh_ttl);
 
         let unique = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -121,7 +354,8 @@ mod tests {
         let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
         let pool = connect_database_with(&cfg.database);
         let kv_store = Arc::new(RedisClient::new(&cfg.redis).expect("Redis接続が成功する"));
-        let auth_repo = AuthRepositoryImpl::new(pool.clone(), kv_store, cfg.auth.ttl);
+        let auth_repo =
+            AuthRepositoryImpl::new(pool.clone(), kv_store, cfg.auth.ttl, cfg.auth.refresh_ttl);
 
         let result = auth_repo
             .find_by_email("not-found@example.com".to_string())
@@ -136,26 +370,28 @@ mod tests {
         let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
         let pool = connect_database_with(&cfg.database);
         let kv_store = Arc::new(RedisClient::new(&cfg.redis).expect("Redis接続が成功する"));
-        let auth_repo = AuthRepositoryImpl::new(pool, kv_store, cfg.auth.ttl);
+        let auth_repo = AuthRepositoryImpl::new(pool, kv_store, cfg.auth.ttl, cfg.auth.refresh_ttl);
 
         let unique = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .expect("timestamp")
             .as_nanos();
         let user_id = UserId::new();
-        let token = AccessToken(format!("test-token-{}", unique));
+        let token = AccessTokenId(format!("test-token-{}", unique));
         let event = StoreToken {
             user_id,
-            access_token: token.clone(),
+            access_token_id: token.clone(),
+            refresh_token: RefreshToken::generate(),
+            client: ClientInfo::default(),
         };
 
-        let stored = auth_repo.store_token(event).await.expect("保存が成功する");
-
-        assert_eq!(stored, token);
+        auth_repo.store_token(event).await.expect("保存が成功する");
 
         let (key, _value) = from(StoreToken {
             user_id,
-            access_token: token.clone(),
+            access_token_id: token.clone(),
+            refresh_token: RefreshToken::generate(),
+            client: ClientInfo::default(),
         });
         let value = auth_repo.kv_store.get(&key).await.expect("token取得");
         let ttl = auth_repo.kv_store.ttl(&key).await.expect("ttl取得");
@@ -166,29 +402,65 @@ mod tests {
         assert!(ttl <= cfg.auth.ttl as i64);
     }
 
+    #[tokio::test]
+    async fn アクセストークンからユーザidを取得できる() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let kv_store = Arc::new(RedisClient::new(&cfg.redis).expect("Redis接続が成功する"));
+        let auth_repo = AuthRepositoryImpl::new(pool, kv_store, cfg.auth.ttl, cfg.auth.refresh_ttl);
+
+        let user_id = UserId::new();
+        let token = AccessTokenId::generate();
+        auth_repo
+            .store_token(StoreToken {
+                user_id,
+                access_token_id: token.clone(),
+                refresh_token: RefreshToken::generate(),
+                client: ClientInfo::default(),
+            })
+            .await
+            .expect("保存が成功する");
+
+        let found = auth_repo
+            .fetch_user_id_from_token(&token)
+            .await
+            .expect("取得が成功する");
+        assert_eq!(found, Some(user_id));
+
+        let missing = auth_repo
+            .fetch_user_id_from_token(&AccessTokenId::generate())
+            .await
+            .expect("取得が成功する");
+        assert!(missing.is_none());
+    }
+
     #[tokio::test]
     async fn アクセストークンは削除できる() {
         let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
         let pool = connect_database_with(&cfg.database);
         let kv_store = Arc::new(RedisClient::new(&cfg.redis).expect("Redis接続が成功する"));
-        let auth_repo = AuthRepositoryImpl::new(pool, kv_store, cfg.auth.ttl);
+        let auth_repo = AuthRepositoryImpl::new(pool, kv_store, cfg.auth.ttl, cfg.auth.refresh_ttl);
 
         let unique = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .expect("timestamp")
             .as_nanos();
         let user_id = UserId::new();
-        let token = AccessToken(format!("test-token-{}", unique));
+        let token = AccessTokenId(format!("test-token-{}", unique));
         let event = StoreToken {
             user_id,
-            access_token: token.clone(),
+            access_token_id: token.clone(),
+            refresh_token: RefreshToken::generate(),
+            client: ClientInfo::default(),
         };
 
         auth_repo.store_token(event).await.expect("保存が成功する");
 
         let (key, _value) = from(StoreToken {
             user_id,
-            access_token: token.clone(),
+            access_token_id: token.clone(),
+            refresh_token: RefreshToken::generate(),
+            client: ClientInfo::default(),
         });
         let stored = auth_repo.kv_store.get(&key).await.expect("token取得");
         assert!(stored.is_some());
@@ -205,18 +477,57 @@ mod tests {
         assert_eq!(ttl, -2);
     }
 
+    #[tokio::test]
+    async fn 指定したトークン以外のアクセストークンは削除される() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let kv_store = Arc::new(RedisClient::new(&cfg.redis).expect("Redis接続が成功する"));
+        let auth_repo = AuthRepositoryImpl::new(pool, kv_store, cfg.auth.ttl, cfg.auth.refresh_ttl);
+
+        let user_id = UserId::new();
+        let keep = AccessTokenId::generate();
+        let other = AccessTokenId::generate();
+        for token in [keep.clone(), other.clone()] {
+            auth_repo
+                .store_token(StoreToken {
+                    user_id,
+                    access_token_id: token,
+                    refresh_token: RefreshToken::generate(),
+                    client: ClientInfo::default(),
+                })
+                .await
+                .expect("保存が成功する");
+        }
+
+        auth_repo
+            .delete_other_tokens(user_id, &keep)
+            .await
+            .expect("削除が成功する");
+
+        let kept = auth_repo
+            .fetch_user_id_from_token(&keep)
+            .await
+            .expect("取得が成功する");
+        let deleted = auth_repo
+            .fetch_user_id_from_token(&other)
+            .await
+            .expect("取得が成功する");
+        assert_eq!(kept, Some(user_id));
+        assert!(deleted.is_none());
+    }
+
     #[tokio::test]
     async fn 無効なアクセストークンは削除できない() {
         let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
         let pool = connect_database_with(&cfg.database);
         let kv_store = Arc::new(RedisClient::new(&cfg.redis).expect("Redis接続が成功する"));
-        let auth_repo = AuthRepositoryImpl::new(pool, kv_store, cfg.auth.ttl);
+        let auth_repo = AuthRepositoryImpl::new(pool, kv_store, cfg.auth.ttl, cfg.auth.refresh_ttl);
 
         let unique = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .expect("timestamp")
             .as_nanos();
-        let token = AccessToken(format!("test-token-{}", unique));
+        let token = AccessTokenId(format!("test-token-{}", unique));
 
         let err = auth_repo
             .delete_token(token)
@@ -225,4 +536,285 @@ mod tests {
 
         assert!(matches!(err, AppError::Unauthorized(_)));
     }
+
+    async fn login(
+        auth_repo: &AuthRepositoryImpl,
+        user_id: UserId,
+    ) -> (AccessTokenId, RefreshToken) {
+        let refresh_token = RefreshToken::generate();
+        let access_token_id = AccessTokenId::generate();
+        auth_repo
+            .store_token(StoreToken {
+                user_id,
+                access_token_id: access_token_id.clone(),
+                refresh_token: refresh_token.clone(),
+                client: ClientInfo::default(),
+            })
+            .await
+            .expect("保存が成功する");
+        (access_token_id, refresh_token)
+    }
+
+    async fn rotate(
+        auth_repo: &AuthRepositoryImpl,
+        refresh_token: &RefreshToken,
+    ) -> AppResult<(AccessTokenId, RefreshToken)> {
+        let access_token_id = AccessTokenId::generate();
+        let new_refresh_token = RefreshToken::generate();
+        auth_repo
+            .rotate_token(RotateToken {
+                refresh_token: refresh_token.clone(),
+                access_token_id: access_token_id.clone(),
+                new_refresh_token: new_refresh_token.clone(),
+                client: ClientInfo::default(),
+            })
+            .await?;
+        Ok((access_token_id, new_refresh_token))
+    }
+
+    #[tokio::test]
+    async fn リフレッシュトークンは使うたびに新しいトークンに入れ替わる() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let kv_store = Arc::new(RedisClient::new(&cfg.redis).expect("Redis接続が成功する"));
+        let auth_repo = AuthRepositoryImpl::new(pool, kv_store, cfg.auth.ttl, cfg.auth.refresh_ttl);
+
+        let user_id = UserId::new();
+        let (old_access, old_refresh) = login(&auth_repo, user_id).await;
+
+        let (new_access, new_refresh) = rotate(&auth_repo, &old_refresh)
+            .await
+            .expect("入れ替えが成功する");
+
+        assert_eq!(
+            auth_repo
+                .fetch_user_id_from_token(&new_access)
+                .await
+                .expect("取得"),
+            Some(user_id)
+        );
+        assert!(
+            auth_repo
+                .fetch_user_id_from_token(&old_access)
+                .await
+                .expect("取得")
+                .is_none()
+        );
+        rotate(&auth_repo, &new_refresh)
+            .await
+            .expect("新しいトークンでさらに入れ替えられる");
+    }
+
+    #[tokio::test]
+    async fn 使用済みのリフレッシュトークンを使うと系列ごと失効する() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let kv_store = Arc::new(RedisClient::new(&cfg.redis).expect("Redis接続が成功する"));
+        let auth_repo = AuthRepositoryImpl::new(pool, kv_store, cfg.auth.ttl, cfg.auth.refresh_ttl);
+
+        let user_id = UserId::new();
+        let (_, old_refresh) = login(&auth_repo, user_id).await;
+        let (new_access, new_refresh) = rotate(&auth_repo, &old_refresh)
+            .await
+            .expect("入れ替えが成功する");
+
+        let err = rotate(&auth_repo, &old_refresh)
+            .await
+            .expect_err("使用済みのトークンは失敗する");
+        assert!(matches!(err, AppError::Unauthorized(_)));
+
+        assert!(
+            auth_repo
+                .fetch_user_id_from_token(&new_access)
+                .await
+                .expect("取得")
+                .is_none()
+        );
+        let err = rotate(&auth_repo, &new_refresh)
+            .await
+            .expect_err("失効した系列のトークンは失敗する");
+        assert!(matches!(err, AppError::Unauthorized(_)));
+    }
+
+    #[tokio::test]
+    async fn ログアウトとパスワード変更でリフレッシュトークンも失効する() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let kv_store = Arc::new(RedisClient::new(&cfg.redis).expect("Redis接続が成功する"));
+        let auth_repo = AuthRepositoryImpl::new(pool, kv_store, cfg.auth.ttl, cfg.auth.refresh_ttl);
+
+        let user_id = UserId::new();
+        let (logged_out_access, logged_out_refresh) = login(&auth_repo, user_id).await;
+        let (keep_access, keep_refresh) = login(&auth_repo, user_id).await;
+        let (_, other_refresh) = login(&auth_repo, user_id).await;
+
+        auth_repo
+            .delete_token(logged_out_access)
+            .await
+            .expect("削除が成功する");
+        auth_repo
+            .delete_other_tokens(user_id, &keep_access)
+            .await
+            .expect("削除が成功する");
+
+        for refresh_token in [logged_out_refresh, other_refresh] {
+            let err = rotate(&auth_repo, &refresh_token)
+                .await
+                .expect_err("失効したトークンは失敗する");
+            assert!(matches!(err, AppError::Unauthorized(_)));
+        }
+        rotate(&auth_repo, &keep_refresh)
+            .await
+            .expect("残したセッションは入れ替えられる");
+    }
+
+    #[tokio::test]
+    async fn セッションは最後に使われた順に一覧でき個別に失効できる() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let kv_store = Arc::new(RedisClient::new(&cfg.redis).expect("Redis接続が成功する"));
+        let auth_repo = AuthRepositoryImpl::new(pool, kv_store, cfg.auth.ttl, cfg.auth.refresh_ttl);
+
+        let user_id = UserId::new();
+        let (first_access, first_refresh) = login(&auth_repo, user_id).await;
+        let (second_access, _) = login(&auth_repo, user_id).await;
+        let (rotated_access, _) = rotate(&auth_repo, &first_refresh)
+            .await
+            .expect("入れ替えが成功する");
+
+        let sessions = auth_repo
+            .find_sessions(user_id)
+            .await
+            .expect("取得が成功する");
+        let tokens: Vec<AccessTokenId> = sessions
+            .iter()
+            .map(|session| session.access_token_id.clone())
+            .collect();
+        assert_eq!(tokens, vec![rotated_access.clone(), second_access.clone()]);
+        assert!(sessions[0].created_at < sessions[0].last_used_at);
+        assert!(!tokens.contains(&first_access));
+
+        let err = auth_repo
+            .delete_session(DeleteSession {
+                id: sessions[0].id,
+                user_id: UserId::new(),
+            })
+            .await
+            .expect_err("他のユーザのセッションは失効できない");
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+
+        auth_repo
+            .delete_session(DeleteSession {
+                id: sessions[0].id,
+                user_id,
+            })
+            .await
+            .expect("失効が成功する");
+
+        assert!(
+            auth_repo
+                .fetch_user_id_from_token(&rotated_access)
+                .await
+                .expect("取得")
+                .is_none()
+        );
+        let sessions = auth_repo
+            .find_sessions(user_id)
+            .await
+            .expect("取得が成功する");
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].access_token_id, second_access);
+    }
+
+    // ダイジェスト導入前の形式でそのまま書き込むためのキー
+    struct LegacyKey(String);
+
+    struct LegacyValue(String);
+
+    impl crate::redis::model::RedisKey for LegacyKey {
+        type Value = LegacyValue;
+
+        fn inner(&self) -> String {
+            self.0.clone()
+        }
+    }
+
+    impl RedisValue for LegacyValue {
+        fn inner(&self) -> String {
+            self.0.clone()
+        }
+    }
+
+    impl TryFrom<String> for LegacyValue {
+        type Error = AppError;
+
+        fn try_from(s: String) -> AppResult<Self> {
+            Ok(Self(s))
+        }
+    }
+
+    #[tokio::test]
+    async fn ダイジェスト導入前に保存したトークンも使える() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let kv_store = Arc::new(RedisClient::new(&cfg.redis).expect("Redis接続が成功する"));
+        let auth_repo =
+            AuthRepositoryImpl::new(pool, kv_store.clone(), cfg.auth.ttl, cfg.auth.refresh_ttl);
+
+        let user_id = UserId::new();
+        let access_token_id = AccessTokenId::generate();
+        let refresh_token = RefreshToken::generate();
+        let family_id = SessionId::new();
+        let legacy = [
+            (access_token_id.0.clone(), user_id.to_string()),
+            (
+                format!("refresh_token:{}", refresh_token.0),
+                family_id.to_string(),
+            ),
+            (
+                format!("refresh_family:{}", family_id),
+                serde_json::json!({
+                    "userId": user_id,
+                    "refreshToken": refresh_token.0,
+                    "accessTokenId": access_token_id.0,
+                })
+                .to_string(),
+            ),
+        ];
+        for (key, value) in legacy {
+            kv_store
+                .set_ex(&LegacyKey(key), &LegacyValue(value), cfg.auth.ttl)
+                .await
+                .expect("旧形式での保存");
+        }
+
+        assert_eq!(
+            auth_repo
+                .fetch_user_id_from_token(&access_token_id)
+                .await
+                .expect("取得"),
+            Some(user_id)
+        );
+        let (new_access, _) = rotate(&auth_repo, &refresh_token)
+            .await
+            .expect("旧形式のリフレッシュトークンで入れ替えられる");
+        assert!(
+            auth_repo
+                .fetch_user_id_from_token(&access_token_id)
+                .await
+                .expect("取得")
+                .is_none()
+        );
+        assert_eq!(
+            auth_repo
+                .fetch_user_id_from_token(&new_access)
+                .await
+                .expect("取得"),
+            Some(user_id)
+        );
+        let err = rotate(&auth_repo, &refresh_token)
+            .await
+            .expect_err("使用済みのトークンは失敗する");
+        assert!(matches!(err, AppError::Unauthorized(_)));
+    }
 }
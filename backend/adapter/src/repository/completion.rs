@@ -0,0 +1,816 @@
+use crate::{
+    database::{ConnectionPool, map_sql_error, model::completion::CompletionRow},
+    repository::todo::{append_position, lock_positions},
+};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        completion::{
+            Completion, CompletionTodo,
+            event::{CreateCompletion, UpdateReopened},
+        },
+        id::{CompletionId, TodoId, UserId},
+        todo::recurrence::Recurrence,
+    },
+    repository::completion::CompletionRepository,
+};
+use shared::error::{AppError, AppResult};
+
+#[derive(new)]
+pub struct CompletionRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl CompletionRepository for CompletionRepositoryImpl {
+    async fn create(&self, event: CreateCompletion) -> AppResult<Completion> {
+        let mut tx = self.db.begin().await?;
+        // 繰り返しの次の回を末尾に置くことがあるので、行ロックより先に並び順のロックを取る
+        lock_positions(&mut tx, event.user_id).await?;
+
+        // 完了済みかどうかの判定と完了の記録の間に他の更新が割り込まないよう行をロックする
+        let todo = sqlx::query!(
+            r#"--sql
+                SELECT title, completed, due_at, recurrence
+                FROM todos
+                WHERE id = $1 AND user_id = $2
+                FOR UPDATE
+            "#,
+            event.todo_id as _,
+            event.user_id as _,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SqlExecuteError)?
+        .ok_or_else(|| AppError::EntityNotFoundError("The todo was not found".into()))?;
+
+        if todo.completed {
+            return Err(AppError::UnprocessableEntity(
+                "The todo has already been completed".into(),
+            ));
+        }
+
+        if !event.force {
+            let blocked = sqlx::query_scalar!(
+                r#"--sql
+                    SELECT EXISTS (
+                        SELECT 1
+                        FROM todo_dependencies
+                        JOIN todos ON todos.id = todo_dependencies.blocker_id
+                        WHERE todo_dependencies.todo_id = $1 AND todos.completed = FALSE
+                    ) AS "blocked!"
+                "#,
+                event.todo_id as _,
+            )
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(AppError::SqlExecuteError)?;
+
+            if blocked {
+                return Err(AppError::StateConflict(
+                    "The todo is blocked by open todos".into(),
+                ));
+            }
+        }
+
+        let completion_id = CompletionId::new();
+        let completed_at = sqlx::query_scalar!(
+            r#"--sql
+                INSERT INTO todo_completions (id, todo_id, user_id)
+                VALUES ($1, $2, $3)
+                RETURNING completed_at AS "completed_at!"
+            "#,
+            completion_id as _,
+            event.todo_id as _,
+            event.user_id as _,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(map_sql_error)?;
+
+        sqlx::query!(
+            r#"--sql
+                UPDATE todos SET completed = TRUE WHERE id = $1
+            "#,
+            event.todo_id as _,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        let next = todo
+            .recurrence
+            .as_deref()
+            .map(str::parse::<Recurrence>)
+            .transpose()?
+            .and_then(|rule| rule.next_occurrence(todo.due_at, completed_at));
+        if let Some((due_at, recurrence)) = next {
+            // 次の回は内容とタグを引き継いだ新しい todo として作り、手動の並び順では末尾に置く
+            let next_id = TodoId::new();
+            let position = append_position(&mut *tx, event.user_id).await?;
+            sqlx::query!(
+                r#"--sql
+                    INSERT INTO todos (
+                        id, user_id, project_id, parent_id, title, description, priority, due_at,
+                        recurrence, position
+                    )
+                    SELECT
+                        $1::UUID, user_id, project_id, parent_id, title, description, priority,
+                        $2::TIMESTAMPTZ, $3::TEXT, $4::TEXT
+                    FROM todos
+                    WHERE id = $5
+                "#,
+                next_id as _,
+                due_at,
+                recurrence.to_string(),
+                position.to_string(),
+                event.todo_id as _,
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(map_sql_error)?;
+
+            sqlx::query!(
+                r#"--sql
+                    INSERT INTO todo_tags (todo_id, tag_id)
+                    SELECT $1::UUID, tag_id FROM todo_tags WHERE todo_id = $2
+                "#,
+                next_id as _,
+                event.todo_id as _,
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(map_sql_error)?;
+
+            // 繰り返しの規則は次の回へ移す。再開してから完了し直しても次の回を重ねて作らない
+            sqlx::query!(
+                r#"--sql
+                    UPDATE todos SET recurrence = NULL WHERE id = $1
+                "#,
+                event.todo_id as _,
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::SqlExecuteError)?;
+        }
+
+        if event.include_subtasks {
+            // 未完了の子孫にも、親と同じように完了の記録を残す
+            let subtask_ids = sqlx::query_scalar!(
+                r#"--sql
+                    WITH RECURSIVE descendants AS (
+                        SELECT id FROM todos WHERE parent_id = $1
+                        UNION
+                        SELECT todos.id
+                        FROM todos
+                        JOIN descendants ON todos.parent_id = descendants.id
+                    )
+                    SELECT id
+                    FROM todos
+                    WHERE id IN (SELECT id FROM descendants) AND completed = FALSE
+                    FOR UPDATE
+                "#,
+                event.todo_id as _,
+            )
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(AppError::SqlExecuteError)?;
+
+            for subtask_id in subtask_ids.iter().copied().map(TodoId::from) {
+                sqlx::query!(
+                    r#"--sql
+                        INSERT INTO todo_completions (id, todo_id, user_id)
+                        VALUES ($1, $2, $3)
+                    "#,
+                    CompletionId::new() as _,
+                    subtask_id as _,
+                    event.user_id as _,
+                )
+                .execute(&mut *tx)
+                .await
+                .map_err(map_sql_error)?;
+            }
+
+            sqlx::query!(
+                r#"--sql
+                    UPDATE todos SET completed = TRUE WHERE id = ANY($1)
+                "#,
+                &subtask_ids,
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::SqlExecuteError)?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(Completion {
+            id: completion_id,
+            todo: CompletionTodo {
+                id: event.todo_id,
+                title: todo.title,
+            },
+            completed_at,
+            reopened_at: None,
+        })
+    }
+
+    async fn update_reopened(&self, event: UpdateReopened) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        let completion = sqlx::query!(
+            r#"--sql
+                SELECT reopened_at
+                FROM todo_completions
+                WHERE id = $1 AND todo_id = $2 AND user_id = $3
+                FOR UPDATE
+            "#,
+            event.completion_id as _,
+            event.todo_id as _,
+            event.user_id as _,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SqlExecuteError)?
+        .ok_or_else(|| AppError::EntityNotFoundError("The completion was not found".into()))?;
+
+        if completion.reopened_at.is_some() {
+            return Err(AppError::UnprocessableEntity(
+                "The completion has already been reopened".into(),
+            ));
+        }
+
+        sqlx::query!(
+            r#"--sql
+                UPDATE todo_completions
+                SET reopened_at = CURRENT_TIMESTAMP
+                WHERE id = $1
+            "#,
+            event.completion_id as _,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        sqlx::query!(
+            r#"--sql
+                UPDATE todos SET completed = FALSE WHERE id = $1
+            "#,
+            event.todo_id as _,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn find_completed_all(&self, user_id: UserId) -> AppResult<Vec<Completion>> {
+        let completions = sqlx::query_as!(
+            CompletionRow,
+            r#"--sql
+                SELECT
+                    c.id AS completion_id,
+                    c.todo_id,
+                    t.title,
+                    c.completed_at,
+                    c.reopened_at
+                FROM todo_completions AS c
+                INNER JOIN todos AS t ON t.id = c.todo_id
+                WHERE c.user_id = $1 AND c.reopened_at IS NULL
+                ORDER BY c.completed_at DESC
+            "#,
+            user_id as _,
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SqlExecuteError)?
+        .into_iter()
+        .map(Completion::from)
+        .collect();
+
+        Ok(completions)
+    }
+
+    async fn find_history_by_todo_id(
+        &self,
+        todo_id: TodoId,
+        user_id: UserId,
+    ) -> AppResult<Vec<Completion>> {
+        let completions = sqlx::query_as!(
+            CompletionRow,
+            r#"--sql
+                SELECT
+                    c.id AS completion_id,
+                    c.todo_id,
+                    t.title,
+                    c.completed_at,
+                    c.reopened_at
+                FROM todo_completions AS c
+                INNER JOIN todos AS t ON t.id = c.todo_id
+                WHERE c.todo_id = $1 AND c.user_id = $2
+                ORDER BY c.completed_at DESC
+            "#,
+            todo_id as _,
+            user_id as _,
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SqlExecuteError)?
+        .into_iter()
+        .map(Completion::from)
+        .collect();
+
+        Ok(completions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::connect_database_with;
+    use crate::repository::{
+        dependency::DependencyRepositoryImpl, todo::TodoRepositoryImpl, user::UserRepositoryImpl,
+    };
+    use chrono::{Duration, Utc};
+    use kernel::model::{
+        dependency::event::AddDependency,
+        list::ListQuery,
+        todo::{Priority, event::CreateTodo, filter::TodoFilter},
+        user::event::CreateUser,
+    };
+    use kernel::repository::{
+        dependency::DependencyRepository, todo::TodoRepository, user::UserRepository,
+    };
+    use shared::config::AppConfig;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    async fn create_todo(pool: &ConnectionPool) -> (UserId, TodoId) {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("timestamp")
+            .as_nanos();
+        let user = UserRepositoryImpl::new(pool.clone())
+            .create(CreateUser {
+                name: "Alice".to_string(),
+                email: format!("alice+{}@example.com", unique),
+                password: "password123".to_string(),
+            })
+            .await
+            .expect("ユーザ作成が成功する");
+        let todo = TodoRepositoryImpl::new(pool.clone())
+            .create(CreateTodo {
+                user_id: user.id,
+                project_id: None,
+                parent_id: None,
+                title: "牛乳を買う".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("todo作成が成功する");
+        (user.id, todo.id)
+    }
+
+    #[tokio::test]
+    async fn todoを完了すると完了一覧に含まれる() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let (user_id, todo_id) = create_todo(&pool).await;
+        let repo = CompletionRepositoryImpl::new(pool.clone());
+
+        let completion = repo
+            .create(CreateCompletion {
+                todo_id,
+                user_id,
+                include_subtasks: false,
+                force: false,
+            })
+            .await
+            .expect("完了が成功する");
+
+        assert_eq!(completion.todo.id, todo_id);
+        assert!(completion.reopened_at.is_none());
+
+        let completed = repo.find_completed_all(user_id).await.expect("一覧取得");
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].id, completion.id);
+
+        let todo = TodoRepositoryImpl::new(pool.clone())
+            .find_by_id(todo_id, user_id)
+            .await
+            .expect("取得が成功する")
+            .expect("todoが存在する");
+        assert!(todo.completed);
+    }
+
+    #[tokio::test]
+    async fn 完了済みのtodoは再度完了できない() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let (user_id, todo_id) = create_todo(&pool).await;
+        let repo = CompletionRepositoryImpl::new(pool.clone());
+
+        repo.create(CreateCompletion {
+            todo_id,
+            user_id,
+            include_subtasks: false,
+            force: false,
+        })
+        .await
+        .expect("完了が成功する");
+        let err = repo
+            .create(CreateCompletion {
+                todo_id,
+                user_id,
+                include_subtasks: false,
+                force: false,
+            })
+            .await
+            .expect_err("二重完了は失敗する");
+
+        assert!(matches!(err, AppError::UnprocessableEntity(_)));
+    }
+
+    #[tokio::test]
+    async fn 他人のtodoは完了できない() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let (_, todo_id) = create_todo(&pool).await;
+        let (other, _) = create_todo(&pool).await;
+        let repo = CompletionRepositoryImpl::new(pool.clone());
+
+        let err = repo
+            .create(CreateCompletion {
+                todo_id,
+                user_id: other,
+                include_subtasks: false,
+                force: false,
+            })
+            .await
+            .expect_err("他人のtodoは完了できない");
+
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+    }
+
+    #[tokio::test]
+    async fn 再オープンすると履歴に残り未完了に戻る() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let (user_id, todo_id) = create_todo(&pool).await;
+        let repo = CompletionRepositoryImpl::new(pool.clone());
+
+        let completion = repo
+            .create(CreateCompletion {
+                todo_id,
+                user_id,
+                include_subtasks: false,
+                force: false,
+            })
+            .await
+            .expect("完了が成功する");
+        repo.update_reopened(UpdateReopened {
+            completion_id: completion.id,
+            todo_id,
+            user_id,
+        })
+        .await
+        .expect("再オープンが成功する");
+        repo.create(CreateCompletion {
+            todo_id,
+            user_id,
+            include_subtasks: false,
+            force: false,
+        })
+        .await
+        .expect("再度の完了が成功する");
+
+        let history = repo
+            .find_history_by_todo_id(todo_id, user_id)
+            .await
+            .expect("履歴取得");
+        assert_eq!(history.len(), 2);
+        assert!(history[0].reopened_at.is_none());
+        assert_eq!(history[1].id, completion.id);
+        assert!(history[1].reopened_at.is_some());
+
+        let err = repo
+            .update_reopened(UpdateReopened {
+                completion_id: completion.id,
+                todo_id,
+                user_id,
+            })
+            .await
+            .expect_err("再オープン済みの完了は再オープンできない");
+        assert!(matches!(err, AppError::UnprocessableEntity(_)));
+    }
+
+    #[tokio::test]
+    async fn 子孫もまとめて完了にできる() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let (user_id, parent_id) = create_todo(&pool).await;
+        let todos = TodoRepositoryImpl::new(pool.clone());
+        let repo = CompletionRepositoryImpl::new(pool.clone());
+        let mut subtasks = Vec::new();
+        for _ in 0..2 {
+            let todo = todos
+                .create(CreateTodo {
+                    user_id,
+                    project_id: None,
+                    parent_id: Some(parent_id),
+                    title: "子".to_string(),
+                    description: None,
+                    priority: Priority::None,
+                    due_at: None,
+                    recurrence: None,
+                })
+                .await
+                .expect("todo作成が成功する");
+            subtasks.push(todo.id);
+        }
+        // 完了済みの子には新たな完了を記録しない
+        repo.create(CreateCompletion {
+            todo_id: subtasks[0],
+            user_id,
+            include_subtasks: false,
+            force: false,
+        })
+        .await
+        .expect("完了が成功する");
+
+        repo.create(CreateCompletion {
+            todo_id: parent_id,
+            user_id,
+            include_subtasks: true,
+            force: false,
+        })
+        .await
+        .expect("完了が成功する");
+
+        let tree = todos
+            .find_tree(parent_id, user_id)
+            .await
+            .expect("取得が成功する")
+            .expect("todoが存在する");
+        assert!(tree.todo.completed);
+        assert_eq!(tree.progress.done, 2);
+        for subtask_id in subtasks {
+            let history = repo
+                .find_history_by_todo_id(subtask_id, user_id)
+                .await
+                .expect("履歴取得");
+            assert_eq!(history.len(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn 繰り返しのtodoを完了すると次の回が作られる() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let (user_id, _) = create_todo(&pool).await;
+        let todos = TodoRepositoryImpl::new(pool.clone());
+        let repo = CompletionRepositoryImpl::new(pool.clone());
+        let due_at = Utc::now();
+        let todo = todos
+            .create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: "ゴミ出し".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: Some(due_at),
+                recurrence: Some("FREQ=DAILY;COUNT=2".parse().expect("正しい規則")),
+            })
+            .await
+            .expect("todo作成が成功する");
+        let open_todos = || async {
+            todos
+                .find_all(
+                    user_id,
+                    TodoFilter {
+                        completed: Some(false),
+                        title: Some("ゴミ出し".to_string()),
+                        ..Default::default()
+                    },
+                    ListQuery::default(),
+                )
+                .await
+                .expect("一覧取得")
+                .items
+        };
+
+        repo.create(CreateCompletion {
+            todo_id: todo.id,
+            user_id,
+            include_subtasks: false,
+            force: false,
+        })
+        .await
+        .expect("完了が成功する");
+
+        let next = open_todos().await;
+        assert_eq!(next.len(), 1);
+        assert_eq!(
+            next[0].due_at.map(|at| at.timestamp()),
+            Some((due_at + Duration::days(1)).timestamp())
+        );
+        assert_eq!(
+            next[0].recurrence.as_ref().and_then(|rule| rule.count),
+            Some(1)
+        );
+
+        // 回数を使い切ったので、それ以上は作られない
+        repo.create(CreateCompletion {
+            todo_id: next[0].id,
+            user_id,
+            include_subtasks: false,
+            force: false,
+        })
+        .await
+        .expect("完了が成功する");
+        assert!(open_todos().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn 繰り返しのtodoを再オープンして完了し直しても次の回は増えない() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let (user_id, _) = create_todo(&pool).await;
+        let todos = TodoRepositoryImpl::new(pool.clone());
+        let repo = CompletionRepositoryImpl::new(pool.clone());
+        let todo = todos
+            .create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: "水やり".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: Some(Utc::now()),
+                recurrence: Some("FREQ=DAILY;COUNT=3".parse().expect("正しい規則")),
+            })
+            .await
+            .expect("todo作成が成功する");
+        let complete = || {
+            repo.create(CreateCompletion {
+                todo_id: todo.id,
+                user_id,
+                include_subtasks: false,
+                force: false,
+            })
+        };
+
+        let completion = complete().await.expect("完了が成功する");
+        repo.update_reopened(UpdateReopened {
+            completion_id: completion.id,
+            todo_id: todo.id,
+            user_id,
+        })
+        .await
+        .expect("再オープンが成功する");
+        complete().await.expect("完了し直しが成功する");
+
+        let open = todos
+            .find_all(
+                user_id,
+                TodoFilter {
+                    completed: Some(false),
+                    title: Some("水やり".to_string()),
+                    ..Default::default()
+                },
+                ListQuery::default(),
+            )
+            .await
+            .expect("一覧取得")
+            .items;
+        assert_eq!(open.len(), 1);
+        assert_eq!(
+            open[0].recurrence.as_ref().and_then(|rule| rule.count),
+            Some(2)
+        );
+        let completed = todos
+            .find_by_id(todo.id, user_id)
+            .await
+            .expect("取得")
+            .expect("todoが存在する");
+        assert!(completed.recurrence.is_none());
+    }
+
+    #[tokio::test]
+    async fn 未完了のblockerがあると強制しない限り完了できない() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let (user_id, todo_id) = create_todo(&pool).await;
+        let todos = TodoRepositoryImpl::new(pool.clone());
+        let repo = CompletionRepositoryImpl::new(pool.clone());
+        let blocker = todos
+            .create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: "先にやること".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("todo作成が成功する");
+        DependencyRepositoryImpl::new(pool.clone())
+            .add(AddDependency {
+                todo_id,
+                blocker_id: blocker.id,
+                user_id,
+            })
+            .await
+            .expect("依存を張れる");
+        let blocked_ids = |blocked| {
+            let todos = &todos;
+            async move {
+                todos
+                    .find_all(
+                        user_id,
+                        TodoFilter {
+                            blocked: Some(blocked),
+                            ..Default::default()
+                        },
+                        ListQuery::default(),
+                    )
+                    .await
+                    .expect("一覧取得")
+                    .items
+                    .into_iter()
+                    .map(|todo| todo.id)
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(blocked_ids(true).await, vec![todo_id]);
+        assert_eq!(blocked_ids(false).await, vec![blocker.id]);
+
+        let complete = |todo_id, force| CreateCompletion {
+            todo_id,
+            user_id,
+            include_subtasks: false,
+            force,
+        };
+        let err = repo
+            .create(complete(todo_id, false))
+            .await
+            .expect_err("blockerが未完了なので完了できない");
+        assert!(matches!(err, AppError::StateConflict(_)));
+
+        // blocker を片付ければ妨げられなくなる
+        repo.create(complete(blocker.id, false))
+            .await
+            .expect("完了が成功する");
+        assert!(blocked_ids(true).await.is_empty());
+        repo.create(complete(todo_id, false))
+            .await
+            .expect("完了が成功する");
+    }
+
+    #[tokio::test]
+    async fn 強制すれば未完了のblockerがあっても完了できる() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let (user_id, todo_id) = create_todo(&pool).await;
+        let blocker = TodoRepositoryImpl::new(pool.clone())
+            .create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: "先にやること".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("todo作成が成功する");
+        DependencyRepositoryImpl::new(pool.clone())
+            .add(AddDependency {
+                todo_id,
+                blocker_id: blocker.id,
+                user_id,
+            })
+            .await
+            .expect("依存を張れる");
+
+        CompletionRepositoryImpl::new(pool.clone())
+            .create(CreateCompletion {
+                todo_id,
+                user_id,
+                include_subtasks: false,
+                force: true,
+            })
+            .await
+            .expect("強制すれば完了できる");
+    }
+}
@@ -0,0 +1,359 @@
+use crate::database::{ConnectionPool, map_sql_error};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        dependency::{
+            DependencyTodo, TodoDependencies,
+            event::{AddDependency, RemoveDependency},
+        },
+        id::{TodoId, UserId},
+    },
+    repository::dependency::DependencyRepository,
+};
+use shared::error::{AppError, AppResult};
+
+#[derive(new)]
+pub struct DependencyRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl DependencyRepository for DependencyRepositoryImpl {
+    async fn add(&self, event: AddDependency) -> AppResult<()> {
+        if event.todo_id == event.blocker_id {
+            return Err(AppError::UnprocessableEntity(
+                "A todo cannot block itself".into(),
+            ));
+        }
+
+        let mut tx = self.db.begin().await?;
+
+        // 依存の追加はユーザごとに直列にする。別々の依存が同時に張られて 3 件以上の循環ができる場合は、
+        // 張る両端の行をロックしても防げないため、判定から書き込みまでをまとめて advisory lock で守る
+        sqlx::query!(
+            r#"--sql
+                SELECT pg_advisory_xact_lock(hashtext('todo_dependencies'), hashtext($1::UUID::TEXT))
+            "#,
+            event.user_id as _,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        // 判定の間に todo が消されないよう、両方の todo をロックしておく
+        let owned = sqlx::query_scalar!(
+            r#"--sql
+                SELECT id
+                FROM todos
+                WHERE id IN ($1, $2) AND user_id = $3
+                ORDER BY id
+                FOR UPDATE
+            "#,
+            event.todo_id as _,
+            event.blocker_id as _,
+            event.user_id as _,
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        if owned.len() != 2 {
+            return Err(AppError::EntityNotFoundError(
+                "The todo or blocker was not found".into(),
+            ));
+        }
+
+        // blocker の先にある blocker を辿り、todo に行き着くなら循環する
+        let cyclic = sqlx::query_scalar!(
+            r#"--sql
+                WITH RECURSIVE upstream AS (
+                    SELECT blocker_id FROM todo_dependencies WHERE todo_id = $1
+                    UNION
+                    SELECT todo_dependencies.blocker_id
+                    FROM todo_dependencies
+                    JOIN upstream ON todo_dependencies.todo_id = upstream.blocker_id
+                )
+                SELECT EXISTS (SELECT 1 FROM upstream WHERE blocker_id = $2) AS "cyclic!"
+            "#,
+            event.blocker_id as _,
+            event.todo_id as _,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        if cyclic {
+            return Err(AppError::UnprocessableEntity(
+                "The dependency would create a cycle".into(),
+            ));
+        }
+
+        sqlx::query!(
+            r#"--sql
+                INSERT INTO todo_dependencies (todo_id, blocker_id)
+                VALUES ($1, $2)
+                ON CONFLICT (todo_id, blocker_id) DO NOTHING
+            "#,
+            event.todo_id as _,
+            event.blocker_id as _,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(map_sql_error)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn remove(&self, event: RemoveDependency) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"--sql
+                DELETE FROM todo_dependencies
+                USING todos
+                WHERE todo_dependencies.todo_id = todos.id
+                    AND todo_dependencies.todo_id = $1
+                    AND todo_dependencies.blocker_id = $2
+                    AND todos.user_id = $3
+            "#,
+            event.todo_id as _,
+            event.blocker_id as _,
+            event.user_id as _,
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFoundError(
+                "The todo is not blocked by the blocker".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn find_by_todo_id(
+        &self,
+        todo_id: TodoId,
+        user_id: UserId,
+    ) -> AppResult<TodoDependencies> {
+        let blockers = sqlx::query!(
+            r#"--sql
+                SELECT todos.id, todos.title, todos.completed
+                FROM todo_dependencies
+                JOIN todos ON todos.id = todo_dependencies.blocker_id
+                WHERE todo_dependencies.todo_id = $1 AND todos.user_id = $2
+                ORDER BY todo_dependencies.created_at, todos.id
+            "#,
+            todo_id as _,
+            user_id as _,
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        let dependents = sqlx::query!(
+            r#"--sql
+                SELECT todos.id, todos.title, todos.completed
+                FROM todo_dependencies
+                JOIN todos ON todos.id = todo_dependencies.todo_id
+                WHERE todo_dependencies.blocker_id = $1 AND todos.user_id = $2
+                ORDER BY todo_dependencies.created_at, todos.id
+            "#,
+            todo_id as _,
+            user_id as _,
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        Ok(TodoDependencies {
+            blockers: blockers
+                .into_iter()
+                .map(|row| DependencyTodo {
+                    id: row.id.into(),
+                    title: row.title,
+                    completed: row.completed,
+                })
+                .collect(),
+            dependents: dependents
+                .into_iter()
+                .map(|row| DependencyTodo {
+                    id: row.id.into(),
+                    title: row.title,
+                    completed: row.completed,
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::connect_database_with;
+    use crate::repository::{todo::TodoRepositoryImpl, user::UserRepositoryImpl};
+    use kernel::model::{
+        todo::{Priority, event::CreateTodo},
+        user::event::CreateUser,
+    };
+    use kernel::repository::{todo::TodoRepository, user::UserRepository};
+    use shared::config::AppConfig;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    async fn create_user(pool: &ConnectionPool) -> UserId {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("timestamp")
+            .as_nanos();
+        UserRepositoryImpl::new(pool.clone())
+            .create(CreateUser {
+                name: "Alice".to_string(),
+                email: format!("alice+{}@example.com", unique),
+                password: "password123".to_string(),
+            })
+            .await
+            .expect("ユーザ作成が成功する")
+            .id
+    }
+
+    async fn create_todo(pool: &ConnectionPool, user_id: UserId, title: &str) -> TodoId {
+        TodoRepositoryImpl::new(pool.clone())
+            .create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: title.to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("作成が成功する")
+            .id
+    }
+
+    #[tokio::test]
+    async fn 依存を張ると双方から参照でき循環は拒否する() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let repo = DependencyRepositoryImpl::new(pool.clone());
+        let design = create_todo(&pool, user_id, "設計").await;
+        let build = create_todo(&pool, user_id, "実装").await;
+        let release = create_todo(&pool, user_id, "リリース").await;
+        let add = |todo_id, blocker_id| AddDependency {
+            todo_id,
+            blocker_id,
+            user_id,
+        };
+
+        repo.add(add(build, design)).await.expect("依存を張れる");
+        repo.add(add(release, build)).await.expect("依存を張れる");
+        // 張り直しても成功する
+        repo.add(add(release, build)).await.expect("依存を張れる");
+
+        let deps = repo
+            .find_by_todo_id(build, user_id)
+            .await
+            .expect("取得が成功する");
+        assert_eq!(deps.blockers.len(), 1);
+        assert_eq!(deps.blockers[0].id, design);
+        assert_eq!(deps.dependents.len(), 1);
+        assert_eq!(deps.dependents[0].id, release);
+
+        for (todo_id, blocker_id) in [(design, release), (design, build), (design, design)] {
+            let err = repo
+                .add(add(todo_id, blocker_id))
+                .await
+                .expect_err("循環する依存は張れない");
+            assert!(matches!(err, AppError::UnprocessableEntity(_)));
+        }
+
+        repo.remove(RemoveDependency {
+            todo_id: build,
+            blocker_id: design,
+            user_id,
+        })
+        .await
+        .expect("依存を外せる");
+        // 外したので逆向きに張れる
+        repo.add(add(design, build)).await.expect("依存を張れる");
+    }
+
+    #[tokio::test]
+    async fn 同時に張っても3件以上の循環はできない() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let repo = DependencyRepositoryImpl::new(pool.clone());
+        let a = create_todo(&pool, user_id, "a").await;
+        let b = create_todo(&pool, user_id, "b").await;
+        let c = create_todo(&pool, user_id, "c").await;
+        repo.add(AddDependency {
+            todo_id: a,
+            blocker_id: b,
+            user_id,
+        })
+        .await
+        .expect("依存を張れる");
+
+        // どちらか片方だけなら張れるが、両方張ると a → b → c → a と循環する
+        let handles = [(b, c), (c, a)]
+            .into_iter()
+            .map(|(todo_id, blocker_id)| {
+                let repo = DependencyRepositoryImpl::new(pool.clone());
+                tokio::spawn(async move {
+                    repo.add(AddDependency {
+                        todo_id,
+                        blocker_id,
+                        user_id,
+                    })
+                    .await
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut succeeded = 0;
+        for handle in handles {
+            match handle.await.expect("タスクが終わる") {
+                Ok(()) => succeeded += 1,
+                Err(err) => assert!(matches!(err, AppError::UnprocessableEntity(_))),
+            }
+        }
+        assert_eq!(succeeded, 1);
+    }
+
+    #[tokio::test]
+    async fn 他人のtodoには依存を張れない() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let other = create_user(&pool).await;
+        let repo = DependencyRepositoryImpl::new(pool.clone());
+        let mine = create_todo(&pool, user_id, "自分のtodo").await;
+        let others = create_todo(&pool, other, "他人のtodo").await;
+
+        let err = repo
+            .add(AddDependency {
+                todo_id: mine,
+                blocker_id: others,
+                user_id,
+            })
+            .await
+            .expect_err("他人のtodoには依存を張れない");
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+
+        let err = repo
+            .remove(RemoveDependency {
+                todo_id: mine,
+                blocker_id: others,
+                user_id,
+            })
+            .await
+            .expect_err("張っていない依存は外せない");
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+    }
+}
@@ -0,0 +1,282 @@
+use crate::{
+    database::{
+        ConnectionPool, map_sql_error, model::personal_access_token::PersonalAccessTokenRow,
+    },
+    digest::sha256_hex,
+};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        id::{PersonalAccessTokenId, UserId},
+        personal_access_token::{
+            PersonalAccessToken, PersonalAccessTokenSecret,
+            event::{CreatePersonalAccessToken, DeletePersonalAccessToken},
+        },
+    },
+    repository::personal_access_token::PersonalAccessTokenRepository,
+};
+use shared::error::{AppError, AppResult};
+
+#[derive(new)]
+pub struct PersonalAccessTokenRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl PersonalAccessTokenRepository for PersonalAccessTokenRepositoryImpl {
+    async fn create(&self, event: CreatePersonalAccessToken) -> AppResult<PersonalAccessToken> {
+        let scopes: Vec<String> = event
+            .scopes
+            .iter()
+            .map(|scope| scope.as_str().to_string())
+            .collect();
+        let row = sqlx::query_as!(
+            PersonalAccessTokenRow,
+            r#"--sql
+                INSERT INTO personal_access_tokens
+                    (id, user_id, name, token_hash, scopes, expires_at)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                RETURNING
+                    id,
+                    user_id,
+                    name,
+                    scopes,
+                    expires_at,
+                    last_used_at,
+                    created_at
+            "#,
+            PersonalAccessTokenId::new() as _,
+            event.user_id as _,
+            event.name,
+            sha256_hex(&event.secret.0),
+            &scopes,
+            event.expires_at,
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(map_sql_error)?;
+
+        PersonalAccessToken::try_from(row)
+    }
+
+    async fn find_all(&self, user_id: UserId) -> AppResult<Vec<PersonalAccessToken>> {
+        let rows = sqlx::query_as!(
+            PersonalAccessTokenRow,
+            r#"--sql
+                SELECT
+                    id,
+                    user_id,
+                    name,
+                    scopes,
+                    expires_at,
+                    last_used_at,
+                    created_at
+                FROM personal_access_tokens
+                WHERE user_id = $1
+                ORDER BY created_at DESC
+            "#,
+            user_id as _,
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        rows.into_iter()
+            .map(PersonalAccessToken::try_from)
+            .collect()
+    }
+
+    async fn delete(&self, event: DeletePersonalAccessToken) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"--sql
+                DELETE FROM personal_access_tokens WHERE id = $1 AND user_id = $2
+            "#,
+            event.id as _,
+            event.user_id as _,
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(map_sql_error)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFoundError(
+                "The personal access token was not found".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn authenticate(
+        &self,
+        secret: &PersonalAccessTokenSecret,
+    ) -> AppResult<Option<PersonalAccessToken>> {
+        let row = sqlx::query_as!(
+            PersonalAccessTokenRow,
+            r#"--sql
+                UPDATE personal_access_tokens
+                SET last_used_at = CURRENT_TIMESTAMP
+                WHERE token_hash = $1
+                    AND (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP)
+                RETURNING
+                    id,
+                    user_id,
+                    name,
+                    scopes,
+                    expires_at,
+                    last_used_at,
+                    created_at
+            "#,
+            sha256_hex(&secret.0),
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(map_sql_error)?;
+
+        row.map(PersonalAccessToken::try_from).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::connect_database_with;
+    use crate::repository::user::UserRepositoryImpl;
+    use chrono::{Duration, Utc};
+    use kernel::model::{personal_access_token::TokenScope, user::event::CreateUser};
+    use kernel::repository::user::UserRepository;
+    use shared::config::AppConfig;
+    use sqlx::Row;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    async fn create_user(pool: &ConnectionPool) -> UserId {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("timestamp")
+            .as_nanos();
+        let repo = UserRepositoryImpl::new(pool.clone());
+        let event = CreateUser {
+            name: "Alice".to_string(),
+            email: format!("alice+{}@example.com", unique),
+            password: "password123".to_string(),
+        };
+        repo.create(event).await.expect("ユーザ作成が成功する").id
+    }
+
+    fn create_event(
+        user_id: UserId,
+        name: &str,
+        secret: &PersonalAccessTokenSecret,
+    ) -> CreatePersonalAccessToken {
+        CreatePersonalAccessToken {
+            user_id,
+            name: name.to_string(),
+            scopes: vec![TokenScope::TodosRead, TokenScope::TodosWrite],
+            expires_at: None,
+            secret: secret.clone(),
+        }
+    }
+
+    #[tokio::test]
+    async fn トークンはダイジェストだけを保存しシークレットで認証できる() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let repo = PersonalAccessTokenRepositoryImpl::new(pool.clone());
+
+        let secret = PersonalAccessTokenSecret::generate();
+        let created = repo
+            .create(create_event(user_id, "ci", &secret))
+            .await
+            .expect("作成が成功する");
+        assert_eq!(
+            created.scopes,
+            vec![TokenScope::TodosRead, TokenScope::TodosWrite]
+        );
+
+        let stored = sqlx::query("SELECT token_hash FROM personal_access_tokens WHERE id = $1")
+            .bind(created.id.raw())
+            .fetch_one(pool.inner_ref())
+            .await
+            .expect("取得");
+        let token_hash: String = stored.get("token_hash");
+        assert_eq!(token_hash, sha256_hex(&secret.0));
+
+        let token = repo
+            .authenticate(&secret)
+            .await
+            .expect("認証が成功する")
+            .expect("トークンが見つかる");
+        assert_eq!(token.id, created.id);
+        assert!(token.last_used_at.is_some());
+        assert!(
+            repo.authenticate(&PersonalAccessTokenSecret::generate())
+                .await
+                .expect("認証を試せる")
+                .is_none()
+        );
+
+        let err = repo
+            .create(create_event(
+                user_id,
+                "ci",
+                &PersonalAccessTokenSecret::generate(),
+            ))
+            .await
+            .expect_err("同じ名前は作れない");
+        assert!(matches!(err, AppError::Conflict(_)));
+    }
+
+    #[tokio::test]
+    async fn 期限切れか失効したトークンでは認証できない() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let repo = PersonalAccessTokenRepositoryImpl::new(pool.clone());
+
+        let expired = PersonalAccessTokenSecret::generate();
+        repo.create(CreatePersonalAccessToken {
+            expires_at: Some(Utc::now() - Duration::seconds(1)),
+            ..create_event(user_id, "expired", &expired)
+        })
+        .await
+        .expect("作成が成功する");
+        assert!(
+            repo.authenticate(&expired)
+                .await
+                .expect("認証を試せる")
+                .is_none()
+        );
+
+        let revoked = PersonalAccessTokenSecret::generate();
+        let token = repo
+            .create(create_event(user_id, "revoked", &revoked))
+            .await
+            .expect("作成が成功する");
+        let err = repo
+            .delete(DeletePersonalAccessToken {
+                id: token.id,
+                user_id: create_user(&pool).await,
+            })
+            .await
+            .expect_err("他のユーザのトークンは失効できない");
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+        repo.delete(DeletePersonalAccessToken {
+            id: token.id,
+            user_id,
+        })
+        .await
+        .expect("失効が成功する");
+        assert!(
+            repo.authenticate(&revoked)
+                .await
+                .expect("認証を試せる")
+                .is_none()
+        );
+
+        let tokens = repo.find_all(user_id).await.expect("一覧取得");
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].name, "expired");
+    }
+}
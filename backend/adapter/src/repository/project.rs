@@ -0,0 +1,383 @@
+use crate::database::{ConnectionPool, map_sql_error, model::project::ProjectRow};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        id::{ProjectId, UserId},
+        project::{
+            Project,
+            event::{CreateProject, DeleteProject, DeleteProjectMode, UpdateProject},
+        },
+    },
+    repository::project::ProjectRepository,
+};
+use shared::error::{AppError, AppResult};
+
+#[derive(new)]
+pub struct ProjectRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl ProjectRepository for ProjectRepositoryImpl {
+    async fn create(&self, event: CreateProject) -> AppResult<Project> {
+        let row = sqlx::query_as!(
+            ProjectRow,
+            r#"--sql
+                INSERT INTO projects (id, user_id, name, colour)
+                VALUES ($1, $2, $3, $4)
+                RETURNING
+                    id,
+                    user_id,
+                    name,
+                    colour,
+                    archived,
+                    0::BIGINT AS "total!",
+                    0::BIGINT AS "completed!",
+                    created_at,
+                    updated_at
+            "#,
+            ProjectId::new() as _,
+            event.user_id as _,
+            event.name,
+            event.colour,
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(map_sql_error)?;
+
+        Ok(Project::from(row))
+    }
+
+    async fn find_all(&self, user_id: UserId, include_archived: bool) -> AppResult<Vec<Project>> {
+        let rows = sqlx::query_as!(
+            ProjectRow,
+            r#"--sql
+                SELECT
+                    p.id,
+                    p.user_id,
+                    p.name,
+                    p.colour,
+                    p.archived,
+                    COUNT(t.id) AS "total!",
+                    COUNT(t.id) FILTER (WHERE t.completed) AS "completed!",
+                    p.created_at,
+                    p.updated_at
+                FROM projects AS p
+                LEFT JOIN todos AS t ON t.project_id = p.id
+                WHERE p.user_id = $1 AND ($2 OR NOT p.archived)
+                GROUP BY p.id
+                ORDER BY p.name ASC
+            "#,
+            user_id as _,
+            include_archived,
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        Ok(rows.into_iter().map(Project::from).collect())
+    }
+
+    async fn find_by_id(&self, id: ProjectId, user_id: UserId) -> AppResult<Option<Project>> {
+        let row = sqlx::query_as!(
+            ProjectRow,
+            r#"--sql
+                SELECT
+                    p.id,
+                    p.user_id,
+                    p.name,
+                    p.colour,
+                    p.archived,
+                    COUNT(t.id) AS "total!",
+                    COUNT(t.id) FILTER (WHERE t.completed) AS "completed!",
+                    p.created_at,
+                    p.updated_at
+                FROM projects AS p
+                LEFT JOIN todos AS t ON t.project_id = p.id
+                WHERE p.id = $1 AND p.user_id = $2
+                GROUP BY p.id
+            "#,
+            id as _,
+            user_id as _,
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        Ok(row.map(Project::from))
+    }
+
+    async fn update(&self, event: UpdateProject) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"--sql
+                UPDATE projects
+                SET
+                    name = $1,
+                    colour = $2,
+                    archived = $3
+                WHERE id = $4 AND user_id = $5
+            "#,
+            event.name,
+            event.colour,
+            event.archived,
+            event.id as _,
+            event.user_id as _,
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(map_sql_error)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFoundError(
+                "No project has been updated".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn delete(&self, event: DeleteProject) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        // 外部キーは ON DELETE SET NULL なので、削除モードのときは先に todo を消しておく
+        if event.mode == DeleteProjectMode::Cascade {
+            sqlx::query!(
+                r#"--sql
+                    DELETE FROM todos WHERE project_id = $1 AND user_id = $2
+                "#,
+                event.id as _,
+                event.user_id as _,
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::SqlExecuteError)?;
+        }
+
+        let res = sqlx::query!(
+            r#"--sql
+                DELETE FROM projects WHERE id = $1 AND user_id = $2
+            "#,
+            event.id as _,
+            event.user_id as _,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFoundError(
+                "No project has been deleted".into(),
+            ));
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::connect_database_with;
+    use crate::repository::{
+        completion::CompletionRepositoryImpl, todo::TodoRepositoryImpl, user::UserRepositoryImpl,
+    };
+    use kernel::model::{
+        completion::event::CreateCompletion,
+        id::TodoId,
+        list::ListQuery,
+        project::DEFAULT_COLOUR,
+        todo::{
+            Priority,
+            event::CreateTodo,
+            filter::{ProjectScope, TodoFilter},
+        },
+        user::event::CreateUser,
+    };
+    use kernel::repository::{
+        completion::CompletionRepository, todo::TodoRepository, user::UserRepository,
+    };
+    use shared::config::AppConfig;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    async fn create_user(pool: &ConnectionPool) -> UserId {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("timestamp")
+            .as_nanos();
+        let repo = UserRepositoryImpl::new(pool.clone());
+        let event = CreateUser {
+            name: "Alice".to_string(),
+            email: format!("alice+{}@example.com", unique),
+            password: "password123".to_string(),
+        };
+        repo.create(event).await.expect("ユーザ作成が成功する").id
+    }
+
+    async fn create_project(pool: &ConnectionPool, user_id: UserId, name: &str) -> ProjectId {
+        ProjectRepositoryImpl::new(pool.clone())
+            .create(CreateProject {
+                user_id,
+                name: name.to_string(),
+                colour: DEFAULT_COLOUR.to_string(),
+            })
+            .await
+            .expect("作成が成功する")
+            .id
+    }
+
+    async fn create_todo(
+        pool: &ConnectionPool,
+        user_id: UserId,
+        project_id: Option<ProjectId>,
+    ) -> AppResult<TodoId> {
+        TodoRepositoryImpl::new(pool.clone())
+            .create(CreateTodo {
+                user_id,
+                project_id,
+                parent_id: None,
+                title: "牛乳を買う".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .map(|todo| todo.id)
+    }
+
+    #[tokio::test]
+    async fn プロジェクトの件数と完了数を返す() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let repo = ProjectRepositoryImpl::new(pool.clone());
+        let project_id = create_project(&pool, user_id, "買い物").await;
+
+        let done = create_todo(&pool, user_id, Some(project_id))
+            .await
+            .expect("作成が成功する");
+        create_todo(&pool, user_id, Some(project_id))
+            .await
+            .expect("作成が成功する");
+        create_todo(&pool, user_id, None)
+            .await
+            .expect("作成が成功する");
+        CompletionRepositoryImpl::new(pool.clone())
+            .create(CreateCompletion {
+                todo_id: done,
+                user_id,
+                include_subtasks: false,
+                force: false,
+            })
+            .await
+            .expect("完了が成功する");
+
+        let project = repo
+            .find_by_id(project_id, user_id)
+            .await
+            .expect("取得")
+            .expect("プロジェクトが存在する");
+        assert_eq!(project.progress.total, 2);
+        assert_eq!(project.progress.completed, 1);
+
+        let page = TodoRepositoryImpl::new(pool.clone())
+            .find_all(
+                user_id,
+                TodoFilter {
+                    project: Some(ProjectScope::Inbox),
+                    ..Default::default()
+                },
+                ListQuery::default(),
+            )
+            .await
+            .expect("一覧取得");
+        assert_eq!(page.items.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn アーカイブしたプロジェクトは一覧から除外できる() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let repo = ProjectRepositoryImpl::new(pool.clone());
+        let project_id = create_project(&pool, user_id, "昔の仕事").await;
+
+        repo.update(UpdateProject {
+            id: project_id,
+            user_id,
+            name: "昔の仕事".to_string(),
+            colour: "#ff0000".to_string(),
+            archived: true,
+        })
+        .await
+        .expect("更新が成功する");
+
+        assert!(
+            repo.find_all(user_id, false)
+                .await
+                .expect("一覧")
+                .is_empty()
+        );
+        let all = repo.find_all(user_id, true).await.expect("一覧");
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].colour, "#ff0000");
+    }
+
+    #[tokio::test]
+    async fn プロジェクト削除はtodoを削除するか受信箱に移す() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let repo = ProjectRepositoryImpl::new(pool.clone());
+        let todos = TodoRepositoryImpl::new(pool.clone());
+        let moved = create_project(&pool, user_id, "移す").await;
+        let cascaded = create_project(&pool, user_id, "消す").await;
+        let kept = create_todo(&pool, user_id, Some(moved))
+            .await
+            .expect("作成が成功する");
+        let removed = create_todo(&pool, user_id, Some(cascaded))
+            .await
+            .expect("作成が成功する");
+
+        for (id, mode) in [
+            (moved, DeleteProjectMode::MoveToInbox),
+            (cascaded, DeleteProjectMode::Cascade),
+        ] {
+            repo.delete(DeleteProject { id, user_id, mode })
+                .await
+                .expect("削除が成功する");
+        }
+
+        let kept = todos
+            .find_by_id(kept, user_id)
+            .await
+            .expect("取得")
+            .expect("受信箱に残っている");
+        assert!(kept.project_id.is_none());
+        assert!(
+            todos
+                .find_by_id(removed, user_id)
+                .await
+                .expect("取得")
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn 他人のプロジェクトにはtodoを追加できない() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let owner = create_user(&pool).await;
+        let other = create_user(&pool).await;
+        let project_id = create_project(&pool, owner, "買い物").await;
+
+        let err = create_todo(&pool, other, Some(project_id))
+            .await
+            .expect_err("他人のプロジェクトには追加できない");
+
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+    }
+}
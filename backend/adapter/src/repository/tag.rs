@@ -0,0 +1,420 @@
+use crate::database::{ConnectionPool, map_sql_error, model::tag::TagRow};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        id::{TagId, UserId},
+        tag::{
+            Tag,
+            event::{AttachTag, CreateTag, DeleteTag, DetachTag, UpdateTag},
+        },
+    },
+    repository::tag::TagRepository,
+};
+use shared::error::{AppError, AppResult};
+
+#[derive(new)]
+pub struct TagRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl TagRepository for TagRepositoryImpl {
+    async fn create(&self, event: CreateTag) -> AppResult<Tag> {
+        let row = sqlx::query_as!(
+            TagRow,
+            r#"--sql
+                INSERT INTO tags (id, user_id, name)
+                VALUES ($1, $2, $3)
+                RETURNING
+                    id,
+                    user_id,
+                    name,
+                    created_at,
+                    updated_at
+            "#,
+            TagId::new() as _,
+            event.user_id as _,
+            event.name,
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(map_sql_error)?;
+
+        Ok(Tag::from(row))
+    }
+
+    async fn find_all(&self, user_id: UserId) -> AppResult<Vec<Tag>> {
+        let rows = sqlx::query_as!(
+            TagRow,
+            r#"--sql
+                SELECT
+                    id,
+                    user_id,
+                    name,
+                    created_at,
+                    updated_at
+                FROM tags
+                WHERE user_id = $1
+                ORDER BY name ASC
+            "#,
+            user_id as _,
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        Ok(rows.into_iter().map(Tag::from).collect())
+    }
+
+    async fn find_by_id(&self, id: TagId, user_id: UserId) -> AppResult<Option<Tag>> {
+        let row = sqlx::query_as!(
+            TagRow,
+            r#"--sql
+                SELECT
+                    id,
+                    user_id,
+                    name,
+                    created_at,
+                    updated_at
+                FROM tags
+                WHERE id = $1 AND user_id = $2
+            "#,
+            id as _,
+            user_id as _,
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        Ok(row.map(Tag::from))
+    }
+
+    async fn update(&self, event: UpdateTag) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"--sql
+                UPDATE tags
+                SET name = $1
+                WHERE id = $2 AND user_id = $3
+            "#,
+            event.name,
+            event.id as _,
+            event.user_id as _,
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(map_sql_error)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFoundError(
+                "No tag has been updated".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn delete(&self, event: DeleteTag) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"--sql
+                DELETE FROM tags WHERE id = $1 AND user_id = $2
+            "#,
+            event.id as _,
+            event.user_id as _,
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFoundError(
+                "No tag has been deleted".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn attach(&self, event: AttachTag) -> AppResult<()> {
+        // todo とタグの両方が同じユーザのものであるときだけ挿入する。
+        // 付け直しでも 1 行として数えるよう、衝突時は何も変えずに更新扱いにする
+        let res = sqlx::query!(
+            r#"--sql
+                INSERT INTO todo_tags (todo_id, tag_id)
+                SELECT todos.id, tags.id
+                FROM todos, tags
+                WHERE todos.id = $1
+                    AND tags.id = $2
+                    AND todos.user_id = $3
+                    AND tags.user_id = $3
+                ON CONFLICT (todo_id, tag_id)
+                    DO UPDATE SET created_at = todo_tags.created_at
+            "#,
+            event.todo_id as _,
+            event.tag_id as _,
+            event.user_id as _,
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFoundError(
+                "The todo or tag was not found".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn detach(&self, event: DetachTag) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"--sql
+                DELETE FROM todo_tags
+                USING tags
+                WHERE todo_tags.tag_id = tags.id
+                    AND todo_tags.todo_id = $1
+                    AND todo_tags.tag_id = $2
+                    AND tags.user_id = $3
+            "#,
+            event.todo_id as _,
+            event.tag_id as _,
+            event.user_id as _,
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFoundError(
+                "The tag is not attached to the todo".into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::connect_database_with;
+    use crate::repository::{todo::TodoRepositoryImpl, user::UserRepositoryImpl};
+    use kernel::model::{
+        id::TodoId,
+        list::ListQuery,
+        todo::{Priority, event::CreateTodo, filter::TodoFilter},
+        user::event::CreateUser,
+    };
+    use kernel::repository::{todo::TodoRepository, user::UserRepository};
+    use shared::config::AppConfig;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    async fn create_user(pool: &ConnectionPool) -> UserId {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("timestamp")
+            .as_nanos();
+        let repo = UserRepositoryImpl::new(pool.clone());
+        let event = CreateUser {
+            name: "Alice".to_string(),
+            email: format!("alice+{}@example.com", unique),
+            password: "password123".to_string(),
+        };
+        repo.create(event).await.expect("ユーザ作成が成功する").id
+    }
+
+    async fn create_todo(pool: &ConnectionPool, user_id: UserId, title: &str) -> TodoId {
+        TodoRepositoryImpl::new(pool.clone())
+            .create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: title.to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("作成が成功する")
+            .id
+    }
+
+    #[tokio::test]
+    async fn タグを作成して名前を変更できる() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let repo = TagRepositoryImpl::new(pool.clone());
+
+        let tag = repo
+            .create(CreateTag {
+                user_id,
+                name: "bug".to_string(),
+            })
+            .await
+            .expect("作成が成功する");
+        repo.update(UpdateTag {
+            id: tag.id,
+            user_id,
+            name: "defect".to_string(),
+        })
+        .await
+        .expect("更新が成功する");
+
+        let tags = repo.find_all(user_id).await.expect("一覧取得");
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].name, "defect");
+    }
+
+    #[tokio::test]
+    async fn 同じ名前のタグは作成できない() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let other = create_user(&pool).await;
+        let repo = TagRepositoryImpl::new(pool.clone());
+        let create = |user_id| CreateTag {
+            user_id,
+            name: "bug".to_string(),
+        };
+
+        repo.create(create(user_id)).await.expect("作成が成功する");
+        let err = repo
+            .create(create(user_id))
+            .await
+            .expect_err("重複したタグは作成できない");
+        assert!(matches!(err, AppError::Conflict(ref field) if field == "name"));
+
+        // 別のユーザなら同じ名前でも作成できる
+        repo.create(create(other)).await.expect("作成が成功する");
+    }
+
+    #[tokio::test]
+    async fn タグを付け外ししてtodoを絞り込める() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let repo = TagRepositoryImpl::new(pool.clone());
+        let todos = TodoRepositoryImpl::new(pool.clone());
+
+        let bug = repo
+            .create(CreateTag {
+                user_id,
+                name: "bug".to_string(),
+            })
+            .await
+            .expect("作成が成功する");
+        let review = repo
+            .create(CreateTag {
+                user_id,
+                name: "review".to_string(),
+            })
+            .await
+            .expect("作成が成功する");
+        let both = create_todo(&pool, user_id, "両方").await;
+        let bug_only = create_todo(&pool, user_id, "bugだけ").await;
+        create_todo(&pool, user_id, "なし").await;
+
+        for (tag_id, todo_id) in [(bug.id, both), (review.id, both), (bug.id, bug_only)] {
+            repo.attach(AttachTag {
+                tag_id,
+                todo_id,
+                user_id,
+            })
+            .await
+            .expect("タグ付けが成功する");
+        }
+        // 付け直しても成功する
+        repo.attach(AttachTag {
+            tag_id: bug.id,
+            todo_id: both,
+            user_id,
+        })
+        .await
+        .expect("タグ付けが成功する");
+
+        let todos = &todos;
+        let find = move |filter: TodoFilter| async move {
+            let mut ids: Vec<TodoId> = todos
+                .find_all(user_id, filter, ListQuery::default())
+                .await
+                .expect("一覧取得")
+                .items
+                .into_iter()
+                .map(|todo| todo.id)
+                .collect();
+            ids.sort_by_key(|id| id.raw());
+            ids
+        };
+        let mut expected = vec![both, bug_only];
+        expected.sort_by_key(|id| id.raw());
+
+        let any = find(TodoFilter {
+            tags_any: vec![bug.id, review.id],
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(any, expected);
+
+        let all = find(TodoFilter {
+            tags_all: vec![bug.id, review.id],
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(all, vec![both]);
+
+        repo.detach(DetachTag {
+            tag_id: review.id,
+            todo_id: both,
+            user_id,
+        })
+        .await
+        .expect("タグ外しが成功する");
+        let all = find(TodoFilter {
+            tags_all: vec![bug.id, review.id],
+            ..Default::default()
+        })
+        .await;
+        assert!(all.is_empty());
+    }
+
+    #[tokio::test]
+    async fn 他人のtodoやタグには付けられない() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let other = create_user(&pool).await;
+        let repo = TagRepositoryImpl::new(pool.clone());
+
+        let tag = repo
+            .create(CreateTag {
+                user_id,
+                name: "bug".to_string(),
+            })
+            .await
+            .expect("作成が成功する");
+        let others_todo = create_todo(&pool, other, "他人のtodo").await;
+
+        let err = repo
+            .attach(AttachTag {
+                tag_id: tag.id,
+                todo_id: others_todo,
+                user_id,
+            })
+            .await
+            .expect_err("他人のtodoには付けられない");
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+
+        let err = repo
+            .attach(AttachTag {
+                tag_id: tag.id,
+                todo_id: others_todo,
+                user_id: other,
+            })
+            .await
+            .expect_err("他人のタグは付けられない");
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+    }
+}
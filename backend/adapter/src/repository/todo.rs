@@ -0,0 +1,1471 @@
+use crate::database::{
+    ConnectionPool,
+    list::{SortColumn, push_keyset_condition, push_order_by},
+    map_sql_error,
+    model::todo::{TodoRow, TodoSearchRow},
+};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        id::{ProjectId, TagId, TodoId, UserId},
+        list::{ListQuery, Page},
+        todo::{
+            Priority, Todo, TodoSort,
+            event::{CreateTodo, DeleteTodo, ReorderTodo, UpdateTodo},
+            filter::{ProjectScope, TimeRange, TodoFilter},
+            position::Position,
+            search::{TodoSearch, TodoSearchHit},
+            tree::TodoTree,
+        },
+    },
+    repository::todo::TodoRepository,
+};
+use shared::error::{AppError, AppResult};
+use sqlx::{PgConnection, PgExecutor, Postgres, QueryBuilder};
+
+#[derive(new)]
+pub struct TodoRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl TodoRepository for TodoRepositoryImpl {
+    async fn create(&self, event: CreateTodo) -> AppResult<Todo> {
+        let todo_id = TodoId::new();
+        let mut tx = self.db.begin().await?;
+        lock_positions(&mut tx, event.user_id).await?;
+        let position = append_position(&mut *tx, event.user_id).await?;
+
+        // プロジェクトや親を指定した場合は、同じユーザのものであるときだけ挿入する
+        let row = sqlx::query_as!(
+            TodoRow,
+            r#"--sql
+                INSERT INTO todos (
+                    id, user_id, project_id, parent_id, title, description, priority, due_at,
+                    recurrence, position
+                )
+                SELECT
+                    $1::UUID, $2::UUID, $3::UUID, $4::UUID, $5, $6, $7::todo_priority,
+                    $8::TIMESTAMPTZ, $9, $10
+                WHERE ($3::UUID IS NULL
+                        OR EXISTS (SELECT 1 FROM projects WHERE id = $3 AND user_id = $2))
+                    AND ($4::UUID IS NULL
+                        OR EXISTS (SELECT 1 FROM todos WHERE id = $4 AND user_id = $2))
+                RETURNING
+                    id,
+                    user_id,
+                    project_id AS "project_id: ProjectId",
+                    parent_id AS "parent_id: TodoId",
+                    title,
+                    description,
+                    priority AS "priority: Priority",
+                    completed,
+                    due_at,
+                    recurrence,
+                    position,
+                    created_at,
+                    updated_at
+            "#,
+            todo_id as _,
+            event.user_id as _,
+            event.project_id as _,
+            event.parent_id as _,
+            event.title,
+            event.description,
+            event.priority as _,
+            event.due_at,
+            event.recurrence.as_ref().map(ToString::to_string),
+            position.to_string(),
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(map_sql_error)?
+        .ok_or_else(|| {
+            AppError::EntityNotFoundError("The project or parent todo was not found".into())
+        })?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Todo::try_from(row)
+    }
+
+    async fn find_all(
+        &self,
+        user_id: UserId,
+        filter: TodoFilter,
+        query: ListQuery<TodoSort>,
+    ) -> AppResult<Page<Todo>> {
+        let column = sort_column(query.sort);
+        let mut builder = QueryBuilder::new(
+            r#"--sql
+                SELECT
+                    id,
+                    user_id,
+                    project_id,
+                    parent_id,
+                    title,
+                    description,
+                    priority,
+                    completed,
+                    due_at,
+                    recurrence,
+                    position,
+                    created_at,
+                    updated_at
+                FROM todos
+                WHERE user_id = "#,
+        );
+        builder.push_bind(user_id);
+        push_filter(&mut builder, filter);
+        if let Some(cursor) = &query.cursor {
+            builder.push(" AND ");
+            push_keyset_condition(&mut builder, column, query.order, cursor)?;
+        }
+        push_order_by(&mut builder, column, query.order);
+        builder.push(" LIMIT ").push_bind(query.fetch_limit());
+
+        let rows = builder
+            .build_query_as::<TodoRow>()
+            .fetch_all(self.db.inner_ref())
+            .await
+            .map_err(AppError::SqlExecuteError)?;
+
+        Page::from_rows(
+            rows,
+            query.limit,
+            |row| row.cursor(query.sort),
+            Todo::try_from,
+        )
+    }
+
+    async fn search(&self, user_id: UserId, search: TodoSearch) -> AppResult<Vec<TodoSearchHit>> {
+        // 語単位で一致する tsvector 検索に加え、分かち書きされない日本語向けに
+        // 全語の部分一致でも拾う。関連度は ts_rank とトライグラム類似度の和とする
+        let mut builder = QueryBuilder::new(
+            r#"--sql
+                SELECT
+                    id,
+                    user_id,
+                    project_id,
+                    parent_id,
+                    title,
+                    description,
+                    priority,
+                    completed,
+                    due_at,
+                    recurrence,
+                    position,
+                    created_at,
+                    updated_at,
+                    ts_rank(search_vector, query) + similarity(title, "#,
+        );
+        builder
+            .push_bind(search.text.clone())
+            .push(") AS rank FROM todos, websearch_to_tsquery('simple', ")
+            .push_bind(search.text.clone())
+            .push(") AS query WHERE user_id = ")
+            .push_bind(user_id)
+            .push(" AND (search_vector @@ query OR (TRUE");
+        for term in search.terms() {
+            builder
+                .push(r" AND (title ILIKE '%' || ")
+                .push_bind(escape_like(term))
+                .push(r" || '%' ESCAPE '\' OR description ILIKE '%' || ")
+                .push_bind(escape_like(term))
+                .push(r" || '%' ESCAPE '\')");
+        }
+        builder
+            .push(")) ORDER BY rank DESC, id LIMIT ")
+            .push_bind(search.limit);
+
+        let rows = builder
+            .build_query_as::<TodoSearchRow>()
+            .fetch_all(self.db.inner_ref())
+            .await
+            .map_err(AppError::SqlExecuteError)?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(TodoSearchHit {
+                    snippet: search.snippet(&row.todo.title, row.todo.description.as_deref()),
+                    rank: row.rank,
+                    todo: Todo::try_from(row.todo)?,
+                })
+            })
+            .collect()
+    }
+
+    async fn find_by_id(&self, id: TodoId, user_id: UserId) -> AppResult<Option<Todo>> {
+        let row = sqlx::query_as!(
+            TodoRow,
+            r#"--sql
+                SELECT
+                    id,
+                    user_id,
+                    project_id AS "project_id: ProjectId",
+                    parent_id AS "parent_id: TodoId",
+                    title,
+                    description,
+                    priority AS "priority: Priority",
+                    completed,
+                    due_at,
+                    recurrence,
+                    position,
+                    created_at,
+                    updated_at
+                FROM todos
+                WHERE id = $1 AND user_id = $2
+            "#,
+            id as _,
+            user_id as _,
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        match row {
+            Some(row) => Ok(Some(Todo::try_from(row)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn find_tree(&self, id: TodoId, user_id: UserId) -> AppResult<Option<TodoTree>> {
+        // 子孫は親と同じユーザのものに限られるので、所有者の確認は根だけでよい
+        let rows = sqlx::query_as!(
+            TodoRow,
+            r#"--sql
+                WITH RECURSIVE tree AS (
+                    SELECT
+                        id, user_id, project_id, parent_id, title, description, priority,
+                        completed, due_at, recurrence, position, created_at, updated_at
+                    FROM todos
+                    WHERE id = $1 AND user_id = $2
+                    UNION
+                    SELECT
+                        todos.id, todos.user_id, todos.project_id, todos.parent_id, todos.title,
+                        todos.description, todos.priority, todos.completed, todos.due_at,
+                        todos.recurrence, todos.position, todos.created_at,
+                        todos.updated_at
+                    FROM todos
+                    JOIN tree ON todos.parent_id = tree.id
+                )
+                SELECT
+                    id AS "id!",
+                    user_id AS "user_id!",
+                    project_id AS "project_id: ProjectId",
+                    parent_id AS "parent_id: TodoId",
+                    title AS "title!",
+                    description,
+                    priority AS "priority!: Priority",
+                    completed AS "completed!",
+                    due_at,
+                    recurrence,
+                    position AS "position!",
+                    created_at AS "created_at!",
+                    updated_at AS "updated_at!"
+                FROM tree
+            "#,
+            id as _,
+            user_id as _,
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        let mut root = None;
+        let mut descendants = Vec::with_capacity(rows.len());
+        for row in rows {
+            let todo = Todo::try_from(row)?;
+            if todo.id == id {
+                root = Some(todo);
+            } else {
+                descendants.push(todo);
+            }
+        }
+        Ok(root.map(|root| TodoTree::build(root, descendants)))
+    }
+
+    async fn update(&self, event: UpdateTodo) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        if let Some(parent_id) = event.parent_id {
+            // 同時に付け替えて循環しないよう、自身と新しい親から根までの todo をロックしてから判定する。
+            // 別の todo の付け替えで循環ができる場合も、その todo はこちらの祖先に含まれるので直列になる
+            sqlx::query_scalar!(
+                r#"--sql
+                    WITH RECURSIVE ancestors AS (
+                        SELECT id, parent_id FROM todos WHERE id = $1 AND user_id = $3
+                        UNION
+                        SELECT todos.id, todos.parent_id
+                        FROM todos
+                        JOIN ancestors ON todos.id = ancestors.parent_id
+                    )
+                    SELECT id
+                    FROM todos
+                    WHERE (id = $2 OR id IN (SELECT id FROM ancestors)) AND user_id = $3
+                    ORDER BY id
+                    FOR UPDATE
+                "#,
+                parent_id as _,
+                event.id as _,
+                event.user_id as _,
+            )
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(AppError::SqlExecuteError)?;
+
+            // 新しい親の祖先に自身が含まれていれば、付け替えると循環する
+            let cycle = sqlx::query_scalar!(
+                r#"--sql
+                    WITH RECURSIVE ancestors AS (
+                        SELECT id, parent_id FROM todos WHERE id = $1 AND user_id = $3
+                        UNION
+                        SELECT todos.id, todos.parent_id
+                        FROM todos
+                        JOIN ancestors ON todos.id = ancestors.parent_id
+                    )
+                    SELECT EXISTS (SELECT 1 FROM ancestors WHERE id = $2) AS "cycle!"
+                "#,
+                parent_id as _,
+                event.id as _,
+                event.user_id as _,
+            )
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(AppError::SqlExecuteError)?;
+
+            if cycle {
+                return Err(AppError::UnprocessableEntity(
+                    "A todo cannot be moved under itself or its subtasks".into(),
+                ));
+            }
+        }
+
+        let res = sqlx::query!(
+            r#"--sql
+                UPDATE todos
+                SET
+                    project_id = $1,
+                    parent_id = $2,
+                    title = $3,
+                    description = $4,
+                    priority = $5,
+                    due_at = $6,
+                    recurrence = $7
+                WHERE id = $8 AND user_id = $9
+                    AND (
+                        $1::UUID IS NULL
+                        OR EXISTS (SELECT 1 FROM projects WHERE id = $1 AND user_id = $9)
+                    )
+                    AND (
+                        $2::UUID IS NULL
+                        OR EXISTS (SELECT 1 FROM todos WHERE id = $2 AND user_id = $9)
+                    )
+            "#,
+            event.project_id as _,
+            event.parent_id as _,
+            event.title,
+            event.description,
+            event.priority as _,
+            event.due_at,
+            event.recurrence.as_ref().map(ToString::to_string),
+            event.id as _,
+            event.user_id as _,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(map_sql_error)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFoundError(
+                "No todo has been updated".into(),
+            ));
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn reorder(&self, event: ReorderTodo) -> AppResult<Todo> {
+        if event.after == Some(event.id) || event.before == Some(event.id) {
+            return Err(AppError::UnprocessableEntity(
+                "A todo cannot be placed next to itself".into(),
+            ));
+        }
+
+        let mut tx = self.db.begin().await?;
+        lock_positions(&mut tx, event.user_id).await?;
+
+        // 同じ隙間に同時に動かして同じキーができないよう、動かす todo と前後の todo をロックする
+        sqlx::query_scalar!(
+            r#"--sql
+                SELECT id FROM todos WHERE id = $1 AND user_id = $2 FOR UPDATE
+            "#,
+            event.id as _,
+            event.user_id as _,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SqlExecuteError)?
+        .ok_or_else(|| AppError::EntityNotFoundError("The todo was not found".into()))?;
+
+        let mut after = match event.after {
+            Some(id) => Some(lock_position(&mut tx, id, event.user_id).await?),
+            None => None,
+        };
+        let mut before = match event.before {
+            Some(id) => Some(lock_position(&mut tx, id, event.user_id).await?),
+            None => None,
+        };
+
+        // 片方しか指定がなければ、もう片方は今そこに隣り合っている todo にする
+        if event.before.is_none() {
+            before = sqlx::query_scalar!(
+                r#"--sql
+                    SELECT position
+                    FROM todos
+                    WHERE user_id = $1 AND id <> $2 AND position > $3
+                    ORDER BY position, id
+                    LIMIT 1
+                    FOR UPDATE
+                "#,
+                event.user_id as _,
+                event.id as _,
+                after.as_ref().map(ToString::to_string),
+            )
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(AppError::SqlExecuteError)?
+            .map(|position| position.parse())
+            .transpose()?;
+        }
+        if event.after.is_none() {
+            after = sqlx::query_scalar!(
+                r#"--sql
+                    SELECT position
+                    FROM todos
+                    WHERE user_id = $1 AND id <> $2 AND ($3::TEXT IS NULL OR position < $3)
+                    ORDER BY position DESC, id DESC
+                    LIMIT 1
+                    FOR UPDATE
+                "#,
+                event.user_id as _,
+                event.id as _,
+                before.as_ref().map(ToString::to_string),
+            )
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(AppError::SqlExecuteError)?
+            .map(|position| position.parse())
+            .transpose()?;
+        }
+
+        let position = Position::between(after.as_ref(), before.as_ref())?;
+        let row = sqlx::query_as!(
+            TodoRow,
+            r#"--sql
+                UPDATE todos
+                SET position = $1
+                WHERE id = $2
+                RETURNING
+                    id,
+                    user_id,
+                    project_id AS "project_id: ProjectId",
+                    parent_id AS "parent_id: TodoId",
+                    title,
+                    description,
+                    priority AS "priority: Priority",
+                    completed,
+                    due_at,
+                    recurrence,
+                    position,
+                    created_at,
+                    updated_at
+            "#,
+            position.to_string(),
+            event.id as _,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(map_sql_error)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Todo::try_from(row)
+    }
+
+    async fn delete(&self, event: DeleteTodo) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"--sql
+                DELETE FROM todos WHERE id = $1 AND user_id = $2
+            "#,
+            event.id as _,
+            event.user_id as _,
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFoundError(
+                "No todo has been deleted".into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+// ユーザの並び順のキーを決めて書き込むまでを、トランザクションの終わりまで直列にする。
+// 末尾のキーは既存の行をロックしないと決まらないので、行ロックの代わりに advisory lock を使う。
+// 行ロックとのデッドロックを避けるため、トランザクションの最初に取ること
+pub(crate) async fn lock_positions(conn: &mut PgConnection, user_id: UserId) -> AppResult<()> {
+    sqlx::query!(
+        r#"--sql
+            SELECT pg_advisory_xact_lock(hashtext('todos.position'), hashtext($1::UUID::TEXT))
+        "#,
+        user_id as _,
+    )
+    .execute(conn)
+    .await
+    .map_err(AppError::SqlExecuteError)?;
+
+    Ok(())
+}
+
+// 末尾に追加する todo のキー。呼び出し側は先に `lock_positions` を取っておく
+pub(crate) async fn append_position(
+    executor: impl PgExecutor<'_>,
+    user_id: UserId,
+) -> AppResult<Position> {
+    let last = sqlx::query_scalar!(
+        r#"--sql
+            SELECT MAX(position) FROM todos WHERE user_id = $1
+        "#,
+        user_id as _,
+    )
+    .fetch_one(executor)
+    .await
+    .map_err(AppError::SqlExecuteError)?
+    .map(|position| position.parse::<Position>())
+    .transpose()?;
+
+    Position::between(last.as_ref(), None)
+}
+
+async fn lock_position(
+    conn: &mut PgConnection,
+    id: TodoId,
+    user_id: UserId,
+) -> AppResult<Position> {
+    sqlx::query_scalar!(
+        r#"--sql
+            SELECT position FROM todos WHERE id = $1 AND user_id = $2 FOR UPDATE
+        "#,
+        id as _,
+        user_id as _,
+    )
+    .fetch_optional(conn)
+    .await
+    .map_err(AppError::SqlExecuteError)?
+    .ok_or_else(|| AppError::EntityNotFoundError("The neighbouring todo was not found".into()))?
+    .parse()
+}
+
+// TodoFilter の各条件を ` AND ...` としてバインド付きで積む
+fn push_filter(builder: &mut QueryBuilder<'_, Postgres>, filter: TodoFilter) {
+    let TodoFilter {
+        completed,
+        overdue,
+        due,
+        created,
+        updated,
+        title,
+        tags_any,
+        tags_all,
+        project,
+        blocked,
+    } = filter;
+
+    if let Some(completed) = completed {
+        builder.push(" AND completed = ").push_bind(completed);
+    }
+    if overdue {
+        builder.push(" AND completed = FALSE AND due_at < NOW()");
+    }
+    push_time_range(builder, "due_at", due);
+    push_time_range(builder, "created_at", created);
+    push_time_range(builder, "updated_at", updated);
+    if let Some(title) = title {
+        builder
+            .push(r" AND title ILIKE '%' || ")
+            .push_bind(escape_like(&title))
+            .push(r" || '%' ESCAPE '\'");
+    }
+    if !tags_any.is_empty() {
+        builder
+            .push(
+                " AND EXISTS (SELECT 1 FROM todo_tags \
+                 WHERE todo_tags.todo_id = todos.id AND todo_tags.tag_id = ANY(",
+            )
+            .push_bind(tag_uuids(&tags_any))
+            .push("))");
+    }
+    if !tags_all.is_empty() {
+        // (todo_id, tag_id) は主キーなので、一致した行数が指定したタグ数と等しければ全部付いている
+        let tag_ids = tag_uuids(&tags_all);
+        let count = tag_ids.len() as i64;
+        builder
+            .push(
+                " AND (SELECT COUNT(*) FROM todo_tags \
+                 WHERE todo_tags.todo_id = todos.id AND todo_tags.tag_id = ANY(",
+            )
+            .push_bind(tag_ids)
+            .push(")) = ")
+            .push_bind(count);
+    }
+    if let Some(blocked) = blocked {
+        builder
+            .push(if blocked { " AND " } else { " AND NOT " })
+            .push(
+                "EXISTS (SELECT 1 FROM todo_dependencies \
+                 JOIN todos AS blockers ON blockers.id = todo_dependencies.blocker_id \
+                 WHERE todo_dependencies.todo_id = todos.id AND blockers.completed = FALSE)",
+            );
+    }
+    match project {
+        Some(ProjectScope::Inbox) => {
+            builder.push(" AND project_id IS NULL");
+        }
+        Some(ProjectScope::Project(project_id)) => {
+            builder.push(" AND project_id = ").push_bind(project_id);
+        }
+        None => {}
+    }
+}
+
+fn tag_uuids(tag_ids: &[TagId]) -> Vec<uuid::Uuid> {
+    let mut ids: Vec<uuid::Uuid> = tag_ids.iter().map(|id| id.raw()).collect();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+fn push_time_range(builder: &mut QueryBuilder<'_, Postgres>, column: &str, range: TimeRange) {
+    if let Some(after) = range.after {
+        builder.push(format!(" AND {column} >= ")).push_bind(after);
+    }
+    if let Some(before) = range.before {
+        builder.push(format!(" AND {column} < ")).push_bind(before);
+    }
+}
+
+// LIKE のワイルドカードを利用者の入力からは効かせない
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn sort_column(sort: TodoSort) -> SortColumn {
+    match sort {
+        TodoSort::CreatedAt => SortColumn::Timestamp("created_at"),
+        TodoSort::UpdatedAt => SortColumn::Timestamp("updated_at"),
+        TodoSort::Title => SortColumn::Text("title"),
+        TodoSort::Manual => SortColumn::Text("position"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::connect_database_with;
+    use crate::repository::{completion::CompletionRepositoryImpl, user::UserRepositoryImpl};
+    use chrono::{Duration, Utc};
+    use kernel::model::{
+        completion::event::CreateCompletion, id::UserId, list::SortOrder, user::event::CreateUser,
+    };
+    use kernel::repository::{completion::CompletionRepository, user::UserRepository};
+    use shared::config::AppConfig;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    async fn create_user(pool: &ConnectionPool) -> UserId {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("timestamp")
+            .as_nanos();
+        let repo = UserRepositoryImpl::new(pool.clone());
+        let event = CreateUser {
+            name: "Alice".to_string(),
+            email: format!("alice+{}@example.com", unique),
+            password: "password123".to_string(),
+        };
+        repo.create(event).await.expect("ユーザ作成が成功する").id
+    }
+
+    #[tokio::test]
+    async fn todoが作成される() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let repo = TodoRepositoryImpl::new(pool.clone());
+
+        let todo = repo
+            .create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: "牛乳を買う".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("作成が成功する");
+
+        assert_eq!(todo.user_id, user_id);
+        assert_eq!(todo.title, "牛乳を買う");
+        assert!(!todo.completed);
+        assert!(todo.due_at.is_none());
+
+        let found = repo
+            .find_by_id(todo.id, user_id)
+            .await
+            .expect("取得が成功する")
+            .expect("todoが存在する");
+        assert_eq!(found.id, todo.id);
+        assert_eq!(found.title, todo.title);
+    }
+
+    #[tokio::test]
+    async fn todo一覧は所有者のものだけを返す() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let owner = create_user(&pool).await;
+        let other = create_user(&pool).await;
+        let repo = TodoRepositoryImpl::new(pool.clone());
+
+        let mine = repo
+            .create(CreateTodo {
+                user_id: owner,
+                project_id: None,
+                parent_id: None,
+                title: "自分のtodo".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("作成が成功する");
+        repo.create(CreateTodo {
+            user_id: other,
+            project_id: None,
+            parent_id: None,
+            title: "他人のtodo".to_string(),
+            description: None,
+            priority: Priority::None,
+            due_at: None,
+            recurrence: None,
+        })
+        .await
+        .expect("作成が成功する");
+
+        let todos = repo
+            .find_all(owner, TodoFilter::default(), ListQuery::default())
+            .await
+            .expect("一覧取得")
+            .items;
+
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0].id, mine.id);
+    }
+
+    #[tokio::test]
+    async fn todo一覧はカーソルで続きを取得できる() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let owner = create_user(&pool).await;
+        let repo = TodoRepositoryImpl::new(pool.clone());
+
+        for title in ["a", "b", "c"] {
+            repo.create(CreateTodo {
+                user_id: owner,
+                project_id: None,
+                parent_id: None,
+                title: title.to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("作成が成功する");
+        }
+
+        let first = repo
+            .find_all(
+                owner,
+                TodoFilter::default(),
+                ListQuery::new(Some(2), None, Some(TodoSort::Title), Some(SortOrder::Asc))
+                    .expect("条件が正しい"),
+            )
+            .await
+            .expect("一覧取得");
+        let titles: Vec<&str> = first.items.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["a", "b"]);
+        let cursor = first.next_cursor.expect("次ページがある").encode();
+
+        let second = repo
+            .find_all(
+                owner,
+                TodoFilter::default(),
+                ListQuery::new(
+                    Some(2),
+                    Some(&cursor),
+                    Some(TodoSort::Title),
+                    Some(SortOrder::Asc),
+                )
+                .expect("条件が正しい"),
+            )
+            .await
+            .expect("一覧取得");
+        assert_eq!(second.items.len(), 1);
+        assert_eq!(second.items[0].title, "c");
+        assert!(second.next_cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn todo一覧は条件で絞り込める() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let owner = create_user(&pool).await;
+        let repo = TodoRepositoryImpl::new(pool.clone());
+        let completions = CompletionRepositoryImpl::new(pool.clone());
+        let now = Utc::now();
+
+        let mut created = Vec::new();
+        for (title, due_at) in [
+            ("牛乳を買う", Some(now - Duration::days(1))),
+            ("卵を買う", Some(now - Duration::days(2))),
+            ("100%_果汁を買う", Some(now + Duration::days(1))),
+            ("掃除", None),
+        ] {
+            let todo = repo
+                .create(CreateTodo {
+                    user_id: owner,
+                    project_id: None,
+                    parent_id: None,
+                    title: title.to_string(),
+                    description: None,
+                    priority: Priority::None,
+                    due_at,
+                    recurrence: None,
+                })
+                .await
+                .expect("作成が成功する");
+            created.push(todo);
+        }
+        completions
+            .create(CreateCompletion {
+                todo_id: created[1].id,
+                user_id: owner,
+                include_subtasks: false,
+                force: false,
+            })
+            .await
+            .expect("完了が成功する");
+
+        let repo = &repo;
+        let titles = move |filter: TodoFilter| async move {
+            let mut titles: Vec<String> = repo
+                .find_all(owner, filter, ListQuery::default())
+                .await
+                .expect("一覧取得")
+                .items
+                .into_iter()
+                .map(|todo| todo.title)
+                .collect();
+            titles.sort();
+            titles
+        };
+
+        let overdue = titles(TodoFilter {
+            overdue: true,
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(overdue, vec!["牛乳を買う"]);
+
+        let completed = titles(TodoFilter {
+            completed: Some(true),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(completed, vec!["卵を買う"]);
+
+        let due_soon = titles(TodoFilter {
+            due: TimeRange::new(Some(now), Some(now + Duration::days(2))).expect("正しい期間"),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(due_soon, vec!["100%_果汁を買う"]);
+
+        // LIKE のワイルドカードは文字として扱われる
+        let by_title = titles(TodoFilter {
+            title: Some("%_".to_string()),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(by_title, vec!["100%_果汁を買う"]);
+    }
+
+    #[tokio::test]
+    async fn todoを日本語と英語の混在した語で検索できる() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let owner = create_user(&pool).await;
+        let other = create_user(&pool).await;
+        let repo = TodoRepositoryImpl::new(pool.clone());
+
+        for (user_id, title) in [
+            (owner, "スーパーで牛乳 milk を買う"),
+            (owner, "milk tea を飲む"),
+            (owner, "卵を買う"),
+            (other, "牛乳 milk を買う"),
+        ] {
+            repo.create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: title.to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("作成が成功する");
+        }
+
+        let hits = repo
+            .search(
+                owner,
+                TodoSearch::new("牛乳", None).expect("検索語が正しい"),
+            )
+            .await
+            .expect("検索が成功する");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].snippet, "スーパーで<mark>牛乳</mark> milk を買う");
+
+        let hits = repo
+            .search(
+                owner,
+                TodoSearch::new("MILK", None).expect("検索語が正しい"),
+            )
+            .await
+            .expect("検索が成功する");
+        assert_eq!(hits.len(), 2);
+        assert!(hits[0].rank >= hits[1].rank);
+
+        repo.create(CreateTodo {
+            user_id: owner,
+            project_id: None,
+            parent_id: None,
+            title: "買い物".to_string(),
+            description: Some("低脂肪の豆乳も忘れない".to_string()),
+            priority: Priority::None,
+            due_at: None,
+            recurrence: None,
+        })
+        .await
+        .expect("作成が成功する");
+        let hits = repo
+            .search(
+                owner,
+                TodoSearch::new("豆乳", None).expect("検索語が正しい"),
+            )
+            .await
+            .expect("検索が成功する");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].snippet, "低脂肪の<mark>豆乳</mark>も忘れない");
+    }
+
+    #[tokio::test]
+    async fn todo取得は他人のtodoならnoneを返す() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let owner = create_user(&pool).await;
+        let other = create_user(&pool).await;
+        let repo = TodoRepositoryImpl::new(pool.clone());
+
+        let todo = repo
+            .create(CreateTodo {
+                user_id: owner,
+                project_id: None,
+                parent_id: None,
+                title: "自分のtodo".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("作成が成功する");
+
+        let result = repo
+            .find_by_id(todo.id, other)
+            .await
+            .expect("取得が成功する");
+
+        assert!(result.is_none());
+    }
+
+    #[tokio::test]
+    async fn todoが更新される() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let repo = TodoRepositoryImpl::new(pool.clone());
+
+        let todo = repo
+            .create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: "更新前".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("作成が成功する");
+
+        let due_at = chrono::DateTime::from_timestamp(1_800_000_000, 0).expect("日時");
+        repo.update(UpdateTodo {
+            id: todo.id,
+            user_id,
+            project_id: None,
+            parent_id: None,
+            title: "更新後".to_string(),
+            description: Some("- 低脂肪\n- 1L".to_string()),
+            priority: Priority::High,
+            due_at: Some(due_at),
+            recurrence: None,
+        })
+        .await
+        .expect("更新が成功する");
+
+        let found = repo
+            .find_by_id(todo.id, user_id)
+            .await
+            .expect("取得が成功する")
+            .expect("todoが存在する");
+        assert_eq!(found.title, "更新後");
+        assert_eq!(found.description.as_deref(), Some("- 低脂肪\n- 1L"));
+        assert_eq!(found.priority, Priority::High);
+        assert_eq!(found.due_at, Some(due_at));
+    }
+
+    #[tokio::test]
+    async fn 他人のtodoは更新できない() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let owner = create_user(&pool).await;
+        let other = create_user(&pool).await;
+        let repo = TodoRepositoryImpl::new(pool.clone());
+
+        let todo = repo
+            .create(CreateTodo {
+                user_id: owner,
+                project_id: None,
+                parent_id: None,
+                title: "自分のtodo".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("作成が成功する");
+
+        let err = repo
+            .update(UpdateTodo {
+                id: todo.id,
+                user_id: other,
+                project_id: None,
+                parent_id: None,
+                title: "乗っ取り".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect_err("他人のtodoは更新できない");
+
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+    }
+
+    #[tokio::test]
+    async fn todo削除で対象が消える() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let repo = TodoRepositoryImpl::new(pool.clone());
+
+        let todo = repo
+            .create(CreateTodo {
+                user_id,
+                project_id: None,
+                parent_id: None,
+                title: "削除対象".to_string(),
+                description: None,
+                priority: Priority::None,
+                due_at: None,
+                recurrence: None,
+            })
+            .await
+            .expect("作成が成功する");
+
+        repo.delete(DeleteTodo {
+            id: todo.id,
+            user_id,
+        })
+        .await
+        .expect("削除が成功する");
+
+        let result = repo
+            .find_by_id(todo.id, user_id)
+            .await
+            .expect("取得が成功する");
+        assert!(result.is_none());
+    }
+
+    #[tokio::test]
+    async fn 存在しないtodoは削除できない() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let repo = TodoRepositoryImpl::new(pool);
+
+        let err = repo
+            .delete(DeleteTodo {
+                id: TodoId::new(),
+                user_id: UserId::new(),
+            })
+            .await
+            .expect_err("存在しないため失敗する");
+
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+    }
+
+    async fn create_subtask(
+        repo: &TodoRepositoryImpl,
+        user_id: UserId,
+        parent_id: Option<TodoId>,
+        title: &str,
+    ) -> Todo {
+        repo.create(CreateTodo {
+            user_id,
+            project_id: None,
+            parent_id,
+            title: title.to_string(),
+            description: None,
+            priority: Priority::None,
+            due_at: None,
+            recurrence: None,
+        })
+        .await
+        .expect("作成が成功する")
+    }
+
+    #[tokio::test]
+    async fn 子孫を木構造で取得し親の削除で子孫も消える() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let repo = TodoRepositoryImpl::new(pool.clone());
+
+        let root = create_subtask(&repo, user_id, None, "引っ越し").await;
+        let packing = create_subtask(&repo, user_id, Some(root.id), "荷造り").await;
+        let books = create_subtask(&repo, user_id, Some(packing.id), "本を詰める").await;
+        CompletionRepositoryImpl::new(pool.clone())
+            .create(CreateCompletion {
+                todo_id: books.id,
+                user_id,
+                include_subtasks: false,
+                force: false,
+            })
+            .await
+            .expect("完了が成功する");
+
+        let tree = repo
+            .find_tree(root.id, user_id)
+            .await
+            .expect("取得が成功する")
+            .expect("todoが存在する");
+        assert_eq!(tree.progress.total, 2);
+        assert_eq!(tree.progress.done, 1);
+        assert_eq!(tree.children[0].children[0].todo.id, books.id);
+
+        repo.delete(DeleteTodo {
+            id: root.id,
+            user_id,
+        })
+        .await
+        .expect("削除が成功する");
+        assert!(
+            repo.find_by_id(books.id, user_id)
+                .await
+                .expect("取得が成功する")
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn 親を子孫に付け替えることはできない() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let repo = TodoRepositoryImpl::new(pool.clone());
+
+        let root = create_subtask(&repo, user_id, None, "親").await;
+        let child = create_subtask(&repo, user_id, Some(root.id), "子").await;
+        let grandchild = create_subtask(&repo, user_id, Some(child.id), "孫").await;
+
+        for parent_id in [root.id, grandchild.id] {
+            let err = repo
+                .update(UpdateTodo {
+                    id: root.id,
+                    user_id,
+                    project_id: None,
+                    parent_id: Some(parent_id),
+                    title: "親".to_string(),
+                    description: None,
+                    priority: Priority::None,
+                    due_at: None,
+                    recurrence: None,
+                })
+                .await
+                .expect_err("循環する付け替えは失敗する");
+            assert!(matches!(err, AppError::UnprocessableEntity(_)));
+        }
+
+        // 循環しなければ付け替えられる
+        repo.update(UpdateTodo {
+            id: grandchild.id,
+            user_id,
+            project_id: None,
+            parent_id: Some(root.id),
+            title: "孫".to_string(),
+            description: None,
+            priority: Priority::None,
+            due_at: None,
+            recurrence: None,
+        })
+        .await
+        .expect("更新が成功する");
+    }
+
+    #[tokio::test]
+    async fn 同時に互いの下へ付け替えても循環しない() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let repo = TodoRepositoryImpl::new(pool.clone());
+        let a = create_subtask(&repo, user_id, None, "a").await;
+        let b = create_subtask(&repo, user_id, None, "b").await;
+
+        let handles = [(a.id, b.id, "a"), (b.id, a.id, "b")]
+            .into_iter()
+            .map(|(id, parent_id, title)| {
+                let repo = TodoRepositoryImpl::new(pool.clone());
+                tokio::spawn(async move {
+                    repo.update(UpdateTodo {
+                        id,
+                        user_id,
+                        project_id: None,
+                        parent_id: Some(parent_id),
+                        title: title.to_string(),
+                        description: None,
+                        priority: Priority::None,
+                        due_at: None,
+                        recurrence: None,
+                    })
+                    .await
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut succeeded = 0;
+        for handle in handles {
+            match handle.await.expect("タスクが終わる") {
+                Ok(()) => succeeded += 1,
+                Err(err) => assert!(matches!(err, AppError::UnprocessableEntity(_))),
+            }
+        }
+        assert_eq!(succeeded, 1);
+    }
+
+    #[tokio::test]
+    async fn 同時に作成しても並び順のキーは重ならない() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let handles = (0..8)
+            .map(|i| {
+                let repo = TodoRepositoryImpl::new(pool.clone());
+                tokio::spawn(async move {
+                    repo.create(CreateTodo {
+                        user_id,
+                        project_id: None,
+                        parent_id: None,
+                        title: format!("todo{i}"),
+                        description: None,
+                        priority: Priority::None,
+                        due_at: None,
+                        recurrence: None,
+                    })
+                    .await
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut positions = Vec::new();
+        for handle in handles {
+            let todo = handle
+                .await
+                .expect("タスクが終わる")
+                .expect("作成が成功する");
+            positions.push(todo.position);
+        }
+        positions.sort();
+        positions.dedup();
+        assert_eq!(positions.len(), 8);
+    }
+
+    #[tokio::test]
+    async fn 手動の並び順で動かした行のキーだけが変わる() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let repo = TodoRepositoryImpl::new(pool.clone());
+        let mut ids = Vec::new();
+        for title in ["a", "b", "c"] {
+            let todo = repo
+                .create(CreateTodo {
+                    user_id,
+                    project_id: None,
+                    parent_id: None,
+                    title: title.to_string(),
+                    description: None,
+                    priority: Priority::None,
+                    due_at: None,
+                    recurrence: None,
+                })
+                .await
+                .expect("作成が成功する");
+            ids.push(todo.id);
+        }
+        let (a, b, c) = (ids[0], ids[1], ids[2]);
+        let repo = &repo;
+        let titles = move || async move {
+            let query =
+                ListQuery::new(None, None, Some(TodoSort::Manual), None).expect("条件が正しい");
+            repo.find_all(user_id, TodoFilter::default(), query)
+                .await
+                .expect("一覧取得")
+                .items
+                .into_iter()
+                .map(|todo| todo.title)
+                .collect::<Vec<_>>()
+        };
+        let reorder = |id, after, before| ReorderTodo {
+            id,
+            user_id,
+            after,
+            before,
+        };
+        // 新しい todo は末尾に追加される
+        assert_eq!(titles().await, vec!["a", "b", "c"]);
+
+        let before_b = repo
+            .find_by_id(b, user_id)
+            .await
+            .expect("取得が成功する")
+            .expect("todoが存在する")
+            .position;
+        let moved = repo
+            .reorder(reorder(c, Some(a), Some(b)))
+            .await
+            .expect("並べ替えが成功する");
+        assert_eq!(titles().await, vec!["a", "c", "b"]);
+        let after_b = repo
+            .find_by_id(b, user_id)
+            .await
+            .expect("取得が成功する")
+            .expect("todoが存在する")
+            .position;
+        assert_eq!(before_b, after_b);
+        assert!(moved.position < after_b);
+
+        // 片方だけなら隣の todo との間に、どちらもなければ末尾に入れる
+        repo.reorder(reorder(b, Some(a), None))
+            .await
+            .expect("並べ替えが成功する");
+        assert_eq!(titles().await, vec!["a", "b", "c"]);
+        repo.reorder(reorder(a, None, None))
+            .await
+            .expect("並べ替えが成功する");
+        assert_eq!(titles().await, vec!["b", "c", "a"]);
+        repo.reorder(reorder(a, None, Some(b)))
+            .await
+            .expect("並べ替えが成功する");
+        assert_eq!(titles().await, vec!["a", "b", "c"]);
+
+        let err = repo
+            .reorder(reorder(a, Some(c), Some(b)))
+            .await
+            .expect_err("前後が逆なら失敗する");
+        assert!(matches!(err, AppError::UnprocessableEntity(_)));
+    }
+
+    #[tokio::test]
+    async fn 他人のtodoの隣には動かせない() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let user_id = create_user(&pool).await;
+        let other = create_user(&pool).await;
+        let repo = TodoRepositoryImpl::new(pool.clone());
+        let create = |user_id| CreateTodo {
+            user_id,
+            project_id: None,
+            parent_id: None,
+            title: "todo".to_string(),
+            description: None,
+            priority: Priority::None,
+            due_at: None,
+            recurrence: None,
+        };
+        let mine = repo.create(create(user_id)).await.expect("作成が成功する");
+        let others = repo.create(create(other)).await.expect("作成が成功する");
+
+        let err = repo
+            .reorder(ReorderTodo {
+                id: mine.id,
+                user_id,
+                after: Some(others.id),
+                before: None,
+            })
+            .await
+            .expect_err("他人のtodoの隣には動かせない");
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+    }
+}
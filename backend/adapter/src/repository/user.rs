@@ -1,17 +1,24 @@
-use crate::database::{ConnectionPool, model::user::UserRow};
+use crate::database::{
+    ConnectionPool,
+    list::{SortColumn, push_keyset_condition, push_order_by},
+    map_sql_error,
+    model::user::UserRow,
+};
 use async_trait::async_trait;
 use derive_new::new;
 use kernel::{
     model::{
         id::UserId,
+        list::{ListQuery, Page},
         user::{
-            User,
-            event::{CreateUser, DeleteUser},
+            User, UserSort,
+            event::{CreateUser, DeleteUser, UpdateUserPassword},
         },
     },
     repository::user::UserRepository,
 };
 use shared::error::{AppError, AppResult};
+use sqlx::QueryBuilder;
 
 #[derive(new)]
 pub struct UserRepositoryImpl {
@@ -36,7 +43,7 @@ impl UserRepository for UserRepositoryImpl {
         )
         .execute(self.db.inner_ref())
         .await
-        .map_err(AppError::SqlExecuteError)?;
+        .map_err(map_sql_error)?;
 
         if res.rows_affected() == 0 {
             return Err(AppError::NoRowsAffectedError(
@@ -75,9 +82,9 @@ impl UserRepository for UserRepositoryImpl {
         }
     }
 
-    async fn find_all(&self) -> AppResult<Vec<User>> {
-        let users = sqlx::query_as!(
-            UserRow,
+    async fn find_all(&self, query: ListQuery<UserSort>) -> AppResult<Page<User>> {
+        let column = sort_column(query.sort);
+        let mut builder = QueryBuilder::new(
             r#"--sql
                 SELECT
                     id,
@@ -86,17 +93,65 @@ impl UserRepository for UserRepositoryImpl {
                     created_at,
                     updated_at
                 FROM users
-                ORDER BY created_at DESC
             "#,
+        );
+        if let Some(cursor) = &query.cursor {
+            builder.push(" WHERE ");
+            push_keyset_condition(&mut builder, column, query.order, cursor)?;
+        }
+        push_order_by(&mut builder, column, query.order);
+        builder.push(" LIMIT ").push_bind(query.fetch_limit());
+
+        let rows = builder
+            .build_query_as::<UserRow>()
+            .fetch_all(self.db.inner_ref())
+            .await
+            .map_err(AppError::SqlExecuteError)?;
+
+        Page::from_rows(
+            rows,
+            query.limit,
+            |row| row.cursor(query.sort),
+            User::try_from,
         )
-        .fetch_all(self.db.inner_ref())
+    }
+
+    async fn update_password(&self, event: UpdateUserPassword) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        let original_password_hash = sqlx::query!(
+            r#"--sql
+                SELECT password_hash FROM users WHERE id = $1 FOR UPDATE
+            "#,
+            event.user_id as _
+        )
+        .fetch_optional(&mut *tx)
         .await
         .map_err(AppError::SqlExecuteError)?
-        .into_iter()
-        .filter_map(|row| User::try_from(row).ok())
-        .collect();
+        .ok_or_else(|| AppError::EntityNotFoundError("The user was not found".into()))?
+        .password_hash;
 
-        Ok(users)
+        if !bcrypt::verify(&event.current_password, &original_password_hash)? {
+            return Err(AppError::Unauthorized(
+                "The current password is incorrect".into(),
+            ));
+        }
+
+        let new_password_hash = hash_password(&event.new_password)?;
+        sqlx::query!(
+            r#"--sql
+                UPDATE users SET password_hash = $2 WHERE id = $1
+            "#,
+            event.user_id as _,
+            new_password_hash,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SqlExecuteError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
     }
 
     async fn delete(&self, event: DeleteUser) -> AppResult<()> {
@@ -120,7 +175,15 @@ impl UserRepository for UserRepositoryImpl {
     }
 }
 
-fn hash_password(password: &str) -> AppResult<String> {
+fn sort_column(sort: UserSort) -> SortColumn {
+    match sort {
+        UserSort::CreatedAt => SortColumn::Timestamp("created_at"),
+        UserSort::Name => SortColumn::Text("name"),
+        UserSort::Email => SortColumn::Text("email"),
+    }
+}
+
+pub(crate) fn hash_password(password: &str) -> AppResult<String> {
     bcrypt::hash(password, bcrypt::DEFAULT_COST).map_err(AppError::from)
 }
 
@@ -128,8 +191,8 @@ fn hash_password(password: &str) -> AppResult<String> {
 mod tests {
     use super::*;
     use crate::database::connect_database_with;
-    use kernel::model::id::UserId;
     use kernel::model::user::event::CreateUser;
+    use kernel::model::{id::UserId, list::MAX_LIMIT};
     use shared::config::AppConfig;
     use sqlx::Row;
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -199,7 +262,7 @@ mod tests {
         };
         let err = repo.create(second).await.expect_err("重複は失敗");
 
-        assert!(matches!(err, AppError::SqlExecuteError(_)));
+        assert!(matches!(err, AppError::Conflict(ref field) if field == "email"));
     }
 
     #[tokio::test]
@@ -214,7 +277,11 @@ mod tests {
             .as_nanos();
         let name = "Alice".to_string();
         let email = format!("alice+{}@example.com", unique);
-        let before = repo.find_all().await.expect("一覧取得");
+        let query = || ListQuery {
+            limit: MAX_LIMIT,
+            ..Default::default()
+        };
+        let before = repo.find_all(query()).await.expect("一覧取得").items;
         let before_count = before.iter().filter(|user| user.email == email).count();
         let event = CreateUser {
             name: name.clone(),
@@ -224,7 +291,7 @@ mod tests {
 
         repo.create(event).await.expect("作成が成功する");
 
-        let after = repo.find_all().await.expect("一覧取得");
+        let after = repo.find_all(query()).await.expect("一覧取得").items;
         let after_count = after.iter().filter(|user| user.email == email).count();
 
         assert_eq!(after_count, before_count + 1);
@@ -328,4 +395,69 @@ mod tests {
 
         assert!(result.is_none());
     }
+
+    #[tokio::test]
+    async fn パスワードが更新される() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let repo = UserRepositoryImpl::new(pool.clone());
+
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("timestamp")
+            .as_nanos();
+        let event = CreateUser {
+            name: "Alice".to_string(),
+            email: format!("alice+{}@example.com", unique),
+            password: "password123".to_string(),
+        };
+        let user = repo.create(event).await.expect("作成が成功する");
+
+        repo.update_password(UpdateUserPassword {
+            user_id: user.id,
+            current_password: "password123".to_string(),
+            new_password: "new-password456".to_string(),
+        })
+        .await
+        .expect("更新が成功する");
+
+        let row = sqlx::query("SELECT password_hash FROM users WHERE id = $1")
+            .bind(user.id)
+            .fetch_one(pool.inner_ref())
+            .await
+            .expect("DBから取得できる");
+        let password_hash: String = row.try_get("password_hash").expect("password_hash取得");
+
+        assert!(bcrypt::verify("new-password456", &password_hash).expect("hash検証"));
+        assert!(!bcrypt::verify("password123", &password_hash).expect("hash検証"));
+    }
+
+    #[tokio::test]
+    async fn パスワード更新は現在のパスワード不一致で失敗する() {
+        let cfg = AppConfig::new().expect("DATABASE_* 環境変数が必要");
+        let pool = connect_database_with(&cfg.database);
+        let repo = UserRepositoryImpl::new(pool.clone());
+
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("timestamp")
+            .as_nanos();
+        let event = CreateUser {
+            name: "Alice".to_string(),
+            email: format!("alice+{}@example.com", unique),
+            password: "password123".to_string(),
+        };
+        let user = repo.create(event).await.expect("作成が成功する");
+
+        let err = repo
+            .update_password(UpdateUserPassword {
+                user_id: user.id,
+                current_password: "wrong-password".to_string(),
+                new_password: "new-password456".to_string(),
+            })
+            .await
+            .expect_err("不一致は失敗する");
+
+        assert!(matches!(err, AppError::Unauthorized(_)));
+    }
 }
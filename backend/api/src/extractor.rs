@@ -0,0 +1,517 @@
+use axum::{
+    extract::{ConnectInfo, FromRequestParts, OriginalUri},
+    http::{
+        Method,
+        header::{AUTHORIZATION, USER_AGENT},
+        request::Parts,
+    },
+};
+use kernel::model::{
+    auth::{AccessToken, AccessTokenId, ClientInfo},
+    id::UserId,
+    personal_access_token::{PersonalAccessToken, PersonalAccessTokenSecret, TokenScope},
+    user::User,
+};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+use std::{convert::Infallible, net::SocketAddr};
+
+// リクエストの認証に使った資格情報
+pub enum Credential {
+    // ログインで発行した JWT。すべての操作ができる
+    Session(AccessTokenId),
+    // スコープで許された操作だけができる
+    PersonalAccessToken(PersonalAccessToken),
+}
+
+pub struct AuthorizedUser {
+    pub credential: Credential,
+    pub user: User,
+}
+
+impl AuthorizedUser {
+    pub fn new(access_token_id: AccessTokenId, user: User) -> Self {
+        Self {
+            credential: Credential::Session(access_token_id),
+            user,
+        }
+    }
+
+    pub fn id(&self) -> UserId {
+        self.user.id
+    }
+
+    // ログアウトなどセッションを前提とする操作のために、アクセストークンの jti を返す
+    pub fn access_token_id(&self) -> AppResult<&AccessTokenId> {
+        match &self.credential {
+            Credential::Session(access_token_id) => Ok(access_token_id),
+            Credential::PersonalAccessToken(_) => Err(AppError::ForbiddenOperation(
+                "This operation requires a login session".into(),
+            )),
+        }
+    }
+}
+
+// ハンドラのテスト用に、ログインセッションで認証したユーザを作る
+#[cfg(test)]
+impl AuthorizedUser {
+    pub fn for_test(user_id: UserId) -> Self {
+        Self::new(
+            AccessTokenId("test-token".to_string()),
+            User {
+                id: user_id,
+                name: "Alice".to_string(),
+                email: "alice@example.com".to_string(),
+            },
+        )
+    }
+}
+
+// パーソナルアクセストークンで呼ぶのに必要なスコープ。`/api/v1` の次の区切りで対象を見分ける
+fn required_scope(parts: &Parts) -> AppResult<TokenScope> {
+    let path = parts
+        .extensions
+        .get::<OriginalUri>()
+        .map_or(parts.uri.path(), |OriginalUri(uri)| uri.path());
+    let resource = path
+        .trim_start_matches("/api/v1")
+        .trim_start_matches('/')
+        .split('/')
+        .next()
+        .unwrap_or_default();
+    match resource {
+        // トークンでトークンやセッションを増やしたり消したりできないようにする
+        "auth" => Err(AppError::ForbiddenOperation(
+            "Personal access tokens cannot manage sessions or tokens".into(),
+        )),
+        "todos" | "tags" | "projects" => Ok(match parts.method {
+            Method::GET | Method::HEAD => TokenScope::TodosRead,
+            _ => TokenScope::TodosWrite,
+        }),
+        _ => Ok(TokenScope::Admin),
+    }
+}
+
+impl AuthorizedUser {
+    async fn from_personal_access_token(
+        parts: &Parts,
+        registry: &AppRegistry,
+        secret: PersonalAccessTokenSecret,
+    ) -> AppResult<(UserId, Credential)> {
+        let token = registry
+            .personal_access_token_repository()
+            .authenticate(&secret)
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("Invalid token".into()))?;
+        let required = required_scope(parts)?;
+        if !token.allows(required) {
+            return Err(AppError::ForbiddenOperation(format!(
+                "The token does not have the {} scope",
+                required.as_str()
+            )));
+        }
+        Ok((token.user_id, Credential::PersonalAccessToken(token)))
+    }
+
+    async fn from_access_token(
+        registry: &AppRegistry,
+        access_token: AccessToken,
+    ) -> AppResult<(UserId, Credential)> {
+        // 署名と期限を確かめたうえで、ログアウト済みでないかを許可リストで確かめる
+        let claims = registry.token_service().verify(&access_token)?;
+        let user_id = registry
+            .auth_repository()
+            .fetch_user_id_from_token(&claims.jti)
+            .await?
+            .filter(|user_id| *user_id == claims.sub)
+            .ok_or_else(|| AppError::Unauthorized("Invalid token".into()))?;
+        Ok((user_id, Credential::Session(claims.jti)))
+    }
+}
+
+impl FromRequestParts<AppRegistry> for AuthorizedUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        registry: &AppRegistry,
+    ) -> Result<Self, Self::Rejection> {
+        let token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| AppError::Unauthorized("Bearer token is required".into()))?;
+
+        let (user_id, credential) = match PersonalAccessTokenSecret::parse(token) {
+            Some(secret) => Self::from_personal_access_token(parts, registry, secret).await?,
+            None => Self::from_access_token(registry, AccessToken(token.to_string())).await?,
+        };
+
+        let user = registry
+            .user_repository()
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("The user was not found".into()))?;
+
+        Ok(Self { credential, user })
+    }
+}
+
+// ログインした端末の情報。クライアントが自由に送れる値なので、表示にだけ使う
+pub struct RequestClient(pub ClientInfo);
+
+impl<S: Send + Sync> FromRequestParts<S> for RequestClient {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        // プロキシを経由していれば最初の転送元を、そうでなければ接続元を使う
+        let ip_address = parts
+            .headers
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+            .or_else(|| {
+                parts
+                    .extensions
+                    .get::<ConnectInfo<SocketAddr>>()
+                    .map(|ConnectInfo(addr)| addr.ip().to_string())
+            });
+
+        Ok(Self(ClientInfo {
+            user_agent,
+            ip_address,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{Request, Uri};
+    use chrono::Utc;
+    use kernel::{
+        model::id::PersonalAccessTokenId,
+        repository::{
+            auth::{AuthRepository, MockAuthRepository},
+            personal_access_token::{
+                MockPersonalAccessTokenRepository, PersonalAccessTokenRepository,
+            },
+            user::{MockUserRepository, UserRepository},
+        },
+        service::token::TokenService,
+    };
+    use registry::MockAppRegistryExt;
+    use shared::config::{JwtKey, JwtKeys};
+    use std::sync::Arc;
+
+    fn token_service() -> TokenService {
+        let current = JwtKey {
+            id: "test".to_string(),
+            secret: "test-secret".to_string(),
+        };
+        TokenService::new(
+            &JwtKeys {
+                current,
+                previous: Vec::new(),
+            },
+            3600,
+        )
+    }
+
+    fn parts_with(authorization: Option<&str>) -> Parts {
+        let mut builder = Request::builder();
+        if let Some(authorization) = authorization {
+            builder = builder.header(AUTHORIZATION, authorization);
+        }
+        let (parts, _body) = builder.body(()).expect("リクエスト生成").into_parts();
+        parts
+    }
+
+    fn registry_with(auth_repo: MockAuthRepository, user_repo: MockUserRepository) -> AppRegistry {
+        let mut registry = MockAppRegistryExt::new();
+        let auth_repo: Arc<dyn AuthRepository> = Arc::new(auth_repo);
+        let user_repo: Arc<dyn UserRepository> = Arc::new(user_repo);
+        registry.expect_auth_repository().return_const(auth_repo);
+        registry.expect_user_repository().return_const(user_repo);
+        registry
+            .expect_token_service()
+            .return_const(Arc::new(token_service()));
+        Arc::new(registry)
+    }
+
+    #[tokio::test]
+    async fn 有効なトークンならユーザを取得できる() {
+        let user_id = UserId::new();
+        let jti = AccessTokenId::generate();
+        let token = token_service()
+            .issue(user_id, jti.clone())
+            .expect("発行が成功する");
+        let mut auth_repo = MockAuthRepository::new();
+        auth_repo
+            .expect_fetch_user_id_from_token()
+            .withf({
+                let jti = jti.clone();
+                move |id| *id == jti
+            })
+            .returning(move |_| Ok(Some(user_id)));
+        let mut user_repo = MockUserRepository::new();
+        user_repo.expect_find_by_id().returning(|id| {
+            Ok(Some(User {
+                id,
+                name: "Alice".to_string(),
+                email: "alice@example.com".to_string(),
+            }))
+        });
+        let registry = registry_with(auth_repo, user_repo);
+
+        let mut parts = parts_with(Some(&format!("Bearer {}", token.0)));
+        let user = AuthorizedUser::from_request_parts(&mut parts, &registry)
+            .await
+            .expect("認証は成功する");
+
+        assert_eq!(user.id(), user_id);
+        assert_eq!(user.access_token_id().expect("セッションで認証する"), &jti);
+    }
+
+    #[tokio::test]
+    async fn トークンがなければ401になる() {
+        let registry = registry_with(MockAuthRepository::new(), MockUserRepository::new());
+
+        let mut parts = parts_with(None);
+        let err = AuthorizedUser::from_request_parts(&mut parts, &registry)
+            .await
+            .err()
+            .expect("認証は失敗する");
+
+        assert!(matches!(err, AppError::Unauthorized(_)));
+    }
+
+    #[tokio::test]
+    async fn 署名を検証できないトークンなら401になる() {
+        let mut auth_repo = MockAuthRepository::new();
+        auth_repo.expect_fetch_user_id_from_token().never();
+        let registry = registry_with(auth_repo, MockUserRepository::new());
+
+        let mut parts = parts_with(Some("Bearer unknown-token"));
+        let err = AuthorizedUser::from_request_parts(&mut parts, &registry)
+            .await
+            .err()
+            .expect("認証は失敗する");
+
+        assert!(matches!(err, AppError::Unauthorized(_)));
+    }
+
+    #[tokio::test]
+    async fn 許可リストにないトークンなら401になる() {
+        let token = token_service()
+            .issue(UserId::new(), AccessTokenId::generate())
+            .expect("発行が成功する");
+        let mut auth_repo = MockAuthRepository::new();
+        auth_repo
+            .expect_fetch_user_id_from_token()
+            .returning(|_| Ok(None));
+        let mut user_repo = MockUserRepository::new();
+        user_repo.expect_find_by_id().never();
+        let registry = registry_with(auth_repo, user_repo);
+
+        let mut parts = parts_with(Some(&format!("Bearer {}", token.0)));
+        let err = AuthorizedUser::from_request_parts(&mut parts, &registry)
+            .await
+            .err()
+            .expect("認証は失敗する");
+
+        assert!(matches!(err, AppError::Unauthorized(_)));
+    }
+
+    #[tokio::test]
+    async fn 端末の情報は転送元のアドレスを優先して取得する() {
+        let (mut parts, _body) = Request::builder()
+            .header(USER_AGENT, "todo-cli/0.1.0")
+            .header("x-forwarded-for", "203.0.113.7, 10.0.0.1")
+            .body(())
+            .expect("リクエスト生成")
+            .into_parts();
+        parts
+            .extensions
+            .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 50000))));
+
+        let Ok(RequestClient(client)) = RequestClient::from_request_parts(&mut parts, &()).await;
+
+        assert_eq!(client.user_agent.as_deref(), Some("todo-cli/0.1.0"));
+        assert_eq!(client.ip_address.as_deref(), Some("203.0.113.7"));
+    }
+
+    #[tokio::test]
+    async fn 転送元がなければ接続元のアドレスを使う() {
+        let mut parts = parts_with(None);
+        parts
+            .extensions
+            .insert(ConnectInfo(SocketAddr::from(([192, 0, 2, 1], 50000))));
+
+        let Ok(RequestClient(client)) = RequestClient::from_request_parts(&mut parts, &()).await;
+
+        assert_eq!(client.user_agent, None);
+        assert_eq!(client.ip_address.as_deref(), Some("192.0.2.1"));
+    }
+
+    // 指定したスコープのパーソナルアクセストークンで、ネストしたルータの中から呼ばれたときのリクエスト
+    fn personal_access_token_request(
+        method: Method,
+        path: &str,
+        scopes: Vec<TokenScope>,
+    ) -> (Parts, AppRegistry) {
+        let secret = PersonalAccessTokenSecret::generate();
+        let (mut parts, _body) = Request::builder()
+            .method(method)
+            .uri("/")
+            .header(AUTHORIZATION, format!("Bearer {}", secret.0))
+            .body(())
+            .expect("リクエスト生成")
+            .into_parts();
+        parts
+            .extensions
+            .insert(OriginalUri(path.parse::<Uri>().expect("URI")));
+
+        let mut token_repo = MockPersonalAccessTokenRepository::new();
+        token_repo
+            .expect_authenticate()
+            .withf(move |candidate| *candidate == secret)
+            .returning(move |_| {
+                Ok(Some(PersonalAccessToken {
+                    id: PersonalAccessTokenId::new(),
+                    user_id: UserId::new(),
+                    name: "ci".to_string(),
+                    scopes: scopes.clone(),
+                    expires_at: None,
+                    last_used_at: Some(Utc::now()),
+                    created_at: Utc::now(),
+                }))
+            });
+        let mut user_repo = MockUserRepository::new();
+        user_repo.expect_find_by_id().returning(|id| {
+            Ok(Some(User {
+                id,
+                name: "Alice".to_string(),
+                email: "alice@example.com".to_string(),
+            }))
+        });
+        let mut auth_repo = MockAuthRepository::new();
+        auth_repo.expect_fetch_user_id_from_token().never();
+        let mut registry = MockAppRegistryExt::new();
+        let token_repo: Arc<dyn PersonalAccessTokenRepository> = Arc::new(token_repo);
+        let user_repo: Arc<dyn UserRepository> = Arc::new(user_repo);
+        let auth_repo: Arc<dyn AuthRepository> = Arc::new(auth_repo);
+        registry
+            .expect_personal_access_token_repository()
+            .return_const(token_repo);
+        registry.expect_user_repository().return_const(user_repo);
+        registry.expect_auth_repository().return_const(auth_repo);
+        (parts, Arc::new(registry))
+    }
+
+    #[tokio::test]
+    async fn 読み取りスコープのトークンでtodoを参照できる() {
+        let (mut parts, registry) = personal_access_token_request(
+            Method::GET,
+            "/api/v1/todos?limit=10",
+            vec![TokenScope::TodosRead],
+        );
+
+        let user = AuthorizedUser::from_request_parts(&mut parts, &registry)
+            .await
+            .expect("認証は成功する");
+
+        assert!(matches!(
+            user.credential,
+            Credential::PersonalAccessToken(_)
+        ));
+        assert!(matches!(
+            user.access_token_id(),
+            Err(AppError::ForbiddenOperation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn スコープが足りないトークンは403になる() {
+        for (method, path, scopes) in [
+            (Method::POST, "/api/v1/todos", vec![TokenScope::TodosRead]),
+            (
+                Method::GET,
+                "/api/v1/users",
+                vec![TokenScope::TodosRead, TokenScope::TodosWrite],
+            ),
+        ] {
+            let (mut parts, registry) = personal_access_token_request(method, path, scopes);
+
+            let err = AuthorizedUser::from_request_parts(&mut parts, &registry)
+                .await
+                .err()
+                .expect("認証は失敗する");
+
+            assert!(matches!(err, AppError::ForbiddenOperation(_)));
+        }
+    }
+
+    #[tokio::test]
+    async fn 書き込みスコープのトークンでtodoを変更でき管理者スコープならユーザも扱える() {
+        for (method, path, scopes) in [
+            (
+                Method::PATCH,
+                "/api/v1/tags/abc",
+                vec![TokenScope::TodosWrite],
+            ),
+            (Method::GET, "/api/v1/users/me", vec![TokenScope::Admin]),
+        ] {
+            let (mut parts, registry) = personal_access_token_request(method, path, scopes);
+
+            AuthorizedUser::from_request_parts(&mut parts, &registry)
+                .await
+                .expect("認証は成功する");
+        }
+    }
+
+    #[tokio::test]
+    async fn パーソナルアクセストークンではトークンやセッションを管理できない() {
+        let (mut parts, registry) = personal_access_token_request(
+            Method::POST,
+            "/api/v1/auth/tokens",
+            vec![TokenScope::Admin],
+        );
+
+        let err = AuthorizedUser::from_request_parts(&mut parts, &registry)
+            .await
+            .err()
+            .expect("認証は失敗する");
+
+        assert!(matches!(err, AppError::ForbiddenOperation(_)));
+    }
+
+    #[tokio::test]
+    async fn 見つからないパーソナルアクセストークンは401になる() {
+        let mut token_repo = MockPersonalAccessTokenRepository::new();
+        token_repo.expect_authenticate().returning(|_| Ok(None));
+        let mut registry = MockAppRegistryExt::new();
+        let token_repo: Arc<dyn PersonalAccessTokenRepository> = Arc::new(token_repo);
+        registry
+            .expect_personal_access_token_repository()
+            .return_const(token_repo);
+        let registry: AppRegistry = Arc::new(registry);
+
+        let mut parts = parts_with(Some("Bearer rtpat_revoked"));
+        let err = AuthorizedUser::from_request_parts(&mut parts, &registry)
+            .await
+            .err()
+            .expect("認証は失敗する");
+
+        assert!(matches!(err, AppError::Unauthorized(_)));
+    }
+}
@@ -0,0 +1,447 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use garde::Validate;
+use kernel::{
+    model::{
+        auth::{
+            AccessToken, AccessTokenId, RefreshToken,
+            event::{DeleteSession, RotateToken, StoreToken},
+        },
+        id::{SessionId, UserId},
+    },
+    repository::auth::AuthRepository,
+};
+use registry::AppRegistry;
+
+use crate::{
+    extractor::{AuthorizedUser, RequestClient},
+    model::auth::{
+        AccessTokenResponse, LoginRequest, RefreshTokenRequest, SessionResponse, SessionsResponse,
+    },
+};
+use shared::error::{AppError, AppResult};
+
+pub async fn auth_login(
+    State(registry): State<AppRegistry>,
+    RequestClient(client): RequestClient,
+    Json(req): Json<LoginRequest>,
+) -> AppResult<(StatusCode, Json<AccessTokenResponse>)> {
+    req.validate()?;
+
+    let auth_repository = registry.auth_repository();
+    let credential = auth_repository
+        .find_by_email(req.email)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized("Invalid email or password".into()))?;
+
+    if !credential.verify_password(&req.password)? {
+        return Err(AppError::Unauthorized("Invalid email or password".into()));
+    }
+
+    let access_token_id = AccessTokenId::generate();
+    let access_token = registry
+        .token_service()
+        .issue(credential.id, access_token_id.clone())?;
+    let refresh_token = RefreshToken::generate();
+    auth_repository
+        .store_token(StoreToken {
+            user_id: credential.id,
+            access_token_id,
+            refresh_token: refresh_token.clone(),
+            client,
+        })
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(token_response(
+            auth_repository.as_ref(),
+            credential.id,
+            access_token,
+            refresh_token,
+        )),
+    ))
+}
+
+// アクセストークンの期限が切れていても呼べるよう、Bearer トークンは要求しない
+pub async fn auth_refresh(
+    State(registry): State<AppRegistry>,
+    RequestClient(client): RequestClient,
+    Json(req): Json<RefreshTokenRequest>,
+) -> AppResult<(StatusCode, Json<AccessTokenResponse>)> {
+    req.validate()?;
+
+    let auth_repository = registry.auth_repository();
+    let access_token_id = AccessTokenId::generate();
+    let refresh_token = RefreshToken::generate();
+    let user_id = auth_repository
+        .rotate_token(RotateToken {
+            refresh_token: RefreshToken(req.refresh_token),
+            access_token_id: access_token_id.clone(),
+            new_refresh_token: refresh_token.clone(),
+            client,
+        })
+        .await?;
+    let access_token = registry.token_service().issue(user_id, access_token_id)?;
+
+    Ok((
+        StatusCode::OK,
+        Json(token_response(
+            auth_repository.as_ref(),
+            user_id,
+            access_token,
+            refresh_token,
+        )),
+    ))
+}
+
+fn token_response(
+    auth_repository: &dyn AuthRepository,
+    user_id: UserId,
+    access_token: AccessToken,
+    refresh_token: RefreshToken,
+) -> AccessTokenResponse {
+    AccessTokenResponse {
+        user_id,
+        access_token: access_token.0,
+        expires_in: auth_repository.token_ttl(),
+        refresh_token: refresh_token.0,
+        refresh_expires_in: auth_repository.refresh_token_ttl(),
+    }
+}
+
+pub async fn auth_logout(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry
+        .auth_repository()
+        .delete_token(user.access_token_id()?.clone())
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn list_sessions(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<SessionsResponse>> {
+    let current = user.access_token_id()?;
+    let sessions = registry.auth_repository().find_sessions(user.id()).await?;
+
+    Ok(Json(SessionsResponse {
+        items: sessions
+            .into_iter()
+            .map(|session| SessionResponse::new(session, current))
+            .collect(),
+    }))
+}
+
+pub async fn delete_session(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path(session_id): Path<String>,
+) -> AppResult<StatusCode> {
+    let session_id: SessionId = session_id.parse()?;
+    registry
+        .auth_repository()
+        .delete_session(DeleteSession {
+            id: session_id,
+            user_id: user.id(),
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// 今使っているセッション以外をすべて失効させる
+pub async fn delete_other_sessions(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry
+        .auth_repository()
+        .delete_other_tokens(user.id(), user.access_token_id()?)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use kernel::model::{
+        auth::{ClientInfo, Session, UserCredential},
+        id::UserId,
+        user::User,
+    };
+    use kernel::repository::auth::{AuthRepository, MockAuthRepository};
+    use kernel::service::token::TokenService;
+    use registry::MockAppRegistryExt;
+    use shared::config::{JwtKey, JwtKeys};
+    use std::sync::Arc;
+
+    fn token_service() -> TokenService {
+        let current = JwtKey {
+            id: "test".to_string(),
+            secret: "test-secret".to_string(),
+        };
+        TokenService::new(
+            &JwtKeys {
+                current,
+                previous: Vec::new(),
+            },
+            3600,
+        )
+    }
+
+    fn credential(user_id: UserId, password: &str) -> UserCredential {
+        UserCredential {
+            id: user_id,
+            email: "alice@example.com".to_string(),
+            password_hash: bcrypt::hash(password, 4).expect("hash生成"),
+        }
+    }
+
+    fn client() -> RequestClient {
+        RequestClient(ClientInfo {
+            user_agent: Some("test-agent".to_string()),
+            ip_address: Some("192.0.2.1".to_string()),
+        })
+    }
+
+    fn user(access_token_id: &str) -> AuthorizedUser {
+        AuthorizedUser::new(
+            AccessTokenId(access_token_id.to_string()),
+            User {
+                id: UserId::new(),
+                name: "Alice".to_string(),
+                email: "alice@example.com".to_string(),
+            },
+        )
+    }
+
+    fn session(access_token_id: &str) -> Session {
+        Session {
+            id: SessionId::new(),
+            access_token_id: AccessTokenId(access_token_id.to_string()),
+            client: client().0,
+            created_at: Utc::now(),
+            last_used_at: Utc::now(),
+        }
+    }
+
+    fn registry_with(repo: MockAuthRepository) -> AppRegistry {
+        let mut registry = MockAppRegistryExt::new();
+        let repo_arc: Arc<dyn AuthRepository> = Arc::new(repo);
+        registry.expect_auth_repository().return_const(repo_arc);
+        registry
+            .expect_token_service()
+            .return_const(Arc::new(token_service()));
+        Arc::new(registry)
+    }
+
+    #[tokio::test]
+    async fn ログインはアクセストークンとリフレッシュトークンを返す() {
+        let user_id = UserId::new();
+        let mut repo = MockAuthRepository::new();
+        repo.expect_find_by_email()
+            .returning(move |_| Ok(Some(credential(user_id, "password123"))));
+        repo.expect_store_token()
+            .withf(move |event| event.user_id == user_id && event.client == client().0)
+            .returning(|_| Ok(()));
+        repo.expect_token_ttl().return_const(3600u64);
+        repo.expect_refresh_token_ttl().return_const(86400u64);
+
+        let req = LoginRequest::new("alice@example.com".to_string(), "password123".to_string());
+
+        let (status, Json(body)) = auth_login(State(registry_with(repo)), client(), Json(req))
+            .await
+            .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.user_id, user_id);
+        let claims = token_service()
+            .verify(&AccessToken(body.access_token.clone()))
+            .expect("署名付きのトークンを返す");
+        assert_eq!(claims.sub, user_id);
+        assert_eq!(body.expires_in, 3600);
+        assert!(!body.refresh_token.is_empty());
+        assert_ne!(body.refresh_token, body.access_token);
+        assert_eq!(body.refresh_expires_in, 86400);
+    }
+
+    #[tokio::test]
+    async fn ログインはパスワード不一致で401になる() {
+        let mut repo = MockAuthRepository::new();
+        repo.expect_find_by_email()
+            .returning(|_| Ok(Some(credential(UserId::new(), "password123"))));
+        repo.expect_store_token().never();
+
+        let req = LoginRequest::new("alice@example.com".to_string(), "wrong".to_string());
+
+        let err = auth_login(State(registry_with(repo)), client(), Json(req))
+            .await
+            .expect_err("パスワード不一致は失敗する");
+
+        assert!(matches!(err, AppError::Unauthorized(_)));
+    }
+
+    #[tokio::test]
+    async fn ログインは存在しないメールで401になる() {
+        let mut repo = MockAuthRepository::new();
+        repo.expect_find_by_email().returning(|_| Ok(None));
+        repo.expect_store_token().never();
+
+        let req = LoginRequest::new("nobody@example.com".to_string(), "password123".to_string());
+
+        let err = auth_login(State(registry_with(repo)), client(), Json(req))
+            .await
+            .expect_err("存在しないメールは失敗する");
+
+        assert!(matches!(err, AppError::Unauthorized(_)));
+    }
+
+    #[tokio::test]
+    async fn リフレッシュは新しいトークンの組を返す() {
+        let user_id = UserId::new();
+        let mut repo = MockAuthRepository::new();
+        repo.expect_rotate_token()
+            .withf(|event| {
+                event.refresh_token.0 == "old-refresh-token"
+                    && event.new_refresh_token != event.refresh_token
+            })
+            .returning(move |_| Ok(user_id));
+        repo.expect_token_ttl().return_const(3600u64);
+        repo.expect_refresh_token_ttl().return_const(86400u64);
+
+        let req = RefreshTokenRequest::new("old-refresh-token".to_string());
+
+        let (status, Json(body)) = auth_refresh(State(registry_with(repo)), client(), Json(req))
+            .await
+            .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.user_id, user_id);
+        let claims = token_service()
+            .verify(&AccessToken(body.access_token))
+            .expect("署名付きのトークンを返す");
+        assert_eq!(claims.sub, user_id);
+        assert_ne!(body.refresh_token, "old-refresh-token");
+    }
+
+    #[tokio::test]
+    async fn 使えないリフレッシュトークンは401になる() {
+        let mut repo = MockAuthRepository::new();
+        repo.expect_rotate_token()
+            .returning(|_| Err(AppError::Unauthorized("Invalid refresh token".into())));
+
+        let req = RefreshTokenRequest::new("used-refresh-token".to_string());
+
+        let err = auth_refresh(State(registry_with(repo)), client(), Json(req))
+            .await
+            .expect_err("無効なリフレッシュトークンは失敗する");
+
+        assert!(matches!(err, AppError::Unauthorized(_)));
+    }
+
+    #[tokio::test]
+    async fn ログアウトはトークンを削除して204を返す() {
+        let mut repo = MockAuthRepository::new();
+        repo.expect_delete_token()
+            .withf(|token| token.0 == "test-token")
+            .returning(|_| Ok(()));
+
+        let user = AuthorizedUser::new(
+            AccessTokenId("test-token".to_string()),
+            User {
+                id: UserId::new(),
+                name: "Alice".to_string(),
+                email: "alice@example.com".to_string(),
+            },
+        );
+
+        let status = auth_logout(user, State(registry_with(repo)))
+            .await
+            .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn セッション一覧は今のセッションを印付けして返す() {
+        let mut repo = MockAuthRepository::new();
+        let sessions = vec![session("test-token"), session("other-token")];
+        let expected: Vec<SessionId> = sessions.iter().map(|session| session.id).collect();
+        repo.expect_find_sessions()
+            .returning(move |_| Ok(sessions.clone()));
+
+        let Json(body) = list_sessions(user("test-token"), State(registry_with(repo)))
+            .await
+            .expect("正常系は成功を期待する");
+
+        let ids: Vec<SessionId> = body.items.iter().map(|item| item.id).collect();
+        assert_eq!(ids, expected);
+        assert!(body.items[0].current);
+        assert!(!body.items[1].current);
+        assert_eq!(body.items[0].user_agent.as_deref(), Some("test-agent"));
+        assert_eq!(body.items[0].ip_address.as_deref(), Some("192.0.2.1"));
+    }
+
+    #[tokio::test]
+    async fn セッションは指定して失効できる() {
+        let session_id = SessionId::new();
+        let mut repo = MockAuthRepository::new();
+        repo.expect_delete_session()
+            .withf(move |event| event.id == session_id)
+            .returning(|_| Ok(()));
+
+        let status = delete_session(
+            user("test-token"),
+            State(registry_with(repo)),
+            Path(session_id.to_string()),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn 存在しないセッションの失効は404になる() {
+        let mut repo = MockAuthRepository::new();
+        repo.expect_delete_session().returning(|_| {
+            Err(AppError::EntityNotFoundError(
+                "The session was not found".into(),
+            ))
+        });
+
+        let err = delete_session(
+            user("test-token"),
+            State(registry_with(repo)),
+            Path(SessionId::new().to_string()),
+        )
+        .await
+        .expect_err("存在しないセッションは失敗する");
+
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+    }
+
+    #[tokio::test]
+    async fn 今のセッション以外をまとめて失効できる() {
+        let mut repo = MockAuthRepository::new();
+        repo.expect_delete_other_tokens()
+            .withf(|_, keep| keep.0 == "test-token")
+            .returning(|_, _| Ok(()));
+
+        let status = delete_other_sessions(user("test-token"), State(registry_with(repo)))
+            .await
+            .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+}
@@ -0,0 +1,235 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use kernel::model::{
+    completion::event::{CreateCompletion, UpdateReopened},
+    id::{CompletionId, TodoId},
+};
+use registry::AppRegistry;
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::completion::{CompleteTodoParams, CompletionResponse, CompletionsResponse},
+};
+use shared::error::{AppError, AppResult};
+
+pub async fn complete_todo(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path(todo_id): Path<String>,
+    Query(params): Query<CompleteTodoParams>,
+) -> AppResult<(StatusCode, Json<CompletionResponse>)> {
+    let todo_id: TodoId = todo_id.parse()?;
+    let completion = registry
+        .completion_repository()
+        .create(CreateCompletion {
+            todo_id,
+            user_id: user.id(),
+            include_subtasks: params.subtasks,
+            force: params.force,
+        })
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(completion.into())))
+}
+
+pub async fn reopen_todo(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path((todo_id, completion_id)): Path<(String, String)>,
+) -> AppResult<StatusCode> {
+    let todo_id: TodoId = todo_id.parse()?;
+    let completion_id: CompletionId = completion_id.parse()?;
+    registry
+        .completion_repository()
+        .update_reopened(UpdateReopened {
+            completion_id,
+            todo_id,
+            user_id: user.id(),
+        })
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+pub async fn show_completed_list(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<(StatusCode, Json<CompletionsResponse>)> {
+    let items = registry
+        .completion_repository()
+        .find_completed_all(user.id())
+        .await?
+        .into_iter()
+        .map(CompletionResponse::from)
+        .collect();
+
+    Ok((StatusCode::OK, Json(CompletionsResponse { items })))
+}
+
+pub async fn show_todo_history(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path(todo_id): Path<String>,
+) -> AppResult<(StatusCode, Json<CompletionsResponse>)> {
+    let todo_id: TodoId = todo_id.parse()?;
+    registry
+        .todo_repository()
+        .find_by_id(todo_id, user.id())
+        .await?
+        .ok_or_else(|| AppError::EntityNotFoundError("The todo was not found".into()))?;
+
+    let items = registry
+        .completion_repository()
+        .find_history_by_todo_id(todo_id, user.id())
+        .await?
+        .into_iter()
+        .map(CompletionResponse::from)
+        .collect();
+
+    Ok((StatusCode::OK, Json(CompletionsResponse { items })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use kernel::model::{
+        completion::{Completion, CompletionTodo},
+        id::UserId,
+    };
+    use kernel::repository::{
+        completion::{CompletionRepository, MockCompletionRepository},
+        todo::{MockTodoRepository, TodoRepository},
+    };
+    use registry::MockAppRegistryExt;
+    use std::sync::Arc;
+
+    fn completion(todo_id: TodoId) -> Completion {
+        Completion {
+            id: CompletionId::new(),
+            todo: CompletionTodo {
+                id: todo_id,
+                title: "牛乳を買う".to_string(),
+            },
+            completed_at: Utc::now(),
+            reopened_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn todo完了は201と完了情報を返す() {
+        let user_id = UserId::new();
+        let todo_id = TodoId::new();
+        let mut repo = MockCompletionRepository::new();
+        repo.expect_create()
+            .withf(move |event| event.todo_id == todo_id && event.user_id == user_id)
+            .returning(|event| Ok(completion(event.todo_id)));
+
+        let mut registry = MockAppRegistryExt::new();
+        let repo_arc: Arc<dyn CompletionRepository> = Arc::new(repo);
+        registry
+            .expect_completion_repository()
+            .return_const(repo_arc);
+        let registry: AppRegistry = Arc::new(registry);
+
+        let (status, Json(body)) = complete_todo(
+            AuthorizedUser::for_test(user_id),
+            State(registry),
+            Path(todo_id.to_string()),
+            Query(CompleteTodoParams::default()),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body.todo.id, todo_id);
+        assert!(body.reopened_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn 完了済みtodoの完了は422になる() {
+        let mut repo = MockCompletionRepository::new();
+        repo.expect_create()
+            .returning(|_| Err(AppError::UnprocessableEntity("completed".into())));
+
+        let mut registry = MockAppRegistryExt::new();
+        let repo_arc: Arc<dyn CompletionRepository> = Arc::new(repo);
+        registry
+            .expect_completion_repository()
+            .return_const(repo_arc);
+        let registry: AppRegistry = Arc::new(registry);
+
+        let err = complete_todo(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry),
+            Path(TodoId::new().to_string()),
+            Query(CompleteTodoParams::default()),
+        )
+        .await
+        .expect_err("完了済みは失敗する");
+
+        assert!(matches!(err, AppError::UnprocessableEntity(_)));
+    }
+
+    #[tokio::test]
+    async fn 再オープンは200を返す() {
+        let user_id = UserId::new();
+        let todo_id = TodoId::new();
+        let completion_id = CompletionId::new();
+        let mut repo = MockCompletionRepository::new();
+        repo.expect_update_reopened()
+            .withf(move |event| {
+                event.completion_id == completion_id
+                    && event.todo_id == todo_id
+                    && event.user_id == user_id
+            })
+            .returning(|_| Ok(()));
+
+        let mut registry = MockAppRegistryExt::new();
+        let repo_arc: Arc<dyn CompletionRepository> = Arc::new(repo);
+        registry
+            .expect_completion_repository()
+            .return_const(repo_arc);
+        let registry: AppRegistry = Arc::new(registry);
+
+        let status = reopen_todo(
+            AuthorizedUser::for_test(user_id),
+            State(registry),
+            Path((todo_id.to_string(), completion_id.to_string())),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn 履歴取得は他人のtodoなら404になる() {
+        let mut todo_repo = MockTodoRepository::new();
+        todo_repo.expect_find_by_id().returning(|_, _| Ok(None));
+        let mut completion_repo = MockCompletionRepository::new();
+        completion_repo.expect_find_history_by_todo_id().never();
+
+        let mut registry = MockAppRegistryExt::new();
+        let todo_repo: Arc<dyn TodoRepository> = Arc::new(todo_repo);
+        let completion_repo: Arc<dyn CompletionRepository> = Arc::new(completion_repo);
+        registry.expect_todo_repository().return_const(todo_repo);
+        registry
+            .expect_completion_repository()
+            .return_const(completion_repo);
+        let registry: AppRegistry = Arc::new(registry);
+
+        let err = show_todo_history(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry),
+            Path(TodoId::new().to_string()),
+        )
+        .await
+        .expect_err("他人のtodoは失敗する");
+
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+    }
+}
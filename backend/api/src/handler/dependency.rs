@@ -0,0 +1,127 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use kernel::model::{
+    dependency::event::{AddDependency, RemoveDependency},
+    id::TodoId,
+};
+use registry::AppRegistry;
+
+use crate::extractor::AuthorizedUser;
+use shared::error::AppResult;
+
+// blocker_id の todo が完了するまで todo_id の todo を完了できないようにする
+pub async fn add_blocker(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path((todo_id, blocker_id)): Path<(String, String)>,
+) -> AppResult<StatusCode> {
+    let todo_id: TodoId = todo_id.parse()?;
+    let blocker_id: TodoId = blocker_id.parse()?;
+    registry
+        .dependency_repository()
+        .add(AddDependency {
+            todo_id,
+            blocker_id,
+            user_id: user.id(),
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn remove_blocker(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path((todo_id, blocker_id)): Path<(String, String)>,
+) -> AppResult<StatusCode> {
+    let todo_id: TodoId = todo_id.parse()?;
+    let blocker_id: TodoId = blocker_id.parse()?;
+    registry
+        .dependency_repository()
+        .remove(RemoveDependency {
+            todo_id,
+            blocker_id,
+            user_id: user.id(),
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kernel::model::id::UserId;
+    use kernel::repository::dependency::{DependencyRepository, MockDependencyRepository};
+    use registry::MockAppRegistryExt;
+    use shared::error::AppError;
+    use std::sync::Arc;
+
+    fn registry_with(repo: MockDependencyRepository) -> AppRegistry {
+        let mut registry = MockAppRegistryExt::new();
+        let repo_arc: Arc<dyn DependencyRepository> = Arc::new(repo);
+        registry
+            .expect_dependency_repository()
+            .return_const(repo_arc);
+        Arc::new(registry)
+    }
+
+    #[tokio::test]
+    async fn blocker追加は204を返す() {
+        let user_id = UserId::new();
+        let (todo_id, blocker_id) = (TodoId::new(), TodoId::new());
+        let mut repo = MockDependencyRepository::new();
+        repo.expect_add()
+            .withf(move |event| {
+                event.todo_id == todo_id
+                    && event.blocker_id == blocker_id
+                    && event.user_id == user_id
+            })
+            .returning(|_| Ok(()));
+
+        let status = add_blocker(
+            AuthorizedUser::for_test(user_id),
+            State(registry_with(repo)),
+            Path((todo_id.to_string(), blocker_id.to_string())),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn 循環するblocker追加は422になる() {
+        let mut repo = MockDependencyRepository::new();
+        repo.expect_add()
+            .returning(|_| Err(AppError::UnprocessableEntity("cycle".into())));
+
+        let err = add_blocker(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry_with(repo)),
+            Path((TodoId::new().to_string(), TodoId::new().to_string())),
+        )
+        .await
+        .expect_err("循環は失敗する");
+
+        assert!(matches!(err, AppError::UnprocessableEntity(_)));
+    }
+
+    #[tokio::test]
+    async fn blocker削除は204を返す() {
+        let mut repo = MockDependencyRepository::new();
+        repo.expect_remove().returning(|_| Ok(()));
+
+        let status = remove_blocker(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry_with(repo)),
+            Path((TodoId::new().to_string(), TodoId::new().to_string())),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+}
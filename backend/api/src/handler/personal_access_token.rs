@@ -0,0 +1,213 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use garde::Validate;
+use kernel::model::{
+    id::PersonalAccessTokenId,
+    personal_access_token::{PersonalAccessTokenSecret, event::DeletePersonalAccessToken},
+};
+use registry::AppRegistry;
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::personal_access_token::{
+        CreatePersonalAccessTokenRequest, CreatePersonalAccessTokenRequestWithUserId,
+        CreatedPersonalAccessTokenResponse, PersonalAccessTokenResponse,
+        PersonalAccessTokensResponse,
+    },
+};
+use shared::error::AppResult;
+
+pub async fn register_personal_access_token(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreatePersonalAccessTokenRequest>,
+) -> AppResult<(StatusCode, Json<CreatedPersonalAccessTokenResponse>)> {
+    req.validate()?;
+
+    let secret = PersonalAccessTokenSecret::generate();
+    let personal_access_token = registry
+        .personal_access_token_repository()
+        .create(
+            CreatePersonalAccessTokenRequestWithUserId::new(user.id(), secret.clone(), req).into(),
+        )
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedPersonalAccessTokenResponse {
+            personal_access_token: personal_access_token.into(),
+            token: secret.0,
+        }),
+    ))
+}
+
+pub async fn list_personal_access_tokens(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<(StatusCode, Json<PersonalAccessTokensResponse>)> {
+    let items = registry
+        .personal_access_token_repository()
+        .find_all(user.id())
+        .await?
+        .into_iter()
+        .map(PersonalAccessTokenResponse::from)
+        .collect();
+
+    Ok((StatusCode::OK, Json(PersonalAccessTokensResponse { items })))
+}
+
+pub async fn delete_personal_access_token(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path(token_id): Path<String>,
+) -> AppResult<StatusCode> {
+    let token_id: PersonalAccessTokenId = token_id.parse()?;
+    registry
+        .personal_access_token_repository()
+        .delete(DeletePersonalAccessToken {
+            id: token_id,
+            user_id: user.id(),
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use kernel::model::{
+        id::UserId,
+        personal_access_token::{PersonalAccessToken, TokenScope},
+    };
+    use kernel::repository::personal_access_token::{
+        MockPersonalAccessTokenRepository, PersonalAccessTokenRepository,
+    };
+    use registry::MockAppRegistryExt;
+    use shared::error::AppError;
+    use std::sync::{Arc, Mutex};
+
+    fn personal_access_token(user_id: UserId, name: &str) -> PersonalAccessToken {
+        PersonalAccessToken {
+            id: PersonalAccessTokenId::new(),
+            user_id,
+            name: name.to_string(),
+            scopes: vec![TokenScope::TodosRead],
+            expires_at: None,
+            last_used_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn registry_with(repo: MockPersonalAccessTokenRepository) -> AppRegistry {
+        let mut registry = MockAppRegistryExt::new();
+        let repo_arc: Arc<dyn PersonalAccessTokenRepository> = Arc::new(repo);
+        registry
+            .expect_personal_access_token_repository()
+            .return_const(repo_arc);
+        Arc::new(registry)
+    }
+
+    #[tokio::test]
+    async fn 作成したときだけ平文のトークンを返す() {
+        let user_id = UserId::new();
+        let stored_secret = Arc::new(Mutex::new(None));
+        let mut repo = MockPersonalAccessTokenRepository::new();
+        repo.expect_create()
+            .withf(move |event| {
+                event.user_id == user_id
+                    && event.name == "ci"
+                    && event.scopes == vec![TokenScope::TodosRead]
+                    && event.expires_at.is_some_and(|at| at > Utc::now())
+            })
+            .returning({
+                let stored_secret = stored_secret.clone();
+                move |event| {
+                    *stored_secret.lock().expect("ロック") = Some(event.secret.0.clone());
+                    Ok(personal_access_token(event.user_id, &event.name))
+                }
+            });
+
+        let req = CreatePersonalAccessTokenRequest::new(
+            "ci".to_string(),
+            vec![TokenScope::TodosRead],
+            Some(30),
+        );
+        let (status, Json(body)) = register_personal_access_token(
+            AuthorizedUser::for_test(user_id),
+            State(registry_with(repo)),
+            Json(req),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::CREATED);
+        assert!(body.token.starts_with(PersonalAccessTokenSecret::PREFIX));
+        assert_eq!(
+            stored_secret.lock().expect("ロック").as_deref(),
+            Some(body.token.as_str())
+        );
+        assert_eq!(body.personal_access_token.name, "ci");
+    }
+
+    #[tokio::test]
+    async fn スコープのないトークンは作成できない() {
+        let mut repo = MockPersonalAccessTokenRepository::new();
+        repo.expect_create().never();
+
+        let req = CreatePersonalAccessTokenRequest::new("ci".to_string(), Vec::new(), None);
+        let err = register_personal_access_token(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry_with(repo)),
+            Json(req),
+        )
+        .await
+        .expect_err("スコープは必須");
+
+        assert!(matches!(err, AppError::ValidationError(_)));
+    }
+
+    #[tokio::test]
+    async fn ユーザのトークンを一覧できる() {
+        let user_id = UserId::new();
+        let mut repo = MockPersonalAccessTokenRepository::new();
+        repo.expect_find_all()
+            .withf(move |id| *id == user_id)
+            .returning(move |user_id| Ok(vec![personal_access_token(user_id, "ci")]));
+
+        let (status, Json(body)) = list_personal_access_tokens(
+            AuthorizedUser::for_test(user_id),
+            State(registry_with(repo)),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.items.len(), 1);
+        assert_eq!(body.items[0].name, "ci");
+    }
+
+    #[tokio::test]
+    async fn 存在しないトークンの失効は404になる() {
+        let mut repo = MockPersonalAccessTokenRepository::new();
+        repo.expect_delete().returning(|_| {
+            Err(AppError::EntityNotFoundError(
+                "The personal access token was not found".into(),
+            ))
+        });
+
+        let err = delete_personal_access_token(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry_with(repo)),
+            Path(PersonalAccessTokenId::new().to_string()),
+        )
+        .await
+        .expect_err("存在しないトークンは失敗する");
+
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+    }
+}
@@ -0,0 +1,299 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use garde::Validate;
+use kernel::model::{
+    id::ProjectId,
+    project::event::DeleteProject,
+    todo::{
+        TodoSort,
+        filter::{ProjectScope, TodoFilter},
+    },
+};
+use registry::AppRegistry;
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::{
+        list::ListQueryParams,
+        project::{
+            CreateProjectRequest, CreateProjectRequestWithUserId, DeleteProjectParams,
+            ProjectListParams, ProjectResponse, ProjectsResponse, UpdateProjectRequest,
+            UpdateProjectRequestWithIds,
+        },
+        todo::{TodoFilterParams, TodosResponse},
+    },
+};
+use shared::error::{AppError, AppResult};
+
+pub async fn register_project(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateProjectRequest>,
+) -> AppResult<(StatusCode, Json<ProjectResponse>)> {
+    req.validate()?;
+
+    let project = registry
+        .project_repository()
+        .create(CreateProjectRequestWithUserId::new(user.id(), req).into())
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(project.into())))
+}
+
+pub async fn list_projects(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Query(params): Query<ProjectListParams>,
+) -> AppResult<(StatusCode, Json<ProjectsResponse>)> {
+    let items = registry
+        .project_repository()
+        .find_all(user.id(), params.include_archived)
+        .await?
+        .into_iter()
+        .map(ProjectResponse::from)
+        .collect();
+
+    Ok((StatusCode::OK, Json(ProjectsResponse { items })))
+}
+
+pub async fn show_project(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path(project_id): Path<String>,
+) -> AppResult<(StatusCode, Json<ProjectResponse>)> {
+    let project_id: ProjectId = project_id.parse()?;
+    let project = registry
+        .project_repository()
+        .find_by_id(project_id, user.id())
+        .await?
+        .ok_or_else(|| AppError::EntityNotFoundError("The project was not found".into()))?;
+
+    Ok((StatusCode::OK, Json(project.into())))
+}
+
+pub async fn update_project(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path(project_id): Path<String>,
+    Json(req): Json<UpdateProjectRequest>,
+) -> AppResult<StatusCode> {
+    let project_id: ProjectId = project_id.parse()?;
+    req.validate()?;
+
+    registry
+        .project_repository()
+        .update(UpdateProjectRequestWithIds::new(project_id, user.id(), req).into())
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+pub async fn delete_project(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path(project_id): Path<String>,
+    Query(params): Query<DeleteProjectParams>,
+) -> AppResult<StatusCode> {
+    let project_id: ProjectId = project_id.parse()?;
+    registry
+        .project_repository()
+        .delete(DeleteProject {
+            id: project_id,
+            user_id: user.id(),
+            mode: params.todos,
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// プロジェクトに属する todo の一覧。絞り込みとページングは `GET /todos` と同じ
+pub async fn list_project_todos(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path(project_id): Path<String>,
+    Query(filter): Query<TodoFilterParams>,
+    Query(params): Query<ListQueryParams<TodoSort>>,
+) -> AppResult<(StatusCode, Json<TodosResponse>)> {
+    let project_id: ProjectId = project_id.parse()?;
+    registry
+        .project_repository()
+        .find_by_id(project_id, user.id())
+        .await?
+        .ok_or_else(|| AppError::EntityNotFoundError("The project was not found".into()))?;
+
+    // パスで指定したプロジェクトが project_id や inbox の指定より優先する
+    let filter: TodoFilter = filter.try_into()?;
+    let filter = TodoFilter {
+        project: Some(ProjectScope::Project(project_id)),
+        ..filter
+    };
+    let page = registry
+        .todo_repository()
+        .find_all(user.id(), filter, params.try_into()?)
+        .await?;
+
+    Ok((StatusCode::OK, Json(page.into())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use kernel::model::{
+        id::UserId,
+        list::Page,
+        project::{Project, ProjectProgress, event::DeleteProjectMode},
+    };
+    use kernel::repository::{
+        project::{MockProjectRepository, ProjectRepository},
+        todo::{MockTodoRepository, TodoRepository},
+    };
+    use registry::MockAppRegistryExt;
+    use std::sync::Arc;
+
+    fn project(id: ProjectId, user_id: UserId) -> Project {
+        let now = Utc::now();
+        Project {
+            id,
+            user_id,
+            name: "買い物".to_string(),
+            colour: "#808080".to_string(),
+            archived: false,
+            progress: ProjectProgress {
+                total: 3,
+                completed: 1,
+            },
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn registry_with(project_repo: MockProjectRepository) -> MockAppRegistryExt {
+        let mut registry = MockAppRegistryExt::new();
+        let repo_arc: Arc<dyn ProjectRepository> = Arc::new(project_repo);
+        registry.expect_project_repository().return_const(repo_arc);
+        registry
+    }
+
+    #[tokio::test]
+    async fn プロジェクト追加は色を省略すると既定の色になる() {
+        let user_id = UserId::new();
+        let mut repo = MockProjectRepository::new();
+        repo.expect_create()
+            .withf(move |event| event.user_id == user_id && event.colour == "#808080")
+            .returning(|event| Ok(project(ProjectId::new(), event.user_id)));
+
+        let (status, Json(body)) = register_project(
+            AuthorizedUser::for_test(user_id),
+            State(Arc::new(registry_with(repo))),
+            Json(CreateProjectRequest::new("買い物".to_string(), None)),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body.progress.total, 3);
+        assert_eq!(body.progress.completed, 1);
+    }
+
+    #[tokio::test]
+    async fn プロジェクト追加は不正な色を拒否する() {
+        let registry: AppRegistry = Arc::new(MockAppRegistryExt::new());
+
+        let err = register_project(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry),
+            Json(CreateProjectRequest::new(
+                "買い物".to_string(),
+                Some("red".to_string()),
+            )),
+        )
+        .await
+        .expect_err("バリデーションは失敗する");
+
+        assert!(matches!(err, AppError::ValidationError(_)));
+    }
+
+    #[tokio::test]
+    async fn プロジェクト削除はtodoの扱いをリポジトリに渡す() {
+        let project_id = ProjectId::new();
+        let mut repo = MockProjectRepository::new();
+        repo.expect_delete()
+            .withf(move |event| event.id == project_id && event.mode == DeleteProjectMode::Cascade)
+            .returning(|_| Ok(()));
+
+        let status = delete_project(
+            AuthorizedUser::for_test(UserId::new()),
+            State(Arc::new(registry_with(repo))),
+            Path(project_id.to_string()),
+            Query(DeleteProjectParams::new(DeleteProjectMode::Cascade)),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn プロジェクトのtodo一覧はプロジェクトで絞り込む() {
+        let user_id = UserId::new();
+        let project_id = ProjectId::new();
+        let mut projects = MockProjectRepository::new();
+        projects
+            .expect_find_by_id()
+            .returning(|id, user_id| Ok(Some(project(id, user_id))));
+        let mut todos = MockTodoRepository::new();
+        todos
+            .expect_find_all()
+            .withf(move |_, filter, _| {
+                filter.project == Some(ProjectScope::Project(project_id))
+                    && filter.completed == Some(false)
+            })
+            .returning(|_, _, _| {
+                Ok(Page {
+                    items: vec![],
+                    next_cursor: None,
+                })
+            });
+        let mut registry = registry_with(projects);
+        let todos: Arc<dyn TodoRepository> = Arc::new(todos);
+        registry.expect_todo_repository().return_const(todos);
+
+        let (status, _) = list_project_todos(
+            AuthorizedUser::for_test(user_id),
+            State(Arc::new(registry)),
+            Path(project_id.to_string()),
+            Query(TodoFilterParams {
+                completed: Some(false),
+                ..Default::default()
+            }),
+            Query(ListQueryParams::default()),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn 存在しないプロジェクトのtodo一覧は404になる() {
+        let mut projects = MockProjectRepository::new();
+        projects.expect_find_by_id().returning(|_, _| Ok(None));
+
+        let err = list_project_todos(
+            AuthorizedUser::for_test(UserId::new()),
+            State(Arc::new(registry_with(projects))),
+            Path(ProjectId::new().to_string()),
+            Query(TodoFilterParams::default()),
+            Query(ListQueryParams::default()),
+        )
+        .await
+        .expect_err("存在しないプロジェクトは失敗する");
+
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+    }
+}
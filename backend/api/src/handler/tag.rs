@@ -0,0 +1,238 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use garde::Validate;
+use kernel::model::{
+    id::{TagId, TodoId},
+    tag::event::{AttachTag, DeleteTag, DetachTag},
+};
+use registry::AppRegistry;
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::tag::{
+        CreateTagRequest, CreateTagRequestWithUserId, TagResponse, TagsResponse, UpdateTagRequest,
+        UpdateTagRequestWithIds,
+    },
+};
+use shared::error::{AppError, AppResult};
+
+pub async fn register_tag(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateTagRequest>,
+) -> AppResult<(StatusCode, Json<TagResponse>)> {
+    req.validate()?;
+
+    let tag = registry
+        .tag_repository()
+        .create(CreateTagRequestWithUserId::new(user.id(), req).into())
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(tag.into())))
+}
+
+pub async fn list_tags(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<(StatusCode, Json<TagsResponse>)> {
+    let items = registry
+        .tag_repository()
+        .find_all(user.id())
+        .await?
+        .into_iter()
+        .map(TagResponse::from)
+        .collect();
+
+    Ok((StatusCode::OK, Json(TagsResponse { items })))
+}
+
+pub async fn show_tag(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path(tag_id): Path<String>,
+) -> AppResult<(StatusCode, Json<TagResponse>)> {
+    let tag_id: TagId = tag_id.parse()?;
+    let tag = registry
+        .tag_repository()
+        .find_by_id(tag_id, user.id())
+        .await?
+        .ok_or_else(|| AppError::EntityNotFoundError("The tag was not found".into()))?;
+
+    Ok((StatusCode::OK, Json(tag.into())))
+}
+
+pub async fn update_tag(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path(tag_id): Path<String>,
+    Json(req): Json<UpdateTagRequest>,
+) -> AppResult<StatusCode> {
+    let tag_id: TagId = tag_id.parse()?;
+    req.validate()?;
+
+    registry
+        .tag_repository()
+        .update(UpdateTagRequestWithIds::new(tag_id, user.id(), req).into())
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+pub async fn delete_tag(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path(tag_id): Path<String>,
+) -> AppResult<StatusCode> {
+    let tag_id: TagId = tag_id.parse()?;
+    registry
+        .tag_repository()
+        .delete(DeleteTag {
+            id: tag_id,
+            user_id: user.id(),
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn attach_tag(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path((tag_id, todo_id)): Path<(String, String)>,
+) -> AppResult<StatusCode> {
+    let tag_id: TagId = tag_id.parse()?;
+    let todo_id: TodoId = todo_id.parse()?;
+    registry
+        .tag_repository()
+        .attach(AttachTag {
+            tag_id,
+            todo_id,
+            user_id: user.id(),
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn detach_tag(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path((tag_id, todo_id)): Path<(String, String)>,
+) -> AppResult<StatusCode> {
+    let tag_id: TagId = tag_id.parse()?;
+    let todo_id: TodoId = todo_id.parse()?;
+    registry
+        .tag_repository()
+        .detach(DetachTag {
+            tag_id,
+            todo_id,
+            user_id: user.id(),
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use kernel::model::{id::UserId, tag::Tag};
+    use kernel::repository::tag::{MockTagRepository, TagRepository};
+    use registry::MockAppRegistryExt;
+    use std::sync::Arc;
+
+    fn tag(user_id: UserId, name: &str) -> Tag {
+        let now = Utc::now();
+        Tag {
+            id: TagId::new(),
+            user_id,
+            name: name.to_string(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn registry_with(repo: MockTagRepository) -> AppRegistry {
+        let mut registry = MockAppRegistryExt::new();
+        let repo_arc: Arc<dyn TagRepository> = Arc::new(repo);
+        registry.expect_tag_repository().return_const(repo_arc);
+        Arc::new(registry)
+    }
+
+    #[tokio::test]
+    async fn タグ追加は201と作成したタグを返す() {
+        let user_id = UserId::new();
+        let mut repo = MockTagRepository::new();
+        repo.expect_create()
+            .withf(move |event| event.user_id == user_id && event.name == "bug")
+            .returning(|event| Ok(tag(event.user_id, &event.name)));
+
+        let (status, Json(body)) = register_tag(
+            AuthorizedUser::for_test(user_id),
+            State(registry_with(repo)),
+            Json(CreateTagRequest::new("bug".to_string())),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body.name, "bug");
+    }
+
+    #[tokio::test]
+    async fn タグ追加は長すぎる名前を拒否する() {
+        let registry: AppRegistry = Arc::new(MockAppRegistryExt::new());
+
+        let err = register_tag(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry),
+            Json(CreateTagRequest::new("a".repeat(51))),
+        )
+        .await
+        .expect_err("バリデーションは失敗する");
+
+        assert!(matches!(err, AppError::ValidationError(_)));
+    }
+
+    #[tokio::test]
+    async fn 存在しないタグは404になる() {
+        let mut repo = MockTagRepository::new();
+        repo.expect_find_by_id().returning(|_, _| Ok(None));
+
+        let err = show_tag(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry_with(repo)),
+            Path(TagId::new().to_string()),
+        )
+        .await
+        .expect_err("存在しないタグは失敗する");
+
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+    }
+
+    #[tokio::test]
+    async fn タグ付けは204を返す() {
+        let user_id = UserId::new();
+        let (tag_id, todo_id) = (TagId::new(), TodoId::new());
+        let mut repo = MockTagRepository::new();
+        repo.expect_attach()
+            .withf(move |event| {
+                event.tag_id == tag_id && event.todo_id == todo_id && event.user_id == user_id
+            })
+            .returning(|_| Ok(()));
+
+        let status = attach_tag(
+            AuthorizedUser::for_test(user_id),
+            State(registry_with(repo)),
+            Path((tag_id.to_string(), todo_id.to_string())),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+}
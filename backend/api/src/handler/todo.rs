@@ -0,0 +1,663 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use garde::Validate;
+use kernel::model::{
+    id::TodoId,
+    todo::{TodoSort, event::DeleteTodo},
+};
+use registry::AppRegistry;
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::{
+        list::ListQueryParams,
+        todo::{
+            CreateTodoRequest, CreateTodoRequestWithUserId, MoveTodoRequest,
+            MoveTodoRequestWithIds, TodoDetailResponse, TodoFilterParams, TodoResponse,
+            TodoSearchHitResponse, TodoSearchParams, TodoSearchResponse, TodosResponse,
+            UpdateTodoRequest, UpdateTodoRequestWithIds,
+        },
+    },
+};
+use shared::error::{AppError, AppResult};
+
+pub async fn register_todo(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateTodoRequest>,
+) -> AppResult<(StatusCode, Json<TodoResponse>)> {
+    req.validate()?;
+
+    let todo = registry
+        .todo_repository()
+        .create(CreateTodoRequestWithUserId::new(user.id(), req).into())
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(todo.into())))
+}
+
+pub async fn list_todos(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Query(filter): Query<TodoFilterParams>,
+    Query(params): Query<ListQueryParams<TodoSort>>,
+) -> AppResult<(StatusCode, Json<TodosResponse>)> {
+    let page = registry
+        .todo_repository()
+        .find_all(user.id(), filter.try_into()?, params.try_into()?)
+        .await?;
+
+    Ok((StatusCode::OK, Json(page.into())))
+}
+
+pub async fn search_todos(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Query(params): Query<TodoSearchParams>,
+) -> AppResult<(StatusCode, Json<TodoSearchResponse>)> {
+    let items = registry
+        .todo_repository()
+        .search(user.id(), params.try_into()?)
+        .await?
+        .into_iter()
+        .map(TodoSearchHitResponse::from)
+        .collect();
+
+    Ok((StatusCode::OK, Json(TodoSearchResponse { items })))
+}
+
+pub async fn show_todo(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path(todo_id): Path<String>,
+) -> AppResult<(StatusCode, Json<TodoDetailResponse>)> {
+    let todo_id: TodoId = todo_id.parse()?;
+    let tree = registry
+        .todo_repository()
+        .find_tree(todo_id, user.id())
+        .await?
+        .ok_or_else(|| AppError::EntityNotFoundError("The todo was not found".into()))?;
+    let dependencies = registry
+        .dependency_repository()
+        .find_by_todo_id(todo_id, user.id())
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(TodoDetailResponse::new(tree, dependencies)),
+    ))
+}
+
+pub async fn update_todo(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path(todo_id): Path<String>,
+    Json(req): Json<UpdateTodoRequest>,
+) -> AppResult<StatusCode> {
+    let todo_id: TodoId = todo_id.parse()?;
+    req.validate()?;
+
+    registry
+        .todo_repository()
+        .update(UpdateTodoRequestWithIds::new(todo_id, user.id(), req).into())
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+pub async fn move_todo(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path(todo_id): Path<String>,
+    Json(req): Json<MoveTodoRequest>,
+) -> AppResult<(StatusCode, Json<TodoResponse>)> {
+    let todo_id: TodoId = todo_id.parse()?;
+    req.validate()?;
+
+    let todo = registry
+        .todo_repository()
+        .reorder(MoveTodoRequestWithIds::new(todo_id, user.id(), req).into())
+        .await?;
+
+    Ok((StatusCode::OK, Json(todo.into())))
+}
+
+pub async fn delete_todo(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Path(todo_id): Path<String>,
+) -> AppResult<StatusCode> {
+    let todo_id: TodoId = todo_id.parse()?;
+    registry
+        .todo_repository()
+        .delete(DeleteTodo {
+            id: todo_id,
+            user_id: user.id(),
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use kernel::model::{
+        dependency::{DependencyTodo, TodoDependencies},
+        id::{ProjectId, TagId, TodoId, UserId},
+        list::{Cursor, Page, SortOrder},
+        todo::{Priority, Todo, position::Position, search::TodoSearchHit, tree::TodoTree},
+    };
+    use kernel::repository::{
+        dependency::{DependencyRepository, MockDependencyRepository},
+        todo::{MockTodoRepository, TodoRepository},
+    };
+    use registry::MockAppRegistryExt;
+    use std::sync::Arc;
+
+    fn todo(user_id: UserId, title: &str) -> Todo {
+        let now = Utc::now();
+        Todo {
+            id: TodoId::new(),
+            user_id,
+            project_id: None,
+            parent_id: None,
+            title: title.to_string(),
+            description: None,
+            priority: Priority::None,
+            completed: false,
+            due_at: None,
+            recurrence: None,
+            position: Position::first(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn registry_with(repo: MockTodoRepository) -> AppRegistry {
+        let mut registry = MockAppRegistryExt::new();
+        let repo_arc: Arc<dyn TodoRepository> = Arc::new(repo);
+        registry.expect_todo_repository().return_const(repo_arc);
+        Arc::new(registry)
+    }
+
+    #[tokio::test]
+    async fn todo追加は201と作成したtodoを返す() {
+        let user_id = UserId::new();
+        let mut repo = MockTodoRepository::new();
+        repo.expect_create()
+            .withf(move |event| event.user_id == user_id)
+            .returning(|event| {
+                let mut created = todo(event.user_id, &event.title);
+                created.description = event.description;
+                created.priority = event.priority;
+                created.due_at = event.due_at;
+                Ok(created)
+            });
+
+        let req = CreateTodoRequest::new(
+            "牛乳を買う".to_string(),
+            None,
+            Priority::High,
+            Some("**低脂肪**を選ぶ".to_string()),
+            None,
+            None,
+            None,
+        );
+
+        let (status, Json(body)) = register_todo(
+            AuthorizedUser::for_test(user_id),
+            State(registry_with(repo)),
+            Json(req),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body.title, "牛乳を買う");
+        assert_eq!(body.priority, Priority::High);
+        assert_eq!(
+            body.description_html.as_deref(),
+            Some("<p><strong>低脂肪</strong>を選ぶ</p>\n")
+        );
+        assert!(!body.completed);
+    }
+
+    #[tokio::test]
+    async fn todo追加は空のタイトルで失敗する() {
+        let registry: AppRegistry = Arc::new(MockAppRegistryExt::new());
+        let req =
+            CreateTodoRequest::new(String::new(), None, Priority::None, None, None, None, None);
+
+        let err = register_todo(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry),
+            Json(req),
+        )
+        .await
+        .expect_err("バリデーションは失敗する");
+
+        assert!(matches!(err, AppError::ValidationError(_)));
+    }
+
+    #[tokio::test]
+    async fn todo追加は長すぎる説明を拒否する() {
+        let registry: AppRegistry = Arc::new(MockAppRegistryExt::new());
+        let req = CreateTodoRequest::new(
+            "牛乳を買う".to_string(),
+            None,
+            Priority::None,
+            Some("a".repeat(10_001)),
+            None,
+            None,
+            None,
+        );
+
+        let err = register_todo(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry),
+            Json(req),
+        )
+        .await
+        .expect_err("バリデーションは失敗する");
+
+        assert!(matches!(err, AppError::ValidationError(_)));
+    }
+
+    #[tokio::test]
+    async fn todo一覧は200と自分のtodo配列を返す() {
+        let user_id = UserId::new();
+        let mut repo = MockTodoRepository::new();
+        repo.expect_find_all()
+            .withf(move |id, _, _| *id == user_id)
+            .returning(|id, _, _| {
+                Ok(Page {
+                    items: vec![todo(id, "一つ目"), todo(id, "二つ目")],
+                    next_cursor: None,
+                })
+            });
+
+        let (status, Json(body)) = list_todos(
+            AuthorizedUser::for_test(user_id),
+            State(registry_with(repo)),
+            Query(TodoFilterParams::default()),
+            Query(ListQueryParams::default()),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.items.len(), 2);
+        assert_eq!(body.items[0].title, "一つ目");
+        assert_eq!(body.items[1].title, "二つ目");
+        assert!(body.next_cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn todo一覧は続きがあればnext_cursorを返す() {
+        let user_id = UserId::new();
+        let last = TodoId::new();
+        let mut repo = MockTodoRepository::new();
+        repo.expect_find_all()
+            .withf(|_, _, query| {
+                query.limit == 1 && query.sort == TodoSort::Title && query.order == SortOrder::Asc
+            })
+            .returning(move |id, _, _| {
+                Ok(Page {
+                    items: vec![todo(id, "一つ目")],
+                    next_cursor: Some(Cursor::new(TodoSort::Title, "一つ目".into(), last.raw())),
+                })
+            });
+
+        let (_, Json(body)) = list_todos(
+            AuthorizedUser::for_test(user_id),
+            State(registry_with(repo)),
+            Query(TodoFilterParams::default()),
+            Query(ListQueryParams::new(
+                Some(1),
+                None,
+                Some(TodoSort::Title),
+                Some(SortOrder::Asc),
+            )),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        let cursor =
+            Cursor::decode(&body.next_cursor.expect("次ページがある")).expect("カーソルを戻せる");
+        assert_eq!(cursor.id, last.raw());
+        assert_eq!(cursor.key, "一つ目");
+    }
+
+    #[tokio::test]
+    async fn todo一覧は不正な件数や並び替えと合わないカーソルを拒否する() {
+        let other_sort = Cursor::new(TodoSort::Title, "a".into(), TodoId::new().raw()).encode();
+        let cases = [
+            (ListQueryParams::new(Some(0), None, None, None), "件数0"),
+            (ListQueryParams::new(Some(101), None, None, None), "件数101"),
+            (
+                ListQueryParams::new(None, Some("zz".into()), None, None),
+                "壊れたカーソル",
+            ),
+            (
+                ListQueryParams::new(None, Some(other_sort), Some(TodoSort::CreatedAt), None),
+                "並び替え違いのカーソル",
+            ),
+        ];
+
+        for (params, case) in cases {
+            let result = list_todos(
+                AuthorizedUser::for_test(UserId::new()),
+                State(registry_with(MockTodoRepository::new())),
+                Query(TodoFilterParams::default()),
+                Query(params),
+            )
+            .await;
+            assert!(
+                matches!(
+                    result,
+                    Err(AppError::ValidationError(_) | AppError::ConversionEntityError(_))
+                ),
+                "{case}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn todo一覧は絞り込み条件をリポジトリに渡す() {
+        let now = Utc::now();
+        let (bug, review) = (TagId::new(), TagId::new());
+        let mut repo = MockTodoRepository::new();
+        repo.expect_find_all()
+            .withf(move |_, filter, _| {
+                filter.overdue
+                    && filter.completed == Some(false)
+                    && filter.due.before == Some(now)
+                    && filter.title.as_deref() == Some("牛乳")
+                    && filter.tags_any == vec![bug, review]
+                    && filter.tags_all == vec![bug]
+            })
+            .returning(|_, _, _| {
+                Ok(Page {
+                    items: vec![],
+                    next_cursor: None,
+                })
+            });
+
+        let (status, _) = list_todos(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry_with(repo)),
+            Query(TodoFilterParams {
+                completed: Some(false),
+                overdue: Some(true),
+                due_before: Some(now),
+                title: Some("牛乳".to_string()),
+                tags_any: Some(format!("{bug}, {review}")),
+                tags_all: Some(bug.to_string()),
+                ..Default::default()
+            }),
+            Query(ListQueryParams::default()),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn todo一覧は逆転した期間や空のタイトルを拒否する() {
+        let now = Utc::now();
+        let cases = [
+            TodoFilterParams {
+                due_after: Some(now),
+                due_before: Some(now - chrono::Duration::days(1)),
+                ..Default::default()
+            },
+            TodoFilterParams {
+                title: Some(String::new()),
+                ..Default::default()
+            },
+            TodoFilterParams {
+                tags_any: Some("bug".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        for filter in cases {
+            let result = list_todos(
+                AuthorizedUser::for_test(UserId::new()),
+                State(registry_with(MockTodoRepository::new())),
+                Query(filter),
+                Query(ListQueryParams::default()),
+            )
+            .await;
+            assert!(matches!(
+                result,
+                Err(AppError::ValidationError(_)
+                    | AppError::ConversionEntityError(_)
+                    | AppError::ConvertToUuidError(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn todo検索は200と強調付きの結果を返す() {
+        let user_id = UserId::new();
+        let mut repo = MockTodoRepository::new();
+        repo.expect_search()
+            .withf(move |id, search| *id == user_id && search.text == "牛乳 milk")
+            .returning(|id, search| {
+                let todo = todo(id, "牛乳 milk");
+                Ok(vec![TodoSearchHit {
+                    snippet: search.highlight(&todo.title),
+                    rank: 0.5,
+                    todo,
+                }])
+            });
+
+        let (status, Json(body)) = search_todos(
+            AuthorizedUser::for_test(user_id),
+            State(registry_with(repo)),
+            Query(TodoSearchParams::new(" 牛乳  milk ".to_string(), None)),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.items.len(), 1);
+        assert_eq!(body.items[0].snippet, "<mark>牛乳</mark> <mark>milk</mark>");
+    }
+
+    #[tokio::test]
+    async fn todo検索は空の検索語を拒否する() {
+        for q in ["", "   "] {
+            let result = search_todos(
+                AuthorizedUser::for_test(UserId::new()),
+                State(registry_with(MockTodoRepository::new())),
+                Query(TodoSearchParams::new(q.to_string(), None)),
+            )
+            .await;
+            assert!(matches!(
+                result,
+                Err(AppError::ValidationError(_) | AppError::ConversionEntityError(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn todo取得は存在しないidで404になる() {
+        let mut repo = MockTodoRepository::new();
+        repo.expect_find_tree().returning(|_, _| Ok(None));
+
+        let err = show_todo(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry_with(repo)),
+            Path(TodoId::new().to_string()),
+        )
+        .await
+        .expect_err("存在しないtodoは失敗する");
+
+        assert!(matches!(err, AppError::EntityNotFoundError(_)));
+    }
+
+    #[tokio::test]
+    async fn todo詳細はサブタスクと進捗と依存を返す() {
+        let user_id = UserId::new();
+        let mut repo = MockTodoRepository::new();
+        repo.expect_find_tree().returning(|id, user_id| {
+            let mut root = todo(user_id, "買い物");
+            root.id = id;
+            let mut child = todo(user_id, "牛乳を買う");
+            child.parent_id = Some(id);
+            child.completed = true;
+            Ok(Some(TodoTree::build(root, vec![child])))
+        });
+        let todo_id = TodoId::new();
+        let blocker_id = TodoId::new();
+        let mut dependency_repo = MockDependencyRepository::new();
+        dependency_repo
+            .expect_find_by_todo_id()
+            .returning(move |_, _| {
+                Ok(TodoDependencies {
+                    blockers: vec![DependencyTodo {
+                        id: blocker_id,
+                        title: "財布を探す".to_string(),
+                        completed: false,
+                    }],
+                    dependents: vec![],
+                })
+            });
+        let mut registry = MockAppRegistryExt::new();
+        let repo_arc: Arc<dyn TodoRepository> = Arc::new(repo);
+        let dependency_repo: Arc<dyn DependencyRepository> = Arc::new(dependency_repo);
+        registry.expect_todo_repository().return_const(repo_arc);
+        registry
+            .expect_dependency_repository()
+            .return_const(dependency_repo);
+
+        let (status, Json(body)) = show_todo(
+            AuthorizedUser::for_test(user_id),
+            State(Arc::new(registry)),
+            Path(todo_id.to_string()),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.tree.todo.id, todo_id);
+        assert_eq!((body.tree.progress.done, body.tree.progress.total), (1, 1));
+        assert_eq!(body.tree.children[0].todo.parent_id, Some(todo_id));
+        assert_eq!(body.blockers[0].id, blocker_id);
+        assert!(body.dependents.is_empty());
+    }
+
+    #[tokio::test]
+    async fn todo更新は200を返す() {
+        let user_id = UserId::new();
+        let todo_id = TodoId::new();
+        let project_id = ProjectId::new();
+        let mut repo = MockTodoRepository::new();
+        repo.expect_update()
+            .withf(move |event| {
+                event.id == todo_id
+                    && event.user_id == user_id
+                    && event.title == "更新後"
+                    && event.priority == Priority::Low
+                    && event.project_id == Some(project_id)
+            })
+            .returning(|_event| Ok(()));
+
+        let req = UpdateTodoRequest::new(
+            "更新後".to_string(),
+            None,
+            Priority::Low,
+            None,
+            Some(project_id),
+            None,
+            None,
+        );
+
+        let status = update_todo(
+            AuthorizedUser::for_test(user_id),
+            State(registry_with(repo)),
+            Path(todo_id.to_string()),
+            Json(req),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn todo削除は204を返す() {
+        let user_id = UserId::new();
+        let todo_id = TodoId::new();
+        let mut repo = MockTodoRepository::new();
+        repo.expect_delete()
+            .withf(move |event| event.id == todo_id && event.user_id == user_id)
+            .returning(|_event| Ok(()));
+
+        let status = delete_todo(
+            AuthorizedUser::for_test(user_id),
+            State(registry_with(repo)),
+            Path(todo_id.to_string()),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn todo削除は不正なidで失敗する() {
+        let registry: AppRegistry = Arc::new(MockAppRegistryExt::new());
+
+        let err = delete_todo(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry),
+            Path("invalid".to_string()),
+        )
+        .await
+        .expect_err("不正なIDは失敗する");
+
+        assert!(matches!(err, AppError::ConvertToUuidError(_)));
+    }
+
+    #[tokio::test]
+    async fn todoの移動は200と移動後のtodoを返す() {
+        let user_id = UserId::new();
+        let (todo_id, after) = (TodoId::new(), TodoId::new());
+        let mut repo = MockTodoRepository::new();
+        repo.expect_reorder()
+            .withf(move |event| {
+                event.id == todo_id
+                    && event.user_id == user_id
+                    && event.after == Some(after)
+                    && event.before.is_none()
+            })
+            .returning(|event| {
+                let mut moved = todo(event.user_id, "動かした");
+                moved.id = event.id;
+                moved.position = "a0V".parse().expect("正しいキー");
+                Ok(moved)
+            });
+
+        let (status, Json(body)) = move_todo(
+            AuthorizedUser::for_test(user_id),
+            State(registry_with(repo)),
+            Path(todo_id.to_string()),
+            Json(MoveTodoRequest::new(Some(after), None)),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.id, todo_id);
+        assert_eq!(body.position.as_str(), "a0V");
+    }
+}
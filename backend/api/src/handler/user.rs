@@ -1,14 +1,26 @@
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
 };
 use garde::Validate;
-use kernel::model::{id::UserId, user::event::DeleteUser};
+use kernel::model::{
+    id::UserId,
+    user::{UserSort, event::DeleteUser},
+};
 use registry::AppRegistry;
 
-use crate::model::user::{CreateUserRequest, UserResponse, UsersResponse};
-use shared::error::AppResult;
+use crate::{
+    extractor::AuthorizedUser,
+    model::{
+        list::ListQueryParams,
+        user::{
+            CreateUserRequest, UpdateUserPasswordRequest, UpdateUserPasswordRequestWithUserId,
+            UserResponse, UsersResponse,
+        },
+    },
+};
+use shared::error::{AppError, AppResult};
 
 pub async fn register_user(
     State(registry): State<AppRegistry>,
@@ -22,24 +34,54 @@ pub async fn register_user(
 }
 
 pub async fn list_users(
+    _user: AuthorizedUser,
     State(registry): State<AppRegistry>,
+    Query(params): Query<ListQueryParams<UserSort>>,
 ) -> AppResult<(StatusCode, Json<UsersResponse>)> {
-    let items = registry
+    let page = registry
         .user_repository()
-        .find_all()
-        .await?
-        .into_iter()
-        .map(UserResponse::from)
-        .collect();
+        .find_all(params.try_into()?)
+        .await?;
+
+    Ok((StatusCode::OK, Json(page.into())))
+}
 
-    Ok((StatusCode::OK, Json(UsersResponse { items })))
+pub async fn get_current_user(user: AuthorizedUser) -> Json<UserResponse> {
+    Json(user.user.into())
+}
+
+pub async fn change_password(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateUserPasswordRequest>,
+) -> AppResult<StatusCode> {
+    req.validate()?;
+    // 他のセッションを失効させるときに今のセッションだけは残すため、ログインしていなければ変更させない
+    let access_token_id = user.access_token_id()?;
+
+    registry
+        .user_repository()
+        .update_password(UpdateUserPasswordRequestWithUserId::new(user.id(), req).into())
+        .await?;
+    registry
+        .auth_repository()
+        .delete_other_tokens(user.id(), access_token_id)
+        .await?;
+
+    Ok(StatusCode::OK)
 }
 
 pub async fn delete_user(
+    user: AuthorizedUser,
     State(registry): State<AppRegistry>,
     Path(user_id): Path<String>,
 ) -> AppResult<StatusCode> {
     let user_id: UserId = user_id.parse()?;
+    if user_id != user.id() {
+        return Err(AppError::ForbiddenOperation(
+            "Only the user themselves can be deleted".into(),
+        ));
+    }
     registry
         .user_repository()
         .delete(DeleteUser { id: user_id })
@@ -53,8 +95,11 @@ mod tests {
     use super::*;
     use axum::extract::Path;
     use axum::extract::State;
-    use kernel::model::{id::UserId, user::User};
-    use kernel::repository::user::{MockUserRepository, UserRepository};
+    use kernel::model::{id::UserId, list::Page, user::User};
+    use kernel::repository::{
+        auth::{AuthRepository, MockAuthRepository},
+        user::{MockUserRepository, UserRepository},
+    };
     use registry::MockAppRegistryExt;
     use shared::error::AppError;
     use std::sync::Arc;
@@ -93,19 +138,22 @@ mod tests {
     #[tokio::test]
     async fn ユーザ一覧は200とユーザ配列を返す() {
         let mut repo = MockUserRepository::new();
-        repo.expect_find_all().returning(|| {
-            Ok(vec![
-                User {
-                    id: UserId::new(),
-                    name: "Alice".to_string(),
-                    email: "alice@example.com".to_string(),
-                },
-                User {
-                    id: UserId::new(),
-                    name: "Bob".to_string(),
-                    email: "bob@example.com".to_string(),
-                },
-            ])
+        repo.expect_find_all().returning(|_| {
+            Ok(Page {
+                items: vec![
+                    User {
+                        id: UserId::new(),
+                        name: "Alice".to_string(),
+                        email: "alice@example.com".to_string(),
+                    },
+                    User {
+                        id: UserId::new(),
+                        name: "Bob".to_string(),
+                        email: "bob@example.com".to_string(),
+                    },
+                ],
+                next_cursor: None,
+            })
         });
 
         let mut registry = MockAppRegistryExt::new();
@@ -114,11 +162,16 @@ mod tests {
 
         let registry: AppRegistry = Arc::new(registry);
 
-        let (status, Json(body)) = list_users(State(registry))
-            .await
-            .expect("正常系は成功を期待する");
+        let (status, Json(body)) = list_users(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry),
+            Query(ListQueryParams::default()),
+        )
+        .await
+        .expect("正常系は成功を期待する");
 
         assert_eq!(status, StatusCode::OK);
+        assert!(body.next_cursor.is_none());
         assert_eq!(body.items.len(), 2);
         assert_eq!(body.items[0].name, "Alice");
         assert_eq!(body.items[0].email, "alice@example.com");
@@ -140,9 +193,13 @@ mod tests {
 
         let registry: AppRegistry = Arc::new(registry);
 
-        let status = delete_user(State(registry), Path(user_id.to_string()))
-            .await
-            .expect("正常系は成功を期待する");
+        let status = delete_user(
+            AuthorizedUser::for_test(user_id),
+            State(registry),
+            Path(user_id.to_string()),
+        )
+        .await
+        .expect("正常系は成功を期待する");
 
         assert_eq!(status, StatusCode::NO_CONTENT);
     }
@@ -202,9 +259,13 @@ mod tests {
 
         let registry: AppRegistry = Arc::new(registry);
 
-        let err = delete_user(State(registry), Path(user_id.to_string()))
-            .await
-            .expect_err("存在しないユーザは失敗する");
+        let err = delete_user(
+            AuthorizedUser::for_test(user_id),
+            State(registry),
+            Path(user_id.to_string()),
+        )
+        .await
+        .expect_err("存在しないユーザは失敗する");
 
         assert!(matches!(err, AppError::EntityNotFoundError(_)));
     }
@@ -214,10 +275,137 @@ mod tests {
         let registry = MockAppRegistryExt::new();
         let registry: AppRegistry = Arc::new(registry);
 
-        let err = delete_user(State(registry), Path("invalid".to_string()))
-            .await
-            .expect_err("不正なIDは失敗する");
+        let err = delete_user(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry),
+            Path("invalid".to_string()),
+        )
+        .await
+        .expect_err("不正なIDは失敗する");
 
         assert!(matches!(err, AppError::ConvertToUuidError(_)));
     }
+
+    #[tokio::test]
+    async fn ユーザ削除は他人のidで403になる() {
+        let mut repo = MockUserRepository::new();
+        repo.expect_delete().never();
+
+        let mut registry = MockAppRegistryExt::new();
+        let repo_arc: Arc<dyn UserRepository> = Arc::new(repo);
+        registry.expect_user_repository().return_const(repo_arc);
+
+        let registry: AppRegistry = Arc::new(registry);
+
+        let err = delete_user(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry),
+            Path(UserId::new().to_string()),
+        )
+        .await
+        .expect_err("他人のユーザは削除できない");
+
+        assert!(matches!(err, AppError::ForbiddenOperation(_)));
+    }
+
+    #[tokio::test]
+    async fn 自分情報取得はログイン中のユーザを返す() {
+        let user_id = UserId::new();
+
+        let Json(body) = get_current_user(AuthorizedUser::for_test(user_id)).await;
+
+        assert_eq!(body.id, user_id);
+        assert_eq!(body.name, "Alice");
+        assert_eq!(body.email, "alice@example.com");
+    }
+
+    #[tokio::test]
+    async fn パスワード更新は200を返し他のトークンを無効化する() {
+        let user_id = UserId::new();
+        let mut user_repo = MockUserRepository::new();
+        user_repo
+            .expect_update_password()
+            .withf(move |event| {
+                event.user_id == user_id
+                    && event.current_password == "password123"
+                    && event.new_password == "new-password456"
+            })
+            .returning(|_event| Ok(()));
+        let mut auth_repo = MockAuthRepository::new();
+        auth_repo
+            .expect_delete_other_tokens()
+            .withf(move |id, keep| *id == user_id && keep.0 == "test-token")
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let mut registry = MockAppRegistryExt::new();
+        let user_repo: Arc<dyn UserRepository> = Arc::new(user_repo);
+        let auth_repo: Arc<dyn AuthRepository> = Arc::new(auth_repo);
+        registry.expect_user_repository().return_const(user_repo);
+        registry.expect_auth_repository().return_const(auth_repo);
+
+        let registry: AppRegistry = Arc::new(registry);
+        let req = UpdateUserPasswordRequest::new(
+            "password123".to_string(),
+            "new-password456".to_string(),
+        );
+
+        let status = change_password(
+            AuthorizedUser::for_test(user_id),
+            State(registry),
+            Json(req),
+        )
+        .await
+        .expect("正常系は成功を期待する");
+
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn パスワード更新は現在のパスワード不一致で401になる() {
+        let mut user_repo = MockUserRepository::new();
+        user_repo
+            .expect_update_password()
+            .returning(|_event| Err(AppError::Unauthorized("mismatch".into())));
+        let mut auth_repo = MockAuthRepository::new();
+        auth_repo.expect_delete_other_tokens().never();
+
+        let mut registry = MockAppRegistryExt::new();
+        let user_repo: Arc<dyn UserRepository> = Arc::new(user_repo);
+        let auth_repo: Arc<dyn AuthRepository> = Arc::new(auth_repo);
+        registry.expect_user_repository().return_const(user_repo);
+        registry.expect_auth_repository().return_const(auth_repo);
+
+        let registry: AppRegistry = Arc::new(registry);
+        let req = UpdateUserPasswordRequest::new(
+            "wrong-password".to_string(),
+            "new-password456".to_string(),
+        );
+
+        let err = change_password(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry),
+            Json(req),
+        )
+        .await
+        .expect_err("不一致は失敗する");
+
+        assert!(matches!(err, AppError::Unauthorized(_)));
+    }
+
+    #[tokio::test]
+    async fn パスワード更新は新しいパスワードが空なら失敗する() {
+        let registry: AppRegistry = Arc::new(MockAppRegistryExt::new());
+        let req = UpdateUserPasswordRequest::new("password123".to_string(), String::new());
+
+        let err = change_password(
+            AuthorizedUser::for_test(UserId::new()),
+            State(registry),
+            Json(req),
+        )
+        .await
+        .expect_err("バリデーションは失敗する");
+
+        assert!(matches!(err, AppError::ValidationError(_)));
+    }
 }
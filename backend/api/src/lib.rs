@@ -1,3 +1,5 @@
+pub mod extractor;
 pub mod handler;
+mod markdown;
 pub mod model;
 pub mod route;
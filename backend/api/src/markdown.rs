@@ -0,0 +1,62 @@
+use ammonia::Builder;
+use pulldown_cmark::{Options, Parser, html};
+use std::sync::LazyLock;
+
+// タスクリストのチェックボックスだけは残す。input は種類と編集可否を固定し、チェック状態だけを元の HTML から引き継ぐ
+static SANITIZER: LazyLock<Builder<'static>> = LazyLock::new(|| {
+    let mut builder = Builder::default();
+    builder
+        .add_tags(["input"])
+        .add_tag_attributes("input", ["checked"])
+        .set_tag_attribute_value("input", "type", "checkbox")
+        .set_tag_attribute_value("input", "disabled", "");
+    builder
+});
+
+// Markdown を HTML に変換し、スクリプトやイベント属性などを取り除いてから返す
+pub fn render(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, Parser::new_ext(markdown, options));
+    SANITIZER.clean(&unsafe_html).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdownをhtmlに変換する() {
+        let html = render("**牛乳**を買う\n\n- [x] 低脂肪\n- 1L");
+
+        assert!(html.contains("<strong>牛乳</strong>"));
+        assert!(html.contains("<li>"));
+        assert!(html.contains("type=\"checkbox\""));
+        assert!(html.contains("checked"));
+    }
+
+    #[test]
+    fn チェックボックス以外のinputは残さない() {
+        let html = render("<input type=\"text\" value=\"x\" onfocus=\"x()\">");
+
+        assert!(html.contains("type=\"checkbox\""));
+        assert!(!html.contains("type=\"text\""));
+        assert!(!html.contains("value"));
+        assert!(!html.contains("onfocus"));
+    }
+
+    #[test]
+    fn スクリプトや危険な属性は取り除く() {
+        let html = render(
+            "<script>alert(1)</script><a href=\"javascript:alert(1)\" onclick=\"x()\">link</a>",
+        );
+
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("javascript:"));
+        assert!(!html.contains("onclick"));
+        assert!(html.contains("link"));
+    }
+}
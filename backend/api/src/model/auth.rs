@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    auth::{AccessTokenId, Session},
+    id::{SessionId, UserId},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Validate, new)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginRequest {
+    #[garde(email)]
+    pub email: String,
+    #[garde(length(min = 1))]
+    pub password: String,
+}
+
+#[derive(Serialize, Deserialize, Validate, new)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshTokenRequest {
+    #[garde(length(min = 1))]
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessTokenResponse {
+    pub user_id: UserId,
+    pub access_token: String,
+    pub expires_in: u64,
+    pub refresh_token: String,
+    pub refresh_expires_in: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionResponse {
+    pub id: SessionId,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    // リクエストに使ったアクセストークンのセッションかどうか
+    pub current: bool,
+}
+
+impl SessionResponse {
+    pub fn new(session: Session, current: &AccessTokenId) -> Self {
+        let Session {
+            id,
+            access_token_id,
+            client,
+            created_at,
+            last_used_at,
+        } = session;
+        Self {
+            id,
+            user_agent: client.user_agent,
+            ip_address: client.ip_address,
+            created_at,
+            last_used_at,
+            current: &access_token_id == current,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionsResponse {
+    pub items: Vec<SessionResponse>,
+}
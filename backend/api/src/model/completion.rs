@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use kernel::model::{
+    completion::{Completion, CompletionTodo},
+    id::{CompletionId, TodoId},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, new)]
+pub struct CompleteTodoParams {
+    // true なら未完了のサブタスクもまとめて完了にする
+    #[serde(default)]
+    pub subtasks: bool,
+    // true なら未完了の blocker が残っていても完了にする
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionResponse {
+    pub id: CompletionId,
+    pub todo: CompletionTodoResponse,
+    pub completed_at: DateTime<Utc>,
+    pub reopened_at: Option<DateTime<Utc>>,
+}
+
+impl From<Completion> for CompletionResponse {
+    fn from(value: Completion) -> Self {
+        let Completion {
+            id,
+            todo,
+            completed_at,
+            reopened_at,
+        } = value;
+        Self {
+            id,
+            todo: todo.into(),
+            completed_at,
+            reopened_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompletionsResponse {
+    pub items: Vec<CompletionResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompletionTodoResponse {
+    pub id: TodoId,
+    pub title: String,
+}
+
+impl From<CompletionTodo> for CompletionTodoResponse {
+    fn from(value: CompletionTodo) -> Self {
+        let CompletionTodo { id, title } = value;
+        Self { id, title }
+    }
+}
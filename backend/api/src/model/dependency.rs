@@ -0,0 +1,24 @@
+use kernel::model::{dependency::DependencyTodo, id::TodoId};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DependencyTodoResponse {
+    pub id: TodoId,
+    pub title: String,
+    pub completed: bool,
+}
+
+impl From<DependencyTodo> for DependencyTodoResponse {
+    fn from(value: DependencyTodo) -> Self {
+        let DependencyTodo {
+            id,
+            title,
+            completed,
+        } = value;
+        Self {
+            id,
+            title,
+            completed,
+        }
+    }
+}
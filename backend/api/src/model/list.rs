@@ -0,0 +1,32 @@
+use derive_new::new;
+use garde::Validate;
+use kernel::model::list::{ListQuery, SortKey, SortOrder};
+use serde::Deserialize;
+use shared::error::{AppError, AppResult};
+
+#[derive(Debug, Default, Deserialize, Validate, new)]
+pub struct ListQueryParams<S> {
+    #[garde(range(min = 1, max = 100))]
+    limit: Option<i64>,
+    #[garde(skip)]
+    cursor: Option<String>,
+    #[garde(skip)]
+    sort: Option<S>,
+    #[garde(skip)]
+    order: Option<SortOrder>,
+}
+
+impl<S: SortKey> TryFrom<ListQueryParams<S>> for ListQuery<S> {
+    type Error = AppError;
+
+    fn try_from(value: ListQueryParams<S>) -> AppResult<Self> {
+        value.validate()?;
+        let ListQueryParams {
+            limit,
+            cursor,
+            sort,
+            order,
+        } = value;
+        ListQuery::new(limit, cursor.as_deref(), sort, order)
+    }
+}
@@ -1 +1,9 @@
+pub mod auth;
+pub mod completion;
+pub mod dependency;
+pub mod list;
+pub mod personal_access_token;
+pub mod project;
+pub mod tag;
+pub mod todo;
 pub mod user;
@@ -0,0 +1,98 @@
+use chrono::{DateTime, Duration, Utc};
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    id::{PersonalAccessTokenId, UserId},
+    personal_access_token::{
+        PersonalAccessToken, PersonalAccessTokenSecret, TokenScope,
+        event::CreatePersonalAccessToken,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonalAccessTokenResponse {
+    pub id: PersonalAccessTokenId,
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<PersonalAccessToken> for PersonalAccessTokenResponse {
+    fn from(value: PersonalAccessToken) -> Self {
+        let PersonalAccessToken {
+            id,
+            name,
+            scopes,
+            expires_at,
+            last_used_at,
+            created_at,
+            ..
+        } = value;
+        Self {
+            id,
+            name,
+            scopes,
+            expires_at,
+            last_used_at,
+            created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PersonalAccessTokensResponse {
+    pub items: Vec<PersonalAccessTokenResponse>,
+}
+
+// 作成したときだけ平文のトークンを返す。後から取得する手段はない
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedPersonalAccessTokenResponse {
+    #[serde(flatten)]
+    pub personal_access_token: PersonalAccessTokenResponse,
+    pub token: String,
+}
+
+#[derive(Serialize, Deserialize, Validate, new)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePersonalAccessTokenRequest {
+    #[garde(length(min = 1, max = 100))]
+    name: String,
+    #[garde(length(min = 1))]
+    scopes: Vec<TokenScope>,
+    // 省略すると失効させるまで使える
+    #[garde(range(min = 1, max = 365))]
+    expires_in_days: Option<u32>,
+}
+
+#[derive(new)]
+pub struct CreatePersonalAccessTokenRequestWithUserId(
+    UserId,
+    PersonalAccessTokenSecret,
+    CreatePersonalAccessTokenRequest,
+);
+
+impl From<CreatePersonalAccessTokenRequestWithUserId> for CreatePersonalAccessToken {
+    fn from(value: CreatePersonalAccessTokenRequestWithUserId) -> Self {
+        let CreatePersonalAccessTokenRequestWithUserId(
+            user_id,
+            secret,
+            CreatePersonalAccessTokenRequest {
+                name,
+                scopes,
+                expires_in_days,
+            },
+        ) = value;
+        Self {
+            user_id,
+            name,
+            scopes,
+            expires_at: expires_in_days.map(|days| Utc::now() + Duration::days(days.into())),
+            secret,
+        }
+    }
+}
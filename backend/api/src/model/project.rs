@@ -0,0 +1,152 @@
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    id::{ProjectId, UserId},
+    project::{
+        DEFAULT_COLOUR, Project, ProjectProgress,
+        event::{CreateProject, DeleteProjectMode, UpdateProject},
+        is_valid_colour,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectResponse {
+    pub id: ProjectId,
+    pub name: String,
+    pub colour: String,
+    pub archived: bool,
+    pub progress: ProjectProgressResponse,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Project> for ProjectResponse {
+    fn from(value: Project) -> Self {
+        let Project {
+            id,
+            name,
+            colour,
+            archived,
+            progress,
+            created_at,
+            updated_at,
+            ..
+        } = value;
+        Self {
+            id,
+            name,
+            colour,
+            archived,
+            progress: progress.into(),
+            created_at,
+            updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectProgressResponse {
+    pub total: i64,
+    pub completed: i64,
+}
+
+impl From<ProjectProgress> for ProjectProgressResponse {
+    fn from(value: ProjectProgress) -> Self {
+        let ProjectProgress { total, completed } = value;
+        Self { total, completed }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectsResponse {
+    pub items: Vec<ProjectResponse>,
+}
+
+#[derive(Debug, Default, Deserialize, new)]
+pub struct ProjectListParams {
+    // true ならアーカイブ済みのプロジェクトも含める
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Debug, Default, Deserialize, new)]
+pub struct DeleteProjectParams {
+    // プロジェクトに属する todo の扱い。省略すると受信箱に移す
+    #[serde(default)]
+    pub todos: DeleteProjectMode,
+}
+
+#[derive(Serialize, Deserialize, Validate, new)]
+pub struct CreateProjectRequest {
+    #[garde(length(min = 1, max = 100))]
+    name: String,
+    #[garde(custom(validate_optional_colour))]
+    colour: Option<String>,
+}
+
+#[derive(new)]
+pub struct CreateProjectRequestWithUserId(UserId, CreateProjectRequest);
+
+impl From<CreateProjectRequestWithUserId> for CreateProject {
+    fn from(value: CreateProjectRequestWithUserId) -> Self {
+        let CreateProjectRequestWithUserId(user_id, CreateProjectRequest { name, colour }) = value;
+        Self {
+            user_id,
+            name,
+            colour: colour.unwrap_or_else(|| DEFAULT_COLOUR.to_string()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, new)]
+pub struct UpdateProjectRequest {
+    #[garde(length(min = 1, max = 100))]
+    name: String,
+    #[garde(custom(validate_colour))]
+    colour: String,
+    #[serde(default)]
+    #[garde(skip)]
+    archived: bool,
+}
+
+#[derive(new)]
+pub struct UpdateProjectRequestWithIds(ProjectId, UserId, UpdateProjectRequest);
+
+impl From<UpdateProjectRequestWithIds> for UpdateProject {
+    fn from(value: UpdateProjectRequestWithIds) -> Self {
+        let UpdateProjectRequestWithIds(
+            id,
+            user_id,
+            UpdateProjectRequest {
+                name,
+                colour,
+                archived,
+            },
+        ) = value;
+        Self {
+            id,
+            user_id,
+            name,
+            colour,
+            archived,
+        }
+    }
+}
+
+fn validate_colour(value: &str, _: &()) -> garde::Result {
+    if is_valid_colour(value) {
+        Ok(())
+    } else {
+        Err(garde::Error::new("must be a colour in #rrggbb format"))
+    }
+}
+
+fn validate_optional_colour(value: &Option<String>, context: &()) -> garde::Result {
+    match value {
+        Some(value) => validate_colour(value, context),
+        None => Ok(()),
+    }
+}
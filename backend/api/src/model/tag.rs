@@ -0,0 +1,75 @@
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    id::{TagId, UserId},
+    tag::{
+        Tag,
+        event::{CreateTag, UpdateTag},
+    },
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagResponse {
+    pub id: TagId,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Tag> for TagResponse {
+    fn from(value: Tag) -> Self {
+        let Tag {
+            id,
+            name,
+            created_at,
+            updated_at,
+            ..
+        } = value;
+        Self {
+            id,
+            name,
+            created_at,
+            updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagsResponse {
+    pub items: Vec<TagResponse>,
+}
+
+#[derive(Serialize, Deserialize, Validate, new)]
+pub struct CreateTagRequest {
+    #[garde(length(min = 1, max = 50))]
+    name: String,
+}
+
+#[derive(new)]
+pub struct CreateTagRequestWithUserId(UserId, CreateTagRequest);
+
+impl From<CreateTagRequestWithUserId> for CreateTag {
+    fn from(value: CreateTagRequestWithUserId) -> Self {
+        let CreateTagRequestWithUserId(user_id, CreateTagRequest { name }) = value;
+        Self { user_id, name }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, new)]
+pub struct UpdateTagRequest {
+    #[garde(length(min = 1, max = 50))]
+    name: String,
+}
+
+#[derive(new)]
+pub struct UpdateTagRequestWithIds(TagId, UserId, UpdateTagRequest);
+
+impl From<UpdateTagRequestWithIds> for UpdateTag {
+    fn from(value: UpdateTagRequestWithIds) -> Self {
+        let UpdateTagRequestWithIds(id, user_id, UpdateTagRequest { name }) = value;
+        Self { id, user_id, name }
+    }
+}
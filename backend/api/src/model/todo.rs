@@ -0,0 +1,430 @@
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    dependency::TodoDependencies,
+    id::{ProjectId, TagId, TodoId, UserId},
+    list::Page,
+    todo::{
+        Priority, Todo,
+        event::{CreateTodo, ReorderTodo, UpdateTodo},
+        filter::{ProjectScope, TimeRange, TodoFilter},
+        position::Position,
+        recurrence::Recurrence,
+        search::{TodoSearch, TodoSearchHit},
+        tree::{SubtaskProgress, TodoTree},
+    },
+};
+use serde::{Deserialize, Serialize};
+use shared::error::{AppError, AppResult};
+
+use crate::{markdown, model::dependency::DependencyTodoResponse};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoResponse {
+    pub id: TodoId,
+    pub project_id: Option<ProjectId>,
+    pub parent_id: Option<TodoId>,
+    pub title: String,
+    pub description: Option<String>,
+    // description を Markdown として描画し、サニタイズした HTML
+    pub description_html: Option<String>,
+    pub priority: Priority,
+    pub completed: bool,
+    pub due_at: Option<DateTime<Utc>>,
+    // RFC 5545 の RRULE 形式の繰り返し規則
+    pub recurrence: Option<Recurrence>,
+    // 手動の並び順でのキー。文字列の辞書順（バイト順）で比較する
+    pub position: Position,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Todo> for TodoResponse {
+    fn from(value: Todo) -> Self {
+        let Todo {
+            id,
+            project_id,
+            parent_id,
+            title,
+            description,
+            priority,
+            completed,
+            due_at,
+            recurrence,
+            position,
+            created_at,
+            updated_at,
+            ..
+        } = value;
+        Self {
+            id,
+            project_id,
+            parent_id,
+            title,
+            description_html: description.as_deref().map(markdown::render),
+            description,
+            priority,
+            completed,
+            due_at,
+            recurrence,
+            position,
+            created_at,
+            updated_at,
+        }
+    }
+}
+
+// `GET /todos/{id}` の応答。子孫を入れ子で持ち、進捗は子孫全体で数える
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoTreeResponse {
+    #[serde(flatten)]
+    pub todo: TodoResponse,
+    pub progress: SubtaskProgressResponse,
+    pub children: Vec<TodoTreeResponse>,
+}
+
+impl From<TodoTree> for TodoTreeResponse {
+    fn from(value: TodoTree) -> Self {
+        let TodoTree {
+            todo,
+            progress,
+            children,
+        } = value;
+        Self {
+            todo: todo.into(),
+            progress: progress.into(),
+            children: children.into_iter().map(TodoTreeResponse::from).collect(),
+        }
+    }
+}
+
+// `GET /todos/{id}` の応答。木構造に加えて依存関係を持つ
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TodoDetailResponse {
+    #[serde(flatten)]
+    pub tree: TodoTreeResponse,
+    // この todo を妨げている todo
+    pub blockers: Vec<DependencyTodoResponse>,
+    // この todo に妨げられている todo
+    pub dependents: Vec<DependencyTodoResponse>,
+}
+
+impl TodoDetailResponse {
+    pub fn new(tree: TodoTree, dependencies: TodoDependencies) -> Self {
+        let TodoDependencies {
+            blockers,
+            dependents,
+        } = dependencies;
+        Self {
+            tree: tree.into(),
+            blockers: blockers
+                .into_iter()
+                .map(DependencyTodoResponse::from)
+                .collect(),
+            dependents: dependents
+                .into_iter()
+                .map(DependencyTodoResponse::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubtaskProgressResponse {
+    pub done: i64,
+    pub total: i64,
+}
+
+impl From<SubtaskProgress> for SubtaskProgressResponse {
+    fn from(value: SubtaskProgress) -> Self {
+        let SubtaskProgress { done, total } = value;
+        Self { done, total }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodosResponse {
+    pub items: Vec<TodoResponse>,
+    pub next_cursor: Option<String>,
+}
+
+impl From<Page<Todo>> for TodosResponse {
+    fn from(value: Page<Todo>) -> Self {
+        let Page { items, next_cursor } = value;
+        Self {
+            items: items.into_iter().map(TodoResponse::from).collect(),
+            next_cursor: next_cursor.map(|cursor| cursor.encode()),
+        }
+    }
+}
+
+// `GET /todos` の絞り込み条件。`*_after` は指定日時を含み、`*_before` は含まない
+#[derive(Debug, Default, Serialize, Deserialize, Validate)]
+pub struct TodoFilterParams {
+    #[garde(skip)]
+    pub completed: Option<bool>,
+    #[garde(skip)]
+    pub overdue: Option<bool>,
+    #[garde(skip)]
+    pub due_after: Option<DateTime<Utc>>,
+    #[garde(skip)]
+    pub due_before: Option<DateTime<Utc>>,
+    #[garde(skip)]
+    pub created_after: Option<DateTime<Utc>>,
+    #[garde(skip)]
+    pub created_before: Option<DateTime<Utc>>,
+    #[garde(skip)]
+    pub updated_after: Option<DateTime<Utc>>,
+    #[garde(skip)]
+    pub updated_before: Option<DateTime<Utc>>,
+    #[garde(length(min = 1, max = 255))]
+    pub title: Option<String>,
+    // カンマ区切りのタグ ID。いずれかが付いているもの
+    #[garde(skip)]
+    pub tags_any: Option<String>,
+    // カンマ区切りのタグ ID。すべてが付いているもの
+    #[garde(skip)]
+    pub tags_all: Option<String>,
+    #[garde(skip)]
+    pub project_id: Option<ProjectId>,
+    // true ならどのプロジェクトにも属さないものだけ
+    #[garde(skip)]
+    pub inbox: Option<bool>,
+    // true なら未完了の blocker があるもの、false ならないものだけ
+    #[garde(skip)]
+    pub blocked: Option<bool>,
+}
+
+impl TryFrom<TodoFilterParams> for TodoFilter {
+    type Error = AppError;
+
+    fn try_from(value: TodoFilterParams) -> AppResult<Self> {
+        value.validate()?;
+        let TodoFilterParams {
+            completed,
+            overdue,
+            due_after,
+            due_before,
+            created_after,
+            created_before,
+            updated_after,
+            updated_before,
+            title,
+            tags_any,
+            tags_all,
+            project_id,
+            inbox,
+            blocked,
+        } = value;
+        let project = match (project_id, inbox.unwrap_or_default()) {
+            (Some(_), true) => {
+                return Err(AppError::ConversionEntityError(
+                    "project_id and inbox cannot be combined".into(),
+                ));
+            }
+            (Some(project_id), false) => Some(ProjectScope::Project(project_id)),
+            (None, true) => Some(ProjectScope::Inbox),
+            (None, false) => None,
+        };
+        Ok(Self {
+            completed,
+            overdue: overdue.unwrap_or_default(),
+            due: TimeRange::new(due_after, due_before)?,
+            created: TimeRange::new(created_after, created_before)?,
+            updated: TimeRange::new(updated_after, updated_before)?,
+            title,
+            tags_any: parse_tag_ids(tags_any.as_deref())?,
+            tags_all: parse_tag_ids(tags_all.as_deref())?,
+            project,
+            blocked,
+        })
+    }
+}
+
+fn parse_tag_ids(value: Option<&str>) -> AppResult<Vec<TagId>> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+#[derive(Debug, Deserialize, Validate, new)]
+pub struct TodoSearchParams {
+    #[garde(length(min = 1, max = 255))]
+    q: String,
+    #[garde(range(min = 1, max = 100))]
+    limit: Option<i64>,
+}
+
+impl TryFrom<TodoSearchParams> for TodoSearch {
+    type Error = AppError;
+
+    fn try_from(value: TodoSearchParams) -> AppResult<Self> {
+        value.validate()?;
+        TodoSearch::new(&value.q, value.limit)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoSearchHitResponse {
+    #[serde(flatten)]
+    pub todo: TodoResponse,
+    pub rank: f32,
+    // 検索語を <mark> で囲んだ HTML。それ以外の部分はエスケープ済み
+    pub snippet: String,
+}
+
+impl From<TodoSearchHit> for TodoSearchHitResponse {
+    fn from(value: TodoSearchHit) -> Self {
+        let TodoSearchHit {
+            todo,
+            rank,
+            snippet,
+        } = value;
+        Self {
+            todo: todo.into(),
+            rank,
+            snippet,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TodoSearchResponse {
+    pub items: Vec<TodoSearchHitResponse>,
+}
+
+#[derive(Serialize, Deserialize, Validate, new)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTodoRequest {
+    #[garde(length(min = 1, max = 255))]
+    title: String,
+    #[garde(skip)]
+    due_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    #[garde(skip)]
+    priority: Priority,
+    #[garde(length(max = 10000))]
+    description: Option<String>,
+    #[garde(skip)]
+    project_id: Option<ProjectId>,
+    #[garde(skip)]
+    parent_id: Option<TodoId>,
+    #[garde(skip)]
+    recurrence: Option<Recurrence>,
+}
+
+#[derive(new)]
+pub struct CreateTodoRequestWithUserId(UserId, CreateTodoRequest);
+
+impl From<CreateTodoRequestWithUserId> for CreateTodo {
+    fn from(value: CreateTodoRequestWithUserId) -> Self {
+        let CreateTodoRequestWithUserId(
+            user_id,
+            CreateTodoRequest {
+                title,
+                due_at,
+                priority,
+                description,
+                project_id,
+                parent_id,
+                recurrence,
+            },
+        ) = value;
+        Self {
+            user_id,
+            project_id,
+            parent_id,
+            title,
+            description,
+            priority,
+            due_at,
+            recurrence,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, new)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateTodoRequest {
+    #[garde(length(min = 1, max = 255))]
+    title: String,
+    #[garde(skip)]
+    due_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    #[garde(skip)]
+    priority: Priority,
+    #[garde(length(max = 10000))]
+    description: Option<String>,
+    #[garde(skip)]
+    project_id: Option<ProjectId>,
+    #[garde(skip)]
+    parent_id: Option<TodoId>,
+    #[garde(skip)]
+    recurrence: Option<Recurrence>,
+}
+
+#[derive(new)]
+pub struct UpdateTodoRequestWithIds(TodoId, UserId, UpdateTodoRequest);
+
+impl From<UpdateTodoRequestWithIds> for UpdateTodo {
+    fn from(value: UpdateTodoRequestWithIds) -> Self {
+        let UpdateTodoRequestWithIds(
+            id,
+            user_id,
+            UpdateTodoRequest {
+                title,
+                due_at,
+                priority,
+                description,
+                project_id,
+                parent_id,
+                recurrence,
+            },
+        ) = value;
+        Self {
+            id,
+            user_id,
+            project_id,
+            parent_id,
+            title,
+            description,
+            priority,
+            due_at,
+            recurrence,
+        }
+    }
+}
+
+// `POST /todos/{id}/move` の本文。after の直後、before の直前に動かす。
+// 片方だけなら今そこに隣り合っている todo との間に入れ、どちらもなければ末尾に動かす
+#[derive(Serialize, Deserialize, Validate, new)]
+pub struct MoveTodoRequest {
+    #[garde(skip)]
+    after: Option<TodoId>,
+    #[garde(skip)]
+    before: Option<TodoId>,
+}
+
+#[derive(new)]
+pub struct MoveTodoRequestWithIds(TodoId, UserId, MoveTodoRequest);
+
+impl From<MoveTodoRequestWithIds> for ReorderTodo {
+    fn from(value: MoveTodoRequestWithIds) -> Self {
+        let MoveTodoRequestWithIds(id, user_id, MoveTodoRequest { after, before }) = value;
+        Self {
+            id,
+            user_id,
+            after,
+            before,
+        }
+    }
+}
@@ -2,7 +2,11 @@ use derive_new::new;
 use garde::Validate;
 use kernel::model::{
     id::UserId,
-    user::{User, event::CreateUser},
+    list::Page,
+    user::{
+        User,
+        event::{CreateUser, UpdateUserPassword},
+    },
 };
 use serde::{Deserialize, Serialize};
 
@@ -21,8 +25,20 @@ impl From<User> for UserResponse {
 }
 
 #[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct UsersResponse {
     pub items: Vec<UserResponse>,
+    pub next_cursor: Option<String>,
+}
+
+impl From<Page<User>> for UsersResponse {
+    fn from(value: Page<User>) -> Self {
+        let Page { items, next_cursor } = value;
+        Self {
+            items: items.into_iter().map(UserResponse::from).collect(),
+            next_cursor: next_cursor.map(|cursor| cursor.encode()),
+        }
+    }
 }
 
 #[derive(Deserialize, Validate, new)]
@@ -50,3 +66,32 @@ impl From<CreateUserRequest> for CreateUser {
         }
     }
 }
+
+#[derive(Deserialize, Validate, new)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateUserPasswordRequest {
+    #[garde(length(min = 1))]
+    current_password: String,
+    #[garde(length(min = 1))]
+    new_password: String,
+}
+
+#[derive(new)]
+pub struct UpdateUserPasswordRequestWithUserId(UserId, UpdateUserPasswordRequest);
+
+impl From<UpdateUserPasswordRequestWithUserId> for UpdateUserPassword {
+    fn from(value: UpdateUserPasswordRequestWithUserId) -> Self {
+        let UpdateUserPasswordRequestWithUserId(
+            user_id,
+            UpdateUserPasswordRequest {
+                current_password,
+                new_password,
+            },
+        ) = value;
+        Self {
+            user_id,
+            current_password,
+            new_password,
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use axum::{
+    Router,
+    routing::{delete, get, post},
+};
+use registry::AppRegistry;
+
+use crate::handler::{
+    auth::{
+        auth_login, auth_logout, auth_refresh, delete_other_sessions, delete_session, list_sessions,
+    },
+    personal_access_token::{
+        delete_personal_access_token, list_personal_access_tokens, register_personal_access_token,
+    },
+};
+
+pub fn build_auth_routers() -> Router<AppRegistry> {
+    let routers = Router::new()
+        .route("/login", post(auth_login))
+        .route("/logout", post(auth_logout))
+        .route("/refresh", post(auth_refresh))
+        .route(
+            "/sessions",
+            get(list_sessions).delete(delete_other_sessions),
+        )
+        .route("/sessions/{session_id}", delete(delete_session))
+        .route(
+            "/tokens",
+            post(register_personal_access_token).get(list_personal_access_tokens),
+        )
+        .route("/tokens/{token_id}", delete(delete_personal_access_token));
+
+    Router::new().nest("/auth", routers)
+}
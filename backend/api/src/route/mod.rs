@@ -1,3 +1,7 @@
+pub mod auth;
 pub mod health;
+pub mod project;
+pub mod tag;
+pub mod todo;
 pub mod user;
 pub mod v1;
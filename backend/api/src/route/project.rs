@@ -0,0 +1,22 @@
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use registry::AppRegistry;
+
+use crate::handler::project::{
+    delete_project, list_project_todos, list_projects, register_project, show_project,
+    update_project,
+};
+
+pub fn build_project_routers() -> Router<AppRegistry> {
+    let routers = Router::new()
+        .route("/", post(register_project).get(list_projects))
+        .route(
+            "/{project_id}",
+            get(show_project).put(update_project).delete(delete_project),
+        )
+        .route("/{project_id}/todos", get(list_project_todos));
+
+    Router::new().nest("/projects", routers)
+}
@@ -0,0 +1,24 @@
+use axum::{
+    Router,
+    routing::{get, post, put},
+};
+use registry::AppRegistry;
+
+use crate::handler::tag::{
+    attach_tag, delete_tag, detach_tag, list_tags, register_tag, show_tag, update_tag,
+};
+
+pub fn build_tag_routers() -> Router<AppRegistry> {
+    let routers = Router::new()
+        .route("/", post(register_tag).get(list_tags))
+        .route(
+            "/{tag_id}",
+            get(show_tag).put(update_tag).delete(delete_tag),
+        )
+        .route(
+            "/{tag_id}/todos/{todo_id}",
+            put(attach_tag).delete(detach_tag),
+        );
+
+    Router::new().nest("/tags", routers)
+}
@@ -0,0 +1,37 @@
+use axum::{
+    Router,
+    routing::{get, post, put},
+};
+use registry::AppRegistry;
+
+use crate::handler::{
+    completion::{complete_todo, reopen_todo, show_completed_list, show_todo_history},
+    dependency::{add_blocker, remove_blocker},
+    todo::{
+        delete_todo, list_todos, move_todo, register_todo, search_todos, show_todo, update_todo,
+    },
+};
+
+pub fn build_todo_routers() -> Router<AppRegistry> {
+    let routers = Router::new()
+        .route("/", post(register_todo).get(list_todos))
+        .route("/completed", get(show_completed_list))
+        .route("/search", get(search_todos))
+        .route(
+            "/{todo_id}",
+            get(show_todo).put(update_todo).delete(delete_todo),
+        )
+        .route("/{todo_id}/move", post(move_todo))
+        .route("/{todo_id}/complete", post(complete_todo))
+        .route(
+            "/{todo_id}/complete/{completion_id}/reopen",
+            put(reopen_todo),
+        )
+        .route("/{todo_id}/history", get(show_todo_history))
+        .route(
+            "/{todo_id}/blockers/{blocker_id}",
+            put(add_blocker).delete(remove_blocker),
+        );
+
+    Router::new().nest("/todos", routers)
+}
@@ -1,9 +1,18 @@
 use axum::Router;
 use registry::AppRegistry;
 
-use crate::route::health::build_health_check_routers;
+use crate::route::{
+    auth::build_auth_routers, health::build_health_check_routers, project::build_project_routers,
+    tag::build_tag_routers, todo::build_todo_routers, user::build_user_routers,
+};
 
 pub fn routes() -> Router<AppRegistry> {
-    let routers = Router::new().merge(build_health_check_routers());
+    let routers = Router::new()
+        .merge(build_health_check_routers())
+        .merge(build_user_routers())
+        .merge(build_auth_routers())
+        .merge(build_todo_routers())
+        .merge(build_tag_routers())
+        .merge(build_project_routers());
     Router::new().nest("/api/v1", routers)
 }
@@ -1,6 +1,24 @@
-use crate::model::{auth::AccessToken, id::UserId};
+use crate::model::{
+    auth::{AccessTokenId, ClientInfo, RefreshToken},
+    id::{SessionId, UserId},
+};
 
 pub struct StoreToken {
     pub user_id: UserId,
-    pub access_token: AccessToken,
+    pub access_token_id: AccessTokenId,
+    pub refresh_token: RefreshToken,
+    pub client: ClientInfo,
+}
+
+// 使われたリフレッシュトークンを、新しいアクセストークンとリフレッシュトークンに入れ替える
+pub struct RotateToken {
+    pub refresh_token: RefreshToken,
+    pub access_token_id: AccessTokenId,
+    pub new_refresh_token: RefreshToken,
+    pub client: ClientInfo,
+}
+
+pub struct DeleteSession {
+    pub id: SessionId,
+    pub user_id: UserId,
 }
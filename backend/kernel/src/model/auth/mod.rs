@@ -1,4 +1,7 @@
-use crate::model::id::UserId;
+use crate::model::id::{SessionId, UserId};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::error::AppResult;
 
 pub mod event;
 
@@ -9,5 +12,49 @@ pub struct UserCredential {
     pub password_hash: String,
 }
 
+impl UserCredential {
+    pub fn verify_password(&self, password: &str) -> AppResult<bool> {
+        Ok(bcrypt::verify(password, &self.password_hash)?)
+    }
+}
+
+// Bearer トークンとして受け渡す署名付きの JWT。発行と検証は TokenService が行う
 #[derive(Debug, Clone, PartialEq)]
 pub struct AccessToken(pub String);
+
+// JWT の jti。Redis の許可リストに載っている間だけトークンを有効とする
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessTokenId(pub String);
+
+impl AccessTokenId {
+    pub fn generate() -> Self {
+        Self(uuid::Uuid::new_v4().simple().to_string())
+    }
+}
+
+// アクセストークンの再発行に使う。使うたびに新しいトークンと入れ替える
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefreshToken(pub String);
+
+impl RefreshToken {
+    pub fn generate() -> Self {
+        Self(uuid::Uuid::new_v4().simple().to_string())
+    }
+}
+
+// ログインしたときの端末の情報。一覧で端末を見分けるためだけに使い、認証には使わない
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+// ログインごとのセッション。リフレッシュトークンの系列に対応し、入れ替えのたびに最終利用日時を更新する
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    pub id: SessionId,
+    pub access_token_id: AccessTokenId,
+    pub client: ClientInfo,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+}
@@ -0,0 +1,16 @@
+use crate::model::id::{CompletionId, TodoId, UserId};
+
+pub struct CreateCompletion {
+    pub todo_id: TodoId,
+    pub user_id: UserId,
+    // true なら未完了の子孫もまとめて完了にする
+    pub include_subtasks: bool,
+    // true なら未完了の blocker が残っていても完了にする
+    pub force: bool,
+}
+
+pub struct UpdateReopened {
+    pub completion_id: CompletionId,
+    pub todo_id: TodoId,
+    pub user_id: UserId,
+}
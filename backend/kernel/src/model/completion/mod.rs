@@ -0,0 +1,19 @@
+use chrono::{DateTime, Utc};
+
+use crate::model::id::{CompletionId, TodoId};
+
+pub mod event;
+
+#[derive(Debug)]
+pub struct Completion {
+    pub id: CompletionId,
+    pub todo: CompletionTodo,
+    pub completed_at: DateTime<Utc>,
+    pub reopened_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub struct CompletionTodo {
+    pub id: TodoId,
+    pub title: String,
+}
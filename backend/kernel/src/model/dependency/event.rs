@@ -0,0 +1,15 @@
+use crate::model::id::{TodoId, UserId};
+
+// blocker_id の todo が todo_id の todo を妨げる、という依存を張る。
+// どちらの todo も user_id の所有でなければならない
+pub struct AddDependency {
+    pub todo_id: TodoId,
+    pub blocker_id: TodoId,
+    pub user_id: UserId,
+}
+
+pub struct RemoveDependency {
+    pub todo_id: TodoId,
+    pub blocker_id: TodoId,
+    pub user_id: UserId,
+}
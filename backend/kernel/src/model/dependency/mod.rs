@@ -0,0 +1,19 @@
+use crate::model::id::TodoId;
+
+pub mod event;
+
+// todo の依存関係。todo は blockers がすべて完了するまで完了できない
+#[derive(Debug, Default)]
+pub struct TodoDependencies {
+    // この todo を妨げている todo
+    pub blockers: Vec<DependencyTodo>,
+    // この todo に妨げられている todo
+    pub dependents: Vec<DependencyTodo>,
+}
+
+#[derive(Debug)]
+pub struct DependencyTodo {
+    pub id: TodoId,
+    pub title: String,
+    pub completed: bool,
+}
@@ -53,3 +53,9 @@ macro_rules! define_id {
 }
 
 define_id!(UserId);
+define_id!(TodoId);
+define_id!(CompletionId);
+define_id!(TagId);
+define_id!(ProjectId);
+define_id!(SessionId);
+define_id!(PersonalAccessTokenId);
@@ -0,0 +1,238 @@
+use serde::Deserialize;
+use shared::error::{AppError, AppResult};
+
+pub const DEFAULT_LIMIT: i64 = 20;
+pub const MAX_LIMIT: i64 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+// 並び替えに使える項目。カーソルには項目名を埋め込み、別の並び順のカーソルを弾く
+pub trait SortKey: Copy + Default {
+    fn as_str(&self) -> &'static str;
+
+    // order を省略したときの並び順
+    fn default_order(&self) -> SortOrder {
+        SortOrder::default()
+    }
+}
+
+// 一覧取得の条件。cursor があればその位置の次から limit 件を返す
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListQuery<S> {
+    pub limit: i64,
+    pub cursor: Option<Cursor>,
+    pub sort: S,
+    pub order: SortOrder,
+}
+
+impl<S: SortKey> Default for ListQuery<S> {
+    fn default() -> Self {
+        let sort = S::default();
+        Self {
+            limit: DEFAULT_LIMIT,
+            cursor: None,
+            sort,
+            order: sort.default_order(),
+        }
+    }
+}
+
+impl<S: SortKey> ListQuery<S> {
+    pub fn new(
+        limit: Option<i64>,
+        cursor: Option<&str>,
+        sort: Option<S>,
+        order: Option<SortOrder>,
+    ) -> AppResult<Self> {
+        let sort = sort.unwrap_or_default();
+        let cursor = cursor.map(Cursor::decode).transpose()?;
+        if let Some(cursor) = &cursor {
+            if cursor.sort != sort.as_str() {
+                return Err(AppError::ConversionEntityError(
+                    "The cursor does not match the sort key".into(),
+                ));
+            }
+        }
+        Ok(Self {
+            limit: limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+            cursor,
+            sort,
+            order: order.unwrap_or_else(|| sort.default_order()),
+        })
+    }
+
+    // 次ページの有無を判定するため 1 件多く取得する
+    pub fn fetch_limit(&self) -> i64 {
+        self.limit + 1
+    }
+}
+
+// キーセットページネーションの位置。最後に返した行の並び替え値と ID を持つ
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cursor {
+    pub sort: String,
+    pub key: String,
+    pub id: uuid::Uuid,
+}
+
+impl Cursor {
+    pub fn new(sort: impl SortKey, key: String, id: uuid::Uuid) -> Self {
+        Self {
+            sort: sort.as_str().to_string(),
+            key,
+            id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        format!("{}\n{}\n{}", self.sort, self.id.simple(), self.key)
+            .bytes()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+
+    pub fn decode(value: &str) -> AppResult<Self> {
+        let invalid = || AppError::ConversionEntityError("Invalid cursor".into());
+
+        if value.len() % 2 != 0 || !value.is_ascii() {
+            return Err(invalid());
+        }
+        let bytes = (0..value.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&value[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| invalid())?;
+        let text = String::from_utf8(bytes).map_err(|_| invalid())?;
+
+        let mut parts = text.splitn(3, '\n');
+        let (Some(sort), Some(id), Some(key)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(invalid());
+        };
+        Ok(Self {
+            sort: sort.to_string(),
+            key: key.to_string(),
+            id: uuid::Uuid::parse_str(id).map_err(|_| invalid())?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<Cursor>,
+}
+
+impl<T> Page<T> {
+    // fetch_limit 件取得した結果から、limit 件のページと次ページのカーソルを作る
+    pub fn from_rows<R>(
+        mut rows: Vec<R>,
+        limit: i64,
+        cursor_of: impl Fn(&R) -> Cursor,
+        into_item: impl Fn(R) -> AppResult<T>,
+    ) -> AppResult<Self> {
+        let has_next = rows.len() as i64 > limit;
+        rows.truncate(limit as usize);
+        let next_cursor = if has_next {
+            rows.last().map(cursor_of)
+        } else {
+            None
+        };
+        let items = rows.into_iter().map(into_item).collect::<AppResult<_>>()?;
+        Ok(Self { items, next_cursor })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Default)]
+    enum TestSort {
+        #[default]
+        CreatedAt,
+        Title,
+    }
+
+    impl SortKey for TestSort {
+        fn as_str(&self) -> &'static str {
+            match self {
+                TestSort::CreatedAt => "created_at",
+                TestSort::Title => "title",
+            }
+        }
+    }
+
+    #[test]
+    fn カーソルはエンコードして元に戻せる() {
+        let cursor = Cursor::new(
+            TestSort::Title,
+            "牛乳\nを買う".to_string(),
+            uuid::Uuid::new_v4(),
+        );
+
+        let decoded = Cursor::decode(&cursor.encode()).expect("デコードが成功する");
+
+        assert_eq!(decoded, cursor);
+    }
+
+    #[test]
+    fn 不正なカーソルは失敗する() {
+        for value in ["zz", "abc", "6869"] {
+            let err = Cursor::decode(value).expect_err("不正なカーソルは失敗する");
+            assert!(matches!(err, AppError::ConversionEntityError(_)));
+        }
+    }
+
+    #[test]
+    fn 並び替え項目が異なるカーソルは失敗する() {
+        let cursor = Cursor::new(TestSort::Title, "a".to_string(), uuid::Uuid::new_v4());
+
+        let err = ListQuery::new(
+            None,
+            Some(&cursor.encode()),
+            Some(TestSort::CreatedAt),
+            None,
+        )
+        .expect_err("並び替え項目の不一致は失敗する");
+
+        assert!(matches!(err, AppError::ConversionEntityError(_)));
+    }
+
+    #[test]
+    fn 件数は上限と下限に丸める() {
+        let query = ListQuery::<TestSort>::new(Some(1000), None, None, None).expect("成功する");
+        assert_eq!(query.limit, MAX_LIMIT);
+
+        let query = ListQuery::<TestSort>::new(Some(0), None, None, None).expect("成功する");
+        assert_eq!(query.limit, 1);
+    }
+
+    #[test]
+    fn 多めに取得した行から次ページのカーソルを作る() {
+        let page = Page::from_rows(
+            vec![1, 2, 3],
+            2,
+            |n| Cursor::new(TestSort::CreatedAt, n.to_string(), uuid::Uuid::nil()),
+            Ok,
+        )
+        .expect("成功する");
+
+        assert_eq!(page.items, vec![1, 2]);
+        assert_eq!(page.next_cursor.expect("次ページがある").key, "2");
+
+        let page = Page::from_rows(
+            vec![1, 2],
+            2,
+            |n| Cursor::new(TestSort::CreatedAt, n.to_string(), uuid::Uuid::nil()),
+            Ok,
+        )
+        .expect("成功する");
+        assert!(page.next_cursor.is_none());
+    }
+}
@@ -1,3 +1,10 @@
 pub mod auth;
+pub mod completion;
+pub mod dependency;
 pub mod id;
+pub mod list;
+pub mod personal_access_token;
+pub mod project;
+pub mod tag;
+pub mod todo;
 pub mod user;
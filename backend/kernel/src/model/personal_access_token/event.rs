@@ -0,0 +1,19 @@
+use chrono::{DateTime, Utc};
+
+use crate::model::{
+    id::{PersonalAccessTokenId, UserId},
+    personal_access_token::{PersonalAccessTokenSecret, TokenScope},
+};
+
+pub struct CreatePersonalAccessToken {
+    pub user_id: UserId,
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub secret: PersonalAccessTokenSecret,
+}
+
+pub struct DeletePersonalAccessToken {
+    pub id: PersonalAccessTokenId,
+    pub user_id: UserId,
+}
@@ -0,0 +1,133 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::error::AppError;
+use std::str::FromStr;
+
+use crate::model::id::{PersonalAccessTokenId, UserId};
+
+pub mod event;
+
+// スクリプトなどの自動化に使う、ログインのセッションとは別に発行する長期間有効な API キー
+#[derive(Debug, Clone)]
+pub struct PersonalAccessToken {
+    pub id: PersonalAccessTokenId,
+    pub user_id: UserId,
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    // None なら失効させるまで使える
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PersonalAccessToken {
+    pub fn allows(&self, required: TokenScope) -> bool {
+        self.scopes.iter().any(|scope| scope.allows(required))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TokenScope {
+    #[serde(rename = "todos:read")]
+    TodosRead,
+    #[serde(rename = "todos:write")]
+    TodosWrite,
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl TokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::TodosRead => "todos:read",
+            TokenScope::TodosWrite => "todos:write",
+            TokenScope::Admin => "admin",
+        }
+    }
+
+    // admin はすべての操作を、todos:write は todo の参照も許す
+    pub fn allows(&self, required: TokenScope) -> bool {
+        match self {
+            TokenScope::Admin => true,
+            TokenScope::TodosWrite => required != TokenScope::Admin,
+            TokenScope::TodosRead => required == TokenScope::TodosRead,
+        }
+    }
+}
+
+impl FromStr for TokenScope {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "todos:read" => Ok(TokenScope::TodosRead),
+            "todos:write" => Ok(TokenScope::TodosWrite),
+            "admin" => Ok(TokenScope::Admin),
+            _ => Err(AppError::ConversionEntityError(format!(
+                "Unknown token scope: {s}"
+            ))),
+        }
+    }
+}
+
+// 平文のシークレット。作成したときに一度だけ返し、保存するのはダイジェストだけにする
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersonalAccessTokenSecret(pub String);
+
+impl PersonalAccessTokenSecret {
+    // Bearer トークンが JWT かパーソナルアクセストークンかを見分けるための接頭辞
+    pub const PREFIX: &'static str = "rtpat_";
+
+    pub fn generate() -> Self {
+        Self(format!(
+            "{}{}{}",
+            Self::PREFIX,
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        ))
+    }
+
+    pub fn parse(token: &str) -> Option<Self> {
+        token
+            .starts_with(Self::PREFIX)
+            .then(|| Self(token.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn スコープは上位のスコープで代用できる() {
+        assert!(TokenScope::Admin.allows(TokenScope::TodosWrite));
+        assert!(TokenScope::TodosWrite.allows(TokenScope::TodosRead));
+        assert!(!TokenScope::TodosWrite.allows(TokenScope::Admin));
+        assert!(!TokenScope::TodosRead.allows(TokenScope::TodosWrite));
+    }
+
+    #[test]
+    fn スコープは文字列と相互に変換できる() {
+        for scope in [
+            TokenScope::TodosRead,
+            TokenScope::TodosWrite,
+            TokenScope::Admin,
+        ] {
+            assert_eq!(TokenScope::from_str(scope.as_str()).expect("変換"), scope);
+        }
+        assert!(TokenScope::from_str("todos:delete").is_err());
+    }
+
+    #[test]
+    fn 接頭辞でパーソナルアクセストークンを見分ける() {
+        let secret = PersonalAccessTokenSecret::generate();
+        assert_eq!(
+            PersonalAccessTokenSecret::parse(&secret.0),
+            Some(secret.clone())
+        );
+        assert_eq!(
+            PersonalAccessTokenSecret::parse("eyJhbGciOiJIUzI1NiJ9"),
+            None
+        );
+    }
+}
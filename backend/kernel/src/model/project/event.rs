@@ -0,0 +1,34 @@
+use serde::Deserialize;
+
+use crate::model::id::{ProjectId, UserId};
+
+pub struct CreateProject {
+    pub user_id: UserId,
+    pub name: String,
+    pub colour: String,
+}
+
+pub struct UpdateProject {
+    pub id: ProjectId,
+    pub user_id: UserId,
+    pub name: String,
+    pub colour: String,
+    pub archived: bool,
+}
+
+pub struct DeleteProject {
+    pub id: ProjectId,
+    pub user_id: UserId,
+    pub mode: DeleteProjectMode,
+}
+
+// プロジェクトを削除するときに、属している todo をどう扱うか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeleteProjectMode {
+    // todo もまとめて削除する
+    Cascade,
+    // todo は残し、どのプロジェクトにも属さない受信箱に移す
+    #[default]
+    MoveToInbox,
+}
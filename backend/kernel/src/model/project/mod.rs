@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+
+use crate::model::id::{ProjectId, UserId};
+
+pub mod event;
+
+// 色を指定せずに作成したプロジェクトの色
+pub const DEFAULT_COLOUR: &str = "#808080";
+
+#[derive(Debug)]
+pub struct Project {
+    pub id: ProjectId,
+    pub user_id: UserId,
+    pub name: String,
+    // `#rrggbb` 形式の表示色
+    pub colour: String,
+    pub archived: bool,
+    pub progress: ProjectProgress,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// プロジェクトに属する todo の件数と、そのうち完了したものの件数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProjectProgress {
+    pub total: i64,
+    pub completed: i64,
+}
+
+// `#` に続く 16 進 6 桁だけを色として受け付ける
+pub fn is_valid_colour(value: &str) -> bool {
+    value
+        .strip_prefix('#')
+        .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn 色はシャープと16進6桁だけを受け付ける() {
+        assert!(is_valid_colour("#1a2B3c"));
+        assert!(is_valid_colour(DEFAULT_COLOUR));
+        assert!(!is_valid_colour("1a2b3c"));
+        assert!(!is_valid_colour("#1a2b3"));
+        assert!(!is_valid_colour("#1a2b3g"));
+        assert!(!is_valid_colour("#ａ1a2b3"));
+    }
+}
@@ -0,0 +1,30 @@
+use crate::model::id::{TagId, TodoId, UserId};
+
+pub struct CreateTag {
+    pub user_id: UserId,
+    pub name: String,
+}
+
+pub struct UpdateTag {
+    pub id: TagId,
+    pub user_id: UserId,
+    pub name: String,
+}
+
+pub struct DeleteTag {
+    pub id: TagId,
+    pub user_id: UserId,
+}
+
+// todo へのタグ付け。todo とタグはどちらも user_id の所有でなければならない
+pub struct AttachTag {
+    pub tag_id: TagId,
+    pub todo_id: TodoId,
+    pub user_id: UserId,
+}
+
+pub struct DetachTag {
+    pub tag_id: TagId,
+    pub todo_id: TodoId,
+    pub user_id: UserId,
+}
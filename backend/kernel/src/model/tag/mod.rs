@@ -0,0 +1,14 @@
+use chrono::{DateTime, Utc};
+
+use crate::model::id::{TagId, UserId};
+
+pub mod event;
+
+#[derive(Debug)]
+pub struct Tag {
+    pub id: TagId,
+    pub user_id: UserId,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
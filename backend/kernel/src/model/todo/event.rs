@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+
+use super::{Priority, recurrence::Recurrence};
+use crate::model::id::{ProjectId, TodoId, UserId};
+
+pub struct CreateTodo {
+    pub user_id: UserId,
+    pub project_id: Option<ProjectId>,
+    pub parent_id: Option<TodoId>,
+    pub title: String,
+    pub description: Option<String>,
+    pub priority: Priority,
+    pub due_at: Option<DateTime<Utc>>,
+    pub recurrence: Option<Recurrence>,
+}
+
+pub struct UpdateTodo {
+    pub id: TodoId,
+    pub user_id: UserId,
+    pub project_id: Option<ProjectId>,
+    pub parent_id: Option<TodoId>,
+    pub title: String,
+    pub description: Option<String>,
+    pub priority: Priority,
+    pub due_at: Option<DateTime<Utc>>,
+    pub recurrence: Option<Recurrence>,
+}
+
+// after の直後、before の直前に動かす。片方だけなら隣の todo との間に入れ、
+// どちらもなければ末尾に動かす
+pub struct ReorderTodo {
+    pub id: TodoId,
+    pub user_id: UserId,
+    pub after: Option<TodoId>,
+    pub before: Option<TodoId>,
+}
+
+pub struct DeleteTodo {
+    pub id: TodoId,
+    pub user_id: UserId,
+}
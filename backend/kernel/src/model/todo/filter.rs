@@ -0,0 +1,272 @@
+use chrono::{DateTime, Utc};
+use shared::error::{AppError, AppResult};
+
+use super::Todo;
+use crate::model::id::{ProjectId, TagId};
+
+// `after <= 値 < before` の半開区間。どちらかを省略すると片側だけで絞り込む
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TimeRange {
+    pub after: Option<DateTime<Utc>>,
+    pub before: Option<DateTime<Utc>>,
+}
+
+impl TimeRange {
+    pub fn new(after: Option<DateTime<Utc>>, before: Option<DateTime<Utc>>) -> AppResult<Self> {
+        if let (Some(after), Some(before)) = (after, before) {
+            if after >= before {
+                return Err(AppError::ConversionEntityError(
+                    "The range start must be earlier than its end".into(),
+                ));
+            }
+        }
+        Ok(Self { after, before })
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.after.is_none() && self.before.is_none()
+    }
+
+    pub fn contains(&self, value: DateTime<Utc>) -> bool {
+        self.after.is_none_or(|after| after <= value)
+            && self.before.is_none_or(|before| value < before)
+    }
+}
+
+// 所属プロジェクトでの絞り込み
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectScope {
+    // どのプロジェクトにも属さないもの
+    Inbox,
+    Project(ProjectId),
+}
+
+// todo 一覧の絞り込み条件。指定した条件はすべて AND で結合する
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TodoFilter {
+    pub completed: Option<bool>,
+    // 未完了かつ期限切れのものだけに絞る
+    pub overdue: bool,
+    pub due: TimeRange,
+    pub created: TimeRange,
+    pub updated: TimeRange,
+    // タイトルの部分一致（大文字小文字を区別しない）
+    pub title: Option<String>,
+    // いずれかのタグが付いているもの
+    pub tags_any: Vec<TagId>,
+    // すべてのタグが付いているもの
+    pub tags_all: Vec<TagId>,
+    pub project: Option<ProjectScope>,
+    // 未完了の blocker があるものか、ないもの
+    pub blocked: Option<bool>,
+}
+
+impl TodoFilter {
+    // DB を介さない実装向けに、SQL と同じ条件をメモリ上で評価する
+    pub fn matches(
+        &self,
+        todo: &Todo,
+        tag_ids: &[TagId],
+        blocked: bool,
+        now: DateTime<Utc>,
+    ) -> bool {
+        if self
+            .completed
+            .is_some_and(|completed| completed != todo.completed)
+        {
+            return false;
+        }
+        if self.overdue && (todo.completed || todo.due_at.is_none_or(|due_at| due_at >= now)) {
+            return false;
+        }
+        if !self.due.is_unbounded() && todo.due_at.is_none_or(|due_at| !self.due.contains(due_at)) {
+            return false;
+        }
+        if !self.created.contains(todo.created_at) || !self.updated.contains(todo.updated_at) {
+            return false;
+        }
+        if !self.tags_any.is_empty() && !self.tags_any.iter().any(|id| tag_ids.contains(id)) {
+            return false;
+        }
+        if !self.tags_all.iter().all(|id| tag_ids.contains(id)) {
+            return false;
+        }
+        if self.blocked.is_some_and(|expected| expected != blocked) {
+            return false;
+        }
+        match self.project {
+            Some(ProjectScope::Inbox) if todo.project_id.is_some() => return false,
+            Some(ProjectScope::Project(id)) if todo.project_id != Some(id) => return false,
+            _ => {}
+        }
+        match &self.title {
+            Some(title) => todo.title.to_lowercase().contains(&title.to_lowercase()),
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        id::{TodoId, UserId},
+        todo::position::Position,
+    };
+    use chrono::Duration;
+
+    fn todo(title: &str, completed: bool, due_at: Option<DateTime<Utc>>) -> Todo {
+        let now = Utc::now();
+        Todo {
+            id: TodoId::new(),
+            user_id: UserId::new(),
+            project_id: None,
+            parent_id: None,
+            title: title.to_string(),
+            description: None,
+            priority: Default::default(),
+            completed,
+            due_at,
+            recurrence: None,
+            position: Position::first(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn 期間は開始が終了より前でなければ失敗する() {
+        let now = Utc::now();
+
+        assert!(TimeRange::new(Some(now), Some(now)).is_err());
+        assert!(TimeRange::new(Some(now + Duration::hours(1)), Some(now)).is_err());
+        assert!(TimeRange::new(Some(now), None).is_ok());
+    }
+
+    #[test]
+    fn 期間は開始を含み終了を含まない() {
+        let now = Utc::now();
+        let range = TimeRange::new(Some(now), Some(now + Duration::hours(1))).expect("正しい期間");
+
+        assert!(range.contains(now));
+        assert!(!range.contains(now + Duration::hours(1)));
+        assert!(!range.contains(now - Duration::seconds(1)));
+    }
+
+    #[test]
+    fn 期限切れは未完了かつ期限を過ぎたものだけに一致する() {
+        let now = Utc::now();
+        let filter = TodoFilter {
+            overdue: true,
+            ..Default::default()
+        };
+
+        assert!(filter.matches(
+            &todo("a", false, Some(now - Duration::hours(1))),
+            &[],
+            false,
+            now
+        ));
+        assert!(!filter.matches(
+            &todo("a", true, Some(now - Duration::hours(1))),
+            &[],
+            false,
+            now
+        ));
+        assert!(!filter.matches(
+            &todo("a", false, Some(now + Duration::hours(1))),
+            &[],
+            false,
+            now
+        ));
+        assert!(!filter.matches(&todo("a", false, None), &[], false, now));
+    }
+
+    #[test]
+    fn 期限の範囲指定は期限なしのtodoに一致しない() {
+        let now = Utc::now();
+        let filter = TodoFilter {
+            due: TimeRange::new(None, Some(now)).expect("正しい期間"),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(
+            &todo("a", false, Some(now - Duration::hours(1))),
+            &[],
+            false,
+            now
+        ));
+        assert!(!filter.matches(&todo("a", false, None), &[], false, now));
+    }
+
+    #[test]
+    fn タグはいずれかとすべてで絞り込める() {
+        let now = Utc::now();
+        let (bug, review, docs) = (TagId::new(), TagId::new(), TagId::new());
+        let target = todo("a", false, None);
+
+        let any = TodoFilter {
+            tags_any: vec![bug, docs],
+            ..Default::default()
+        };
+        assert!(any.matches(&target, &[bug, review], false, now));
+        assert!(!any.matches(&target, &[review], false, now));
+
+        let all = TodoFilter {
+            tags_all: vec![bug, review],
+            ..Default::default()
+        };
+        assert!(all.matches(&target, &[bug, review, docs], false, now));
+        assert!(!all.matches(&target, &[bug], false, now));
+    }
+
+    #[test]
+    fn プロジェクトと受信箱で絞り込める() {
+        let now = Utc::now();
+        let project_id = ProjectId::new();
+        let inbox = todo("a", false, None);
+        let mut in_project = todo("b", false, None);
+        in_project.project_id = Some(project_id);
+
+        let filter = TodoFilter {
+            project: Some(ProjectScope::Inbox),
+            ..Default::default()
+        };
+        assert!(filter.matches(&inbox, &[], false, now));
+        assert!(!filter.matches(&in_project, &[], false, now));
+
+        let filter = TodoFilter {
+            project: Some(ProjectScope::Project(project_id)),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&inbox, &[], false, now));
+        assert!(filter.matches(&in_project, &[], false, now));
+    }
+
+    #[test]
+    fn 未完了のblockerの有無で絞り込める() {
+        let now = Utc::now();
+        let target = todo("a", false, None);
+        let filter = TodoFilter {
+            blocked: Some(false),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&target, &[], false, now));
+        assert!(!filter.matches(&target, &[], true, now));
+    }
+
+    #[test]
+    fn タイトルは大文字小文字を区別せず部分一致する() {
+        let now = Utc::now();
+        let filter = TodoFilter {
+            title: Some("milk".to_string()),
+            completed: Some(false),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&todo("Buy MILK", false, None), &[], false, now));
+        assert!(!filter.matches(&todo("Buy MILK", true, None), &[], false, now));
+        assert!(!filter.matches(&todo("Buy eggs", false, None), &[], false, now));
+    }
+}
@@ -0,0 +1,120 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::error::AppError;
+
+use self::{position::Position, recurrence::Recurrence};
+use crate::model::{
+    id::{ProjectId, TodoId, UserId},
+    list::{SortKey, SortOrder},
+};
+
+pub mod event;
+pub mod filter;
+pub mod position;
+pub mod recurrence;
+pub mod search;
+pub mod tree;
+
+#[derive(Debug)]
+pub struct Todo {
+    pub id: TodoId,
+    pub user_id: UserId,
+    // 属するプロジェクト。None なら受信箱
+    pub project_id: Option<ProjectId>,
+    // 親の todo。None なら最上位
+    pub parent_id: Option<TodoId>,
+    pub title: String,
+    // Markdown で書かれた補足説明
+    pub description: Option<String>,
+    pub priority: Priority,
+    pub completed: bool,
+    pub due_at: Option<DateTime<Utc>>,
+    // 繰り返しの規則。完了すると次の回を新しい todo として作る
+    pub recurrence: Option<Recurrence>,
+    // 手動の並び順でのキー
+    pub position: Position,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// 重要度。DB では todo_priority 列挙型として保存し、定義順に大小比較できる
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize, sqlx::Type,
+)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "todo_priority", rename_all = "lowercase")]
+pub enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
+impl Priority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Priority::None => "none",
+            Priority::Low => "low",
+            Priority::Medium => "medium",
+            Priority::High => "high",
+            Priority::Urgent => "urgent",
+        }
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Priority {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Priority::None),
+            "low" => Ok(Priority::Low),
+            "medium" => Ok(Priority::Medium),
+            "high" => Ok(Priority::High),
+            "urgent" => Ok(Priority::Urgent),
+            _ => Err(AppError::ConversionEntityError(format!(
+                "Unknown priority: {s}"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoSort {
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+    Title,
+    // 利用者が並べ替えた順
+    Manual,
+}
+
+impl SortKey for TodoSort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TodoSort::CreatedAt => "created_at",
+            TodoSort::UpdatedAt => "updated_at",
+            TodoSort::Title => "title",
+            TodoSort::Manual => "manual",
+        }
+    }
+
+    // 手動の並び順は上から下へ並べるのが自然なので昇順にする
+    fn default_order(&self) -> SortOrder {
+        match self {
+            TodoSort::Manual => SortOrder::Asc,
+            _ => SortOrder::default(),
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use shared::error::{AppError, AppResult};
+
+// 0-9A-Za-z の 62 進数。ASCII 順に並べてあるので、キーはバイト列の辞書順で比較できる
+const DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+// 整数部として表せる最小の値。これより前にはキーを作れないため、キーとしては使わない
+const SMALLEST_INTEGER: &str = "A00000000000000000000000000";
+
+// 手動の並び順でのキー（fractional indexing）。2 つのキーの間には常に別のキーを作れるため、
+// 並べ替えでは動かした todo のキーだけを書き換えればよい。
+// キーは整数部と小数部からなり、整数部の先頭文字がその桁数を表す（a-z は正、A-Z は負）。
+// 末尾に追加し続けても整数部を繰り上げるだけなので、キーは対数的にしか伸びない
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Position(String);
+
+impl Position {
+    pub fn first() -> Self {
+        Self("a0".to_string())
+    }
+
+    // after と before の間に入るキー。None の側は端までを表す
+    pub fn between(after: Option<&Position>, before: Option<&Position>) -> AppResult<Self> {
+        let key = match (after, before) {
+            (None, None) => return Ok(Self::first()),
+            (None, Some(before)) => {
+                let (integer, fraction) = before.split();
+                if integer == SMALLEST_INTEGER.as_bytes() {
+                    [integer, &midpoint(b"", Some(fraction))].concat()
+                } else if !fraction.is_empty() {
+                    integer.to_vec()
+                } else {
+                    decrement_integer(integer).ok_or_else(exhausted)?
+                }
+            }
+            (Some(after), None) => {
+                let (integer, fraction) = after.split();
+                increment_integer(integer)
+                    .unwrap_or_else(|| [integer, &midpoint(fraction, None)].concat())
+            }
+            (Some(after), Some(before)) => {
+                if after >= before {
+                    return Err(AppError::UnprocessableEntity(
+                        "The preceding todo must be placed before the following todo".into(),
+                    ));
+                }
+                let (after_integer, after_fraction) = after.split();
+                let (before_integer, before_fraction) = before.split();
+                if after_integer == before_integer {
+                    [
+                        after_integer,
+                        &midpoint(after_fraction, Some(before_fraction)),
+                    ]
+                    .concat()
+                } else {
+                    let next = increment_integer(after_integer).ok_or_else(exhausted)?;
+                    if next.as_slice() < before.0.as_bytes() {
+                        next
+                    } else {
+                        [after_integer, &midpoint(after_fraction, None)].concat()
+                    }
+                }
+            }
+        };
+        // DIGITS の文字だけを組み合わせているので UTF-8 として正しい
+        Ok(Self(String::from_utf8(key).map_err(|_| exhausted())?))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn split(&self) -> (&[u8], &[u8]) {
+        let bytes = self.0.as_bytes();
+        let length = integer_length(bytes[0]).unwrap_or(bytes.len());
+        bytes.split_at(length)
+    }
+}
+
+fn exhausted() -> AppError {
+    AppError::UnprocessableEntity("No position is left at the edge of the list".into())
+}
+
+fn digit(c: u8) -> usize {
+    DIGITS.iter().position(|d| *d == c).unwrap_or_default()
+}
+
+fn integer_length(head: u8) -> Option<usize> {
+    match head {
+        b'a'..=b'z' => Some((head - b'a') as usize + 2),
+        b'A'..=b'Z' => Some((b'Z' - head) as usize + 2),
+        _ => None,
+    }
+}
+
+// a < b を満たす小数部どうしの中間。b が None なら 1 との中間を返す
+fn midpoint(a: &[u8], b: Option<&[u8]>) -> Vec<u8> {
+    if let Some(b) = b {
+        // 共通の先頭部分はそのまま残し、その後ろで中間を取る（a の足りない桁は 0 とみなす）
+        let common = b
+            .iter()
+            .enumerate()
+            .take_while(|(i, c)| a.get(*i).copied().unwrap_or(b'0') == **c)
+            .count();
+        if common > 0 {
+            let rest = a.get(common..).unwrap_or_default();
+            return [&b[..common], &midpoint(rest, Some(&b[common..]))].concat();
+        }
+    }
+    let digit_a = a.first().map_or(0, |c| digit(*c));
+    let digit_b = b.map_or(DIGITS.len(), |b| digit(b[0]));
+    if digit_b - digit_a > 1 {
+        vec![DIGITS[(digit_a + digit_b).div_ceil(2)]]
+    } else if let Some(b) = b.filter(|b| b.len() > 1) {
+        vec![b[0]]
+    } else {
+        let rest = a.get(1..).unwrap_or_default();
+        [&[DIGITS[digit_a]][..], &midpoint(rest, None)].concat()
+    }
+}
+
+fn increment_integer(integer: &[u8]) -> Option<Vec<u8>> {
+    let head = integer[0];
+    let mut digits = integer[1..].to_vec();
+    let mut carry = true;
+    for d in digits.iter_mut().rev() {
+        let next = digit(*d) + 1;
+        if next == DIGITS.len() {
+            *d = b'0';
+        } else {
+            *d = DIGITS[next];
+            carry = false;
+            break;
+        }
+    }
+    if !carry {
+        return Some([&[head][..], &digits].concat());
+    }
+    // 桁があふれたら先頭文字を進めて桁数を変える
+    match head {
+        b'Z' => Some(b"a0".to_vec()),
+        b'z' => None,
+        _ => {
+            let head = head + 1;
+            if head > b'a' {
+                digits.push(b'0');
+            } else {
+                digits.pop();
+            }
+            Some([&[head][..], &digits].concat())
+        }
+    }
+}
+
+fn decrement_integer(integer: &[u8]) -> Option<Vec<u8>> {
+    let head = integer[0];
+    let mut digits = integer[1..].to_vec();
+    let mut borrow = true;
+    for d in digits.iter_mut().rev() {
+        let current = digit(*d);
+        if current == 0 {
+            *d = b'z';
+        } else {
+            *d = DIGITS[current - 1];
+            borrow = false;
+            break;
+        }
+    }
+    if !borrow {
+        return Some([&[head][..], &digits].concat());
+    }
+    match head {
+        b'a' => Some(b"Zz".to_vec()),
+        b'A' => None,
+        _ => {
+            let head = head - 1;
+            if head < b'Z' {
+                digits.push(b'z');
+            } else {
+                digits.pop();
+            }
+            Some([&[head][..], &digits].concat())
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Position {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || AppError::ConversionEntityError(format!("Invalid position: {s}"));
+        let bytes = s.as_bytes();
+        let length = bytes
+            .first()
+            .and_then(|head| integer_length(*head))
+            .ok_or_else(invalid)?;
+        // 小数部の末尾の 0 は間にキーを作れなくなるので許さない
+        if bytes.len() < length
+            || s == SMALLEST_INTEGER
+            || bytes[length..].last() == Some(&b'0')
+            || !bytes[1..].iter().all(|c| DIGITS.contains(c))
+        {
+            return Err(invalid());
+        }
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl TryFrom<String> for Position {
+    type Error = AppError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Position> for String {
+    fn from(value: Position) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(s: &str) -> Position {
+        s.parse().expect("正しいキー")
+    }
+
+    #[test]
+    fn 間のキーは前後のキーの間に並ぶ() {
+        let cases = [
+            (None, None, "a0"),
+            (Some("a0"), None, "a1"),
+            (Some("az"), None, "b00"),
+            (None, Some("a0"), "Zz"),
+            (None, Some("a0V"), "a0"),
+            (Some("a0"), Some("a1"), "a0V"),
+            (Some("a0"), Some("a0V"), "a0G"),
+            (Some("a1"), Some("a2"), "a1V"),
+            (Some("Zz"), Some("a01"), "a0"),
+            (Some("a0z"), Some("a1"), "a0zV"),
+        ];
+        for (after, before, expected) in cases {
+            let after = after.map(position);
+            let before = before.map(position);
+            let key = Position::between(after.as_ref(), before.as_ref()).expect("キーを作れる");
+
+            assert_eq!(key.as_str(), expected);
+            assert!(after.is_none_or(|after| after < key));
+            assert!(before.is_none_or(|before| key < before));
+        }
+    }
+
+    #[test]
+    fn 同じ場所に挿入し続けても順序を保つ() {
+        let low = Position::first();
+        let mut high = Position::between(Some(&low), None).expect("キーを作れる");
+        for _ in 0..200 {
+            let mid = Position::between(Some(&low), Some(&high)).expect("キーを作れる");
+            assert!(low < mid && mid < high);
+            high = mid;
+        }
+    }
+
+    #[test]
+    fn 末尾への追加ではキーがほとんど伸びない() {
+        let mut last = Position::first();
+        for _ in 0..10_000 {
+            let next = Position::between(Some(&last), None).expect("キーを作れる");
+            assert!(last < next);
+            last = next;
+        }
+
+        assert!(last.as_str().len() <= 4, "{last}");
+    }
+
+    #[test]
+    fn 順序が逆の前後は拒否する() {
+        let err = Position::between(Some(&position("a1")), Some(&position("a0")))
+            .expect_err("逆順は失敗する");
+
+        assert!(matches!(err, AppError::UnprocessableEntity(_)));
+    }
+
+    #[test]
+    fn 不正なキーは読み込めない() {
+        for value in ["", "a", "b0", "a00", "a0!", "0a", SMALLEST_INTEGER] {
+            assert!(value.parse::<Position>().is_err(), "{value}");
+        }
+    }
+}
@@ -0,0 +1,441 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, NaiveDateTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use shared::error::{AppError, AppResult};
+
+// 次の回を探すときに調べる周期数の上限。該当する日がない規則で探し続けないようにする
+const MAX_PERIODS: u32 = 1000;
+
+// RFC 5545 の RRULE のうち FREQ（DAILY / WEEKLY / MONTHLY）, INTERVAL, BYDAY, UNTIL, COUNT だけを扱う。
+// 週の始まりは月曜日（WKST=MO）とし、次の回は元の回の時刻を引き継ぐ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    pub interval: u32,
+    // 空なら元の回と同じ曜日・日付で繰り返す
+    pub by_day: Vec<ByDay>,
+    // この日時までの回だけを作る
+    pub until: Option<DateTime<Utc>>,
+    // 今の回を含めた残りの回数。次の回を作るたびに 1 減らす
+    pub count: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+// BYDAY の 1 要素。ordinal は MONTHLY でだけ使い、1 なら第 1、-1 なら最終の曜日を表す
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByDay {
+    pub ordinal: Option<i8>,
+    pub weekday: Weekday,
+}
+
+impl Recurrence {
+    // current の次の回の日時と、その回に引き継ぐ規則を返す。回数か終了日時を使い切っていれば None
+    pub fn next(&self, current: DateTime<Utc>) -> Option<(DateTime<Utc>, Recurrence)> {
+        if self.count.is_some_and(|count| count <= 1) {
+            return None;
+        }
+        let next = self
+            .next_date(current.date_naive())?
+            .and_time(current.time())
+            .and_utc();
+        if self.until.is_some_and(|until| next > until) {
+            return None;
+        }
+        let rule = Recurrence {
+            count: self.count.map(|count| count - 1),
+            ..self.clone()
+        };
+        Some((next, rule))
+    }
+
+    // 完了した回の次の回。期限のない回は完了した日時を起点にする
+    pub fn next_occurrence(
+        &self,
+        due_at: Option<DateTime<Utc>>,
+        completed_at: DateTime<Utc>,
+    ) -> Option<(DateTime<Utc>, Recurrence)> {
+        self.next(due_at.unwrap_or(completed_at))
+    }
+
+    fn next_date(&self, current: NaiveDate) -> Option<NaiveDate> {
+        match self.frequency {
+            Frequency::Daily => (1..=MAX_PERIODS)
+                .filter_map(|n| current.checked_add_days(self.periods(n, 1)))
+                .find(|date| {
+                    self.by_day.is_empty()
+                        || self.by_day.iter().any(|d| d.weekday == date.weekday())
+                }),
+            Frequency::Weekly => {
+                let week_start = current.week(Weekday::Mon).first_day();
+                (0..=MAX_PERIODS).find_map(|n| {
+                    let start = week_start.checked_add_days(self.periods(n, 7))?;
+                    self.weekdays(current.weekday())
+                        .filter_map(|weekday| {
+                            start.checked_add_days(Days::new(weekday.num_days_from_monday().into()))
+                        })
+                        .filter(|date| *date > current)
+                        .min()
+                })
+            }
+            Frequency::Monthly => {
+                let month_start = current.with_day(1)?;
+                (0..=MAX_PERIODS).find_map(|n| {
+                    let start = month_start
+                        .checked_add_months(Months::new(n.checked_mul(self.interval)?))?;
+                    self.dates_in_month(start, current.day())
+                        .into_iter()
+                        .filter(|date| *date > current)
+                        .min()
+                })
+            }
+        }
+    }
+
+    // n 周期分の日数。1 周期は INTERVAL × days 日
+    fn periods(&self, n: u32, days: u64) -> Days {
+        Days::new(u64::from(n) * u64::from(self.interval) * days)
+    }
+
+    fn weekdays(&self, default: Weekday) -> impl Iterator<Item = Weekday> + '_ {
+        let default = self.by_day.is_empty().then_some(default);
+        self.by_day.iter().map(|d| d.weekday).chain(default)
+    }
+
+    // start の月のうち規則に当てはまる日。BYDAY がなければ元の回と同じ日付で、その月になければ飛ばす
+    fn dates_in_month(&self, start: NaiveDate, day: u32) -> Vec<NaiveDate> {
+        let dates: Vec<NaiveDate> = start
+            .iter_days()
+            .take_while(|date| date.month() == start.month())
+            .collect();
+        if self.by_day.is_empty() {
+            return dates.into_iter().filter(|date| date.day() == day).collect();
+        }
+        self.by_day
+            .iter()
+            .flat_map(|by_day| {
+                let same: Vec<NaiveDate> = dates
+                    .iter()
+                    .copied()
+                    .filter(|date| date.weekday() == by_day.weekday)
+                    .collect();
+                match by_day.ordinal {
+                    None => same,
+                    Some(ordinal) => {
+                        let index = if ordinal > 0 {
+                            Some(ordinal as usize - 1)
+                        } else {
+                            same.len().checked_sub(ordinal.unsigned_abs() as usize)
+                        };
+                        index
+                            .and_then(|index| same.get(index).copied())
+                            .into_iter()
+                            .collect()
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FREQ={}", self.frequency)?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<String> = self.by_day.iter().map(ToString::to_string).collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%dT%H%M%SZ"))?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={count}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rule = s.trim();
+        let rule = rule.strip_prefix("RRULE:").unwrap_or(rule);
+
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut by_day = Vec::new();
+        let mut until = None;
+        let mut count = None;
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (name, value) = part.split_once('=').ok_or_else(|| invalid(part))?;
+            match name.to_ascii_uppercase().as_str() {
+                "FREQ" => frequency = Some(value.parse::<Frequency>()?),
+                "INTERVAL" => interval = parse_positive(value).ok_or_else(|| invalid(part))?,
+                "BYDAY" => {
+                    by_day = value
+                        .split(',')
+                        .map(str::parse::<ByDay>)
+                        .collect::<AppResult<Vec<_>>>()?
+                }
+                "UNTIL" => until = Some(parse_until(value).ok_or_else(|| invalid(part))?),
+                "COUNT" => count = Some(parse_positive(value).ok_or_else(|| invalid(part))?),
+                _ => return Err(invalid(part)),
+            }
+        }
+
+        let frequency = frequency.ok_or_else(|| invalid("FREQ is required"))?;
+        if until.is_some() && count.is_some() {
+            return Err(invalid("UNTIL and COUNT cannot be combined"));
+        }
+        if frequency != Frequency::Monthly && by_day.iter().any(|d| d.ordinal.is_some()) {
+            return Err(invalid("BYDAY ordinals are only allowed with FREQ=MONTHLY"));
+        }
+        Ok(Self {
+            frequency,
+            interval,
+            by_day,
+            until,
+            count,
+        })
+    }
+}
+
+impl TryFrom<String> for Recurrence {
+    type Error = AppError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Recurrence> for String {
+    fn from(value: Recurrence) -> Self {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+        })
+    }
+}
+
+impl FromStr for Frequency {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "DAILY" => Ok(Frequency::Daily),
+            "WEEKLY" => Ok(Frequency::Weekly),
+            "MONTHLY" => Ok(Frequency::Monthly),
+            _ => Err(invalid(format!("unsupported FREQ {s}"))),
+        }
+    }
+}
+
+impl fmt::Display for ByDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ordinal) = self.ordinal {
+            write!(f, "{ordinal}")?;
+        }
+        f.write_str(match self.weekday {
+            Weekday::Mon => "MO",
+            Weekday::Tue => "TU",
+            Weekday::Wed => "WE",
+            Weekday::Thu => "TH",
+            Weekday::Fri => "FR",
+            Weekday::Sat => "SA",
+            Weekday::Sun => "SU",
+        })
+    }
+}
+
+impl FromStr for ByDay {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.is_ascii() || s.len() < 2 {
+            return Err(invalid(format!("BYDAY {s}")));
+        }
+        let (ordinal, code) = s.split_at(s.len() - 2);
+        let weekday = match code.to_ascii_uppercase().as_str() {
+            "MO" => Weekday::Mon,
+            "TU" => Weekday::Tue,
+            "WE" => Weekday::Wed,
+            "TH" => Weekday::Thu,
+            "FR" => Weekday::Fri,
+            "SA" => Weekday::Sat,
+            "SU" => Weekday::Sun,
+            _ => return Err(invalid(format!("BYDAY {s}"))),
+        };
+        let ordinal = match ordinal {
+            "" => None,
+            ordinal => Some(
+                ordinal
+                    .parse::<i8>()
+                    .ok()
+                    .filter(|n| (1..=5).contains(&n.unsigned_abs()))
+                    .ok_or_else(|| invalid(format!("BYDAY {s}")))?,
+            ),
+        };
+        Ok(Self { ordinal, weekday })
+    }
+}
+
+fn parse_positive(value: &str) -> Option<u32> {
+    value.parse().ok().filter(|n| *n > 0)
+}
+
+// UTC の日時（20261231T090000Z）か日付（20261231）を受け付ける。日付だけならその日の終わりまでを含める
+fn parse_until(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(until) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ") {
+        return Some(until.and_utc());
+    }
+    NaiveDate::parse_from_str(value, "%Y%m%d")
+        .ok()?
+        .and_hms_opt(23, 59, 59)
+        .map(|until| until.and_utc())
+}
+
+fn invalid(detail: impl fmt::Display) -> AppError {
+    AppError::ConversionEntityError(format!("Invalid recurrence rule: {detail}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, 9, 30, 0)
+            .single()
+            .expect("正しい日時")
+    }
+
+    fn next(rule: &str, current: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let rule: Recurrence = rule.parse().expect("正しい規則");
+        rule.next(current).map(|(next, _)| next)
+    }
+
+    #[test]
+    fn 規則を文字列と相互に変換できる() {
+        let rule: Recurrence = "RRULE:freq=weekly;interval=2;byday=MO,we;COUNT=3"
+            .parse()
+            .expect("正しい規則");
+
+        assert_eq!(rule.frequency, Frequency::Weekly);
+        assert_eq!(rule.interval, 2);
+        assert_eq!(
+            rule.to_string(),
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE;COUNT=3"
+        );
+        assert_eq!(
+            "FREQ=MONTHLY;BYDAY=-1FR;UNTIL=20261231"
+                .parse::<Recurrence>()
+                .expect("正しい規則")
+                .to_string(),
+            "FREQ=MONTHLY;BYDAY=-1FR;UNTIL=20261231T235959Z"
+        );
+    }
+
+    #[test]
+    fn 未対応や矛盾する規則は失敗する() {
+        for rule in [
+            "",
+            "INTERVAL=2",
+            "FREQ=YEARLY",
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=DAILY;COUNT=2;UNTIL=20261231",
+            "FREQ=WEEKLY;BYDAY=1MO",
+            "FREQ=MONTHLY;BYDAY=6MO",
+            "FREQ=DAILY;BYHOUR=9",
+            "FREQ=DAILY;BYDAY=月",
+        ] {
+            assert!(rule.parse::<Recurrence>().is_err(), "{rule} は失敗する");
+        }
+    }
+
+    #[test]
+    fn 毎日の繰り返しは間隔と曜日に従い時刻を引き継ぐ() {
+        assert_eq!(
+            next("FREQ=DAILY;INTERVAL=2", at(2026, 10, 18)),
+            Some(at(2026, 10, 20))
+        );
+        // 2026-10-19 は月曜日
+        assert_eq!(
+            next("FREQ=DAILY;BYDAY=MO,FR", at(2026, 10, 19)),
+            Some(at(2026, 10, 23))
+        );
+        assert_eq!(
+            next("FREQ=DAILY;BYDAY=MO,FR", at(2026, 10, 23)),
+            Some(at(2026, 10, 26))
+        );
+        // 7 日おきでは曜日が変わらないため、該当する日はない
+        assert_eq!(
+            next("FREQ=DAILY;INTERVAL=7;BYDAY=TU", at(2026, 10, 19)),
+            None
+        );
+    }
+
+    #[test]
+    fn 毎週の繰り返しは同じ週の次の曜日から間隔分先の週へ進む() {
+        let rule = "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE";
+
+        assert_eq!(next(rule, at(2026, 10, 19)), Some(at(2026, 10, 21)));
+        assert_eq!(next(rule, at(2026, 10, 21)), Some(at(2026, 11, 2)));
+        assert_eq!(
+            next("FREQ=WEEKLY", at(2026, 10, 18)),
+            Some(at(2026, 10, 25))
+        );
+    }
+
+    #[test]
+    fn 毎月の繰り返しは存在しない日付を飛ばす() {
+        assert_eq!(next("FREQ=MONTHLY", at(2026, 1, 31)), Some(at(2026, 3, 31)));
+        assert_eq!(
+            next("FREQ=MONTHLY;INTERVAL=3", at(2026, 10, 18)),
+            Some(at(2027, 1, 18))
+        );
+    }
+
+    #[test]
+    fn 毎月の第n曜日と最終曜日を指定できる() {
+        // 2026-10-13 は 10 月の第 2 火曜日、2026-10-30 は最終金曜日
+        assert_eq!(
+            next("FREQ=MONTHLY;BYDAY=2TU", at(2026, 10, 13)),
+            Some(at(2026, 11, 10))
+        );
+        assert_eq!(
+            next("FREQ=MONTHLY;BYDAY=-1FR", at(2026, 10, 30)),
+            Some(at(2026, 11, 27))
+        );
+    }
+
+    #[test]
+    fn 回数と終了日時を使い切ると次の回はない() {
+        let rule: Recurrence = "FREQ=DAILY;COUNT=2".parse().expect("正しい規則");
+        let (second, rule) = rule.next(at(2026, 10, 18)).expect("2 回目がある");
+        assert_eq!(second, at(2026, 10, 19));
+        assert_eq!(rule.count, Some(1));
+        assert!(rule.next(second).is_none());
+
+        let until = "FREQ=DAILY;UNTIL=20261019T093000Z";
+        assert_eq!(next(until, at(2026, 10, 18)), Some(at(2026, 10, 19)));
+        assert_eq!(next(until, at(2026, 10, 19)), None);
+    }
+}
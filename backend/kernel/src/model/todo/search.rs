@@ -0,0 +1,203 @@
+use shared::error::{AppError, AppResult};
+
+use super::Todo;
+use crate::model::list::{DEFAULT_LIMIT, MAX_LIMIT};
+
+// 説明文から抜粋するときに一致箇所の前後に残す文字数
+const SNIPPET_CONTEXT: usize = 40;
+
+// todo の全文検索条件。空白区切りの語をすべて含むものを関連度順に返す
+#[derive(Debug, Clone, PartialEq)]
+pub struct TodoSearch {
+    pub text: String,
+    pub limit: i64,
+}
+
+impl TodoSearch {
+    pub fn new(text: &str, limit: Option<i64>) -> AppResult<Self> {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.is_empty() {
+            return Err(AppError::ConversionEntityError(
+                "The search query must not be blank".into(),
+            ));
+        }
+        Ok(Self {
+            text,
+            limit: limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+        })
+    }
+
+    pub fn terms(&self) -> Vec<&str> {
+        self.text.split(' ').collect()
+    }
+
+    // 全語を含む場合に、語が占める文字数の割合を関連度として返す。DB を介さない実装向け
+    pub fn score(&self, title: &str, description: Option<&str>) -> Option<f32> {
+        let text = match description {
+            Some(description) => format!("{title}\n{description}"),
+            None => title.to_string(),
+        };
+        self.score_text(&text)
+    }
+
+    fn score_text(&self, text: &str) -> Option<f32> {
+        let total = text.chars().count();
+        let mut matched = 0;
+        for term in self.terms() {
+            find_ignore_case(text, term)?;
+            matched += term.chars().count();
+        }
+        Some((matched as f32 / total.max(1) as f32).min(1.0))
+    }
+
+    // タイトルに一致すればタイトルを、そうでなければ説明文の一致箇所周辺を抜粋する
+    pub fn snippet(&self, title: &str, description: Option<&str>) -> String {
+        if self.find_first(title).is_some() {
+            return self.highlight(title);
+        }
+        let Some((description, start)) =
+            description.and_then(|text| self.find_first(text).map(|start| (text, start)))
+        else {
+            return self.highlight(title);
+        };
+
+        let before = description[..start].chars().count();
+        let skip = before.saturating_sub(SNIPPET_CONTEXT);
+        let excerpt: String = description
+            .chars()
+            .skip(skip)
+            .take(SNIPPET_CONTEXT * 3)
+            .collect();
+        let mut snippet = String::new();
+        if skip > 0 {
+            snippet.push('…');
+        }
+        snippet.push_str(&self.highlight(&excerpt));
+        if skip + excerpt.chars().count() < description.chars().count() {
+            snippet.push('…');
+        }
+        snippet
+    }
+
+    fn find_first(&self, text: &str) -> Option<usize> {
+        self.terms()
+            .iter()
+            .filter_map(|term| find_ignore_case(text, term))
+            .map(|(start, _)| start)
+            .min()
+    }
+
+    // 検索語を `<mark>` で囲んだ文字列を返す。それ以外の部分は HTML エスケープする
+    pub fn highlight(&self, text: &str) -> String {
+        let terms = self.terms();
+        let mut snippet = String::with_capacity(text.len());
+        let mut rest = text;
+        while !rest.is_empty() {
+            let next = terms
+                .iter()
+                .filter_map(|term| find_ignore_case(rest, term))
+                .min_by_key(|&(start, end)| (start, std::cmp::Reverse(end)));
+            let Some((start, end)) = next else {
+                push_escaped(&mut snippet, rest);
+                break;
+            };
+            push_escaped(&mut snippet, &rest[..start]);
+            snippet.push_str("<mark>");
+            push_escaped(&mut snippet, &rest[start..end]);
+            snippet.push_str("</mark>");
+            rest = &rest[end..];
+        }
+        snippet
+    }
+}
+
+#[derive(Debug)]
+pub struct TodoSearchHit {
+    pub todo: Todo,
+    pub rank: f32,
+    pub snippet: String,
+}
+
+// 大文字小文字を区別せずに最初に一致した位置を (開始, 終了) のバイト位置で返す
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<(usize, usize)> {
+    haystack.char_indices().find_map(|(start, _)| {
+        let mut rest = haystack[start..].char_indices();
+        let mut end = start;
+        for expected in needle.chars() {
+            let (offset, actual) = rest.next()?;
+            if !actual.to_lowercase().eq(expected.to_lowercase()) {
+                return None;
+            }
+            end = start + offset + actual.len_utf8();
+        }
+        Some((start, end))
+    })
+}
+
+fn push_escaped(buf: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => buf.push_str("&amp;"),
+            '<' => buf.push_str("&lt;"),
+            '>' => buf.push_str("&gt;"),
+            '"' => buf.push_str("&quot;"),
+            '\'' => buf.push_str("&#39;"),
+            _ => buf.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn 空白だけの検索語は失敗する() {
+        let err = TodoSearch::new(" \u{3000} ", None).expect_err("空の検索語は失敗する");
+        assert!(matches!(err, AppError::ConversionEntityError(_)));
+    }
+
+    #[test]
+    fn 検索語は空白で区切る() {
+        let search = TodoSearch::new("  牛乳　Milk ", None).expect("成功する");
+        assert_eq!(search.terms(), vec!["牛乳", "Milk"]);
+    }
+
+    #[test]
+    fn 日本語と英語の混在したタイトルに部分一致する() {
+        let search = TodoSearch::new("牛乳 milk", None).expect("成功する");
+
+        assert!(search.score("スーパーで牛乳(Milk)を買う", None).is_some());
+        assert!(search.score("スーパーで牛乳を買う", None).is_none());
+        assert!(
+            search
+                .score("スーパーで牛乳を買う", Some("低脂肪 milk"))
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn 抜粋は検索語を強調しhtmlをエスケープする() {
+        let search = TodoSearch::new("milk 牛乳", None).expect("成功する");
+
+        assert_eq!(
+            search.highlight("<b>MILK</b> と牛乳"),
+            "&lt;b&gt;<mark>MILK</mark>&lt;/b&gt; と<mark>牛乳</mark>"
+        );
+    }
+
+    #[test]
+    fn タイトルに一致しなければ説明文の一致箇所を抜粋する() {
+        let search = TodoSearch::new("牛乳", None).expect("成功する");
+        let description = format!("{}牛乳を買う{}", "あ".repeat(50), "い".repeat(200));
+
+        let snippet = search.snippet("買い物", Some(&description));
+
+        assert!(snippet.starts_with(&format!("…{}<mark>牛乳</mark>", "あ".repeat(40))));
+        assert!(snippet.ends_with('…'));
+        assert_eq!(
+            search.snippet("牛乳を買う", Some(&description)),
+            "<mark>牛乳</mark>を買う"
+        );
+    }
+}
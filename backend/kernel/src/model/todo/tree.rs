@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+use super::Todo;
+use crate::model::id::TodoId;
+
+// todo とその子孫を親子関係に沿って組み立てたもの
+#[derive(Debug)]
+pub struct TodoTree {
+    pub todo: Todo,
+    pub progress: SubtaskProgress,
+    pub children: Vec<TodoTree>,
+}
+
+// 子孫すべてのうち完了したものの件数と全件数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SubtaskProgress {
+    pub done: i64,
+    pub total: i64,
+}
+
+impl TodoTree {
+    // root に繋がらない todo は無視する。子は作成順に並べる
+    pub fn build(root: Todo, descendants: Vec<Todo>) -> Self {
+        let mut by_parent: HashMap<TodoId, Vec<Todo>> = HashMap::new();
+        for todo in descendants {
+            if let Some(parent_id) = todo.parent_id {
+                by_parent.entry(parent_id).or_default().push(todo);
+            }
+        }
+        Self::assemble(root, &mut by_parent)
+    }
+
+    // 取り出した子は表から消すので、万一循環していても同じ todo を二度辿らない
+    fn assemble(todo: Todo, by_parent: &mut HashMap<TodoId, Vec<Todo>>) -> Self {
+        let mut children = by_parent.remove(&todo.id).unwrap_or_default();
+        children.sort_by_key(|child| (child.created_at, child.id.raw()));
+        let children: Vec<TodoTree> = children
+            .into_iter()
+            .map(|child| Self::assemble(child, by_parent))
+            .collect();
+
+        let progress = children
+            .iter()
+            .fold(SubtaskProgress::default(), |acc, child| SubtaskProgress {
+                done: acc.done + child.progress.done + i64::from(child.todo.completed),
+                total: acc.total + child.progress.total + 1,
+            });
+        Self {
+            todo,
+            progress,
+            children,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{id::UserId, todo::position::Position};
+    use chrono::{Duration, Utc};
+
+    fn todo(title: &str, parent_id: Option<TodoId>, completed: bool, offset: i64) -> Todo {
+        let created_at = Utc::now() + Duration::seconds(offset);
+        Todo {
+            id: TodoId::new(),
+            user_id: UserId::new(),
+            project_id: None,
+            parent_id,
+            title: title.to_string(),
+            description: None,
+            priority: Default::default(),
+            completed,
+            due_at: None,
+            recurrence: None,
+            position: Position::first(),
+            created_at,
+            updated_at: created_at,
+        }
+    }
+
+    #[test]
+    fn 子孫をまとめて進捗を数える() {
+        let root = todo("引っ越し", None, false, 0);
+        let packing = todo("荷造り", Some(root.id), false, 2);
+        let books = todo("本を詰める", Some(packing.id), true, 3);
+        let dishes = todo("食器を詰める", Some(packing.id), false, 4);
+        let contract = todo("契約", Some(root.id), true, 1);
+
+        let tree = TodoTree::build(root, vec![books, dishes, packing, contract]);
+
+        assert_eq!(tree.progress, SubtaskProgress { done: 2, total: 4 });
+        let titles: Vec<&str> = tree
+            .children
+            .iter()
+            .map(|c| c.todo.title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["契約", "荷造り"]);
+        assert_eq!(
+            tree.children[1].progress,
+            SubtaskProgress { done: 1, total: 2 }
+        );
+    }
+
+    #[test]
+    fn 根に繋がらないtodoは無視する() {
+        let root = todo("a", None, false, 0);
+        let stray = todo("b", Some(TodoId::new()), true, 1);
+
+        let tree = TodoTree::build(root, vec![stray]);
+
+        assert!(tree.children.is_empty());
+        assert_eq!(tree.progress, SubtaskProgress::default());
+    }
+}
@@ -6,6 +6,12 @@ pub struct CreateUser {
     pub password: String,
 }
 
+pub struct UpdateUserPassword {
+    pub user_id: UserId,
+    pub current_password: String,
+    pub new_password: String,
+}
+
 pub struct DeleteUser {
     pub id: UserId,
 }
@@ -1,4 +1,6 @@
-use crate::model::id::UserId;
+use serde::Deserialize;
+
+use crate::model::{id::UserId, list::SortKey};
 
 pub mod event;
 
@@ -8,3 +10,22 @@ pub struct User {
     pub name: String,
     pub email: String,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserSort {
+    #[default]
+    CreatedAt,
+    Name,
+    Email,
+}
+
+impl SortKey for UserSort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            UserSort::CreatedAt => "created_at",
+            UserSort::Name => "name",
+            UserSort::Email => "email",
+        }
+    }
+}
@@ -1,4 +1,10 @@
-use crate::model::auth::{AccessToken, UserCredential, event::StoreToken};
+use crate::model::{
+    auth::{
+        AccessTokenId, Session, UserCredential,
+        event::{DeleteSession, RotateToken, StoreToken},
+    },
+    id::UserId,
+};
 use async_trait::async_trait;
 use shared::error::AppResult;
 
@@ -7,7 +13,28 @@ use shared::error::AppResult;
 pub trait AuthRepository: Send + Sync {
     async fn find_by_email(&self, email: String) -> AppResult<Option<UserCredential>>;
 
-    async fn store_token(&self, event: StoreToken) -> AppResult<AccessToken>;
+    // 署名の検証を済ませたトークンの jti が、許可リストに残っているかを確かめる
+    async fn fetch_user_id_from_token(
+        &self,
+        access_token_id: &AccessTokenId,
+    ) -> AppResult<Option<UserId>>;
 
-    async fn delete_token(&self, access_token: AccessToken) -> AppResult<()>;
+    async fn store_token(&self, event: StoreToken) -> AppResult<()>;
+
+    // 使用済みのリフレッシュトークンが再び使われた場合は、同じ系列のトークンをすべて失効させる
+    async fn rotate_token(&self, event: RotateToken) -> AppResult<UserId>;
+
+    async fn delete_token(&self, access_token_id: AccessTokenId) -> AppResult<()>;
+
+    // 最後に使われた順に並べる
+    async fn find_sessions(&self, user_id: UserId) -> AppResult<Vec<Session>>;
+
+    // セッションのリフレッシュトークンと、それと組で発行したアクセストークンを失効させる
+    async fn delete_session(&self, event: DeleteSession) -> AppResult<()>;
+
+    async fn delete_other_tokens(&self, user_id: UserId, keep: &AccessTokenId) -> AppResult<()>;
+
+    fn token_ttl(&self) -> u64;
+
+    fn refresh_token_ttl(&self) -> u64;
 }
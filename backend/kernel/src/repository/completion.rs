@@ -0,0 +1,22 @@
+use crate::model::{
+    completion::{
+        Completion,
+        event::{CreateCompletion, UpdateReopened},
+    },
+    id::{TodoId, UserId},
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait CompletionRepository: Send + Sync {
+    async fn create(&self, event: CreateCompletion) -> AppResult<Completion>;
+    async fn update_reopened(&self, event: UpdateReopened) -> AppResult<()>;
+    async fn find_completed_all(&self, user_id: UserId) -> AppResult<Vec<Completion>>;
+    async fn find_history_by_todo_id(
+        &self,
+        todo_id: TodoId,
+        user_id: UserId,
+    ) -> AppResult<Vec<Completion>>;
+}
@@ -0,0 +1,22 @@
+use crate::model::{
+    dependency::{
+        TodoDependencies,
+        event::{AddDependency, RemoveDependency},
+    },
+    id::{TodoId, UserId},
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait DependencyRepository: Send + Sync {
+    // 既にある依存を張り直しても成功する。循環する依存は UnprocessableEntity で失敗する
+    async fn add(&self, event: AddDependency) -> AppResult<()>;
+    async fn remove(&self, event: RemoveDependency) -> AppResult<()>;
+    async fn find_by_todo_id(
+        &self,
+        todo_id: TodoId,
+        user_id: UserId,
+    ) -> AppResult<TodoDependencies>;
+}
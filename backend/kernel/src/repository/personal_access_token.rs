@@ -0,0 +1,22 @@
+use crate::model::{
+    id::UserId,
+    personal_access_token::{
+        PersonalAccessToken, PersonalAccessTokenSecret,
+        event::{CreatePersonalAccessToken, DeletePersonalAccessToken},
+    },
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait PersonalAccessTokenRepository: Send + Sync {
+    async fn create(&self, event: CreatePersonalAccessToken) -> AppResult<PersonalAccessToken>;
+    async fn find_all(&self, user_id: UserId) -> AppResult<Vec<PersonalAccessToken>>;
+    async fn delete(&self, event: DeletePersonalAccessToken) -> AppResult<()>;
+    // 期限内のトークンなら最終利用日時を更新して返す
+    async fn authenticate(
+        &self,
+        secret: &PersonalAccessTokenSecret,
+    ) -> AppResult<Option<PersonalAccessToken>>;
+}
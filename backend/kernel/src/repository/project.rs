@@ -0,0 +1,20 @@
+use crate::model::{
+    id::{ProjectId, UserId},
+    project::{
+        Project,
+        event::{CreateProject, DeleteProject, UpdateProject},
+    },
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait ProjectRepository: Send + Sync {
+    async fn create(&self, event: CreateProject) -> AppResult<Project>;
+    // include_archived が false ならアーカイブ済みのプロジェクトを除く
+    async fn find_all(&self, user_id: UserId, include_archived: bool) -> AppResult<Vec<Project>>;
+    async fn find_by_id(&self, id: ProjectId, user_id: UserId) -> AppResult<Option<Project>>;
+    async fn update(&self, event: UpdateProject) -> AppResult<()>;
+    async fn delete(&self, event: DeleteProject) -> AppResult<()>;
+}
@@ -0,0 +1,22 @@
+use crate::model::{
+    id::{TagId, UserId},
+    tag::{
+        Tag,
+        event::{AttachTag, CreateTag, DeleteTag, DetachTag, UpdateTag},
+    },
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait TagRepository: Send + Sync {
+    async fn create(&self, event: CreateTag) -> AppResult<Tag>;
+    async fn find_all(&self, user_id: UserId) -> AppResult<Vec<Tag>>;
+    async fn find_by_id(&self, id: TagId, user_id: UserId) -> AppResult<Option<Tag>>;
+    async fn update(&self, event: UpdateTag) -> AppResult<()>;
+    async fn delete(&self, event: DeleteTag) -> AppResult<()>;
+    // 既に付いているタグを付け直しても成功する
+    async fn attach(&self, event: AttachTag) -> AppResult<()>;
+    async fn detach(&self, event: DetachTag) -> AppResult<()>;
+}
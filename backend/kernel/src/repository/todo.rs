@@ -0,0 +1,35 @@
+use crate::model::{
+    id::{TodoId, UserId},
+    list::{ListQuery, Page},
+    todo::{
+        Todo, TodoSort,
+        event::{CreateTodo, DeleteTodo, ReorderTodo, UpdateTodo},
+        filter::TodoFilter,
+        search::{TodoSearch, TodoSearchHit},
+        tree::TodoTree,
+    },
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait TodoRepository: Send + Sync {
+    async fn create(&self, event: CreateTodo) -> AppResult<Todo>;
+    async fn find_all(
+        &self,
+        user_id: UserId,
+        filter: TodoFilter,
+        query: ListQuery<TodoSort>,
+    ) -> AppResult<Page<Todo>>;
+    async fn search(&self, user_id: UserId, search: TodoSearch) -> AppResult<Vec<TodoSearchHit>>;
+    async fn find_by_id(&self, id: TodoId, user_id: UserId) -> AppResult<Option<Todo>>;
+    // todo と、その子孫すべてを木構造で返す
+    async fn find_tree(&self, id: TodoId, user_id: UserId) -> AppResult<Option<TodoTree>>;
+    // 親を自身や子孫に付け替えて循環させる更新は UnprocessableEntity になる
+    async fn update(&self, event: UpdateTodo) -> AppResult<()>;
+    // 手動の並び順での位置を変える。書き換えるのは動かした todo のキーだけ
+    async fn reorder(&self, event: ReorderTodo) -> AppResult<Todo>;
+    // 子孫の todo もまとめて削除する
+    async fn delete(&self, event: DeleteTodo) -> AppResult<()>;
+}
@@ -1,8 +1,9 @@
 use crate::model::{
     id::UserId,
+    list::{ListQuery, Page},
     user::{
-        User,
-        event::{CreateUser, DeleteUser},
+        User, UserSort,
+        event::{CreateUser, DeleteUser, UpdateUserPassword},
     },
 };
 use async_trait::async_trait;
@@ -13,6 +14,7 @@ use shared::error::AppResult;
 pub trait UserRepository: Send + Sync {
     async fn create(&self, event: CreateUser) -> AppResult<User>;
     async fn find_by_id(&self, id: UserId) -> AppResult<Option<User>>;
-    async fn find_all(&self) -> AppResult<Vec<User>>;
+    async fn find_all(&self, query: ListQuery<UserSort>) -> AppResult<Page<User>>;
+    async fn update_password(&self, event: UpdateUserPassword) -> AppResult<()>;
     async fn delete(&self, event: DeleteUser) -> AppResult<()>;
 }
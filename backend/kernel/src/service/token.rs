@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use shared::{
+    config::JwtKeys,
+    error::{AppError, AppResult},
+};
+
+use crate::model::{
+    auth::{AccessToken, AccessTokenId},
+    id::UserId,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccessTokenClaims {
+    pub sub: UserId,
+    pub exp: i64,
+    pub iat: i64,
+    pub jti: AccessTokenId,
+}
+
+// アクセストークン（HS256 の JWT）を発行・検証する。署名は現在の鍵で行い、
+// 検証では header の kid から鍵を選ぶため、ローテーション前の鍵で署名したトークンも受け付ける
+pub struct TokenService {
+    key_id: String,
+    encoding_key: EncodingKey,
+    decoding_keys: HashMap<String, DecodingKey>,
+    ttl: u64,
+}
+
+impl TokenService {
+    pub fn new(keys: &JwtKeys, ttl: u64) -> Self {
+        let decoding_keys = std::iter::once(&keys.current)
+            .chain(&keys.previous)
+            .map(|key| {
+                (
+                    key.id.clone(),
+                    DecodingKey::from_secret(key.secret.as_bytes()),
+                )
+            })
+            .collect();
+        Self {
+            key_id: keys.current.id.clone(),
+            encoding_key: EncodingKey::from_secret(keys.current.secret.as_bytes()),
+            decoding_keys,
+            ttl,
+        }
+    }
+
+    // jti は許可リストへの登録と揃えるため、呼び出し側で決めたものを使う
+    pub fn issue(&self, user_id: UserId, jti: AccessTokenId) -> AppResult<AccessToken> {
+        let now = Utc::now();
+        let claims = AccessTokenClaims {
+            sub: user_id,
+            exp: (now + Duration::seconds(self.ttl as i64)).timestamp(),
+            iat: now.timestamp(),
+            jti,
+        };
+        let header = Header {
+            kid: Some(self.key_id.clone()),
+            ..Header::new(Algorithm::HS256)
+        };
+        let token = jsonwebtoken::encode(&header, &claims, &self.encoding_key)
+            .map_err(AppError::IssueTokenError)?;
+        Ok(AccessToken(token))
+    }
+
+    // 署名と期限だけを確かめる。ログアウト済みかどうかは AuthRepository の許可リストで判断する
+    pub fn verify(&self, access_token: &AccessToken) -> AppResult<AccessTokenClaims> {
+        let invalid = || AppError::Unauthorized("Invalid token".into());
+        let header = jsonwebtoken::decode_header(&access_token.0).map_err(|_| invalid())?;
+        let key = header
+            .kid
+            .and_then(|kid| self.decoding_keys.get(&kid))
+            .ok_or_else(invalid)?;
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.leeway = 0;
+        validation.set_required_spec_claims(&["exp", "sub"]);
+        let data = jsonwebtoken::decode::<AccessTokenClaims>(&access_token.0, key, &validation)
+            .map_err(|_| invalid())?;
+        Ok(data.claims)
+    }
+
+    pub fn ttl(&self) -> u64 {
+        self.ttl
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::config::JwtKey;
+
+    fn key(id: &str, secret: &str) -> JwtKey {
+        JwtKey {
+            id: id.to_string(),
+            secret: secret.to_string(),
+        }
+    }
+
+    fn service(current: JwtKey, previous: Vec<JwtKey>, ttl: u64) -> TokenService {
+        TokenService::new(&JwtKeys { current, previous }, ttl)
+    }
+
+    #[test]
+    fn 発行したトークンは検証できる() {
+        let service = service(key("k1", "secret"), Vec::new(), 3600);
+        let user_id = UserId::new();
+        let jti = AccessTokenId::generate();
+
+        let token = service.issue(user_id, jti.clone()).expect("発行が成功する");
+        let verified = service.verify(&token).expect("検証が成功する");
+
+        assert_eq!(verified.sub, user_id);
+        assert_eq!(verified.jti, jti);
+        assert_eq!(verified.exp - verified.iat, 3600);
+        let header = jsonwebtoken::decode_header(&token.0).expect("header");
+        assert_eq!(header.alg, Algorithm::HS256);
+        assert_eq!(header.kid.as_deref(), Some("k1"));
+    }
+
+    #[test]
+    fn ローテーション前の鍵で署名したトークンも検証できる() {
+        let old = service(key("k1", "old-secret"), Vec::new(), 3600);
+        let rotated = service(key("k2", "new-secret"), vec![key("k1", "old-secret")], 3600);
+        let token = old
+            .issue(UserId::new(), AccessTokenId::generate())
+            .expect("発行が成功する");
+
+        rotated.verify(&token).expect("古い鍵でも検証できる");
+
+        let dropped = service(key("k2", "new-secret"), Vec::new(), 3600);
+        assert!(dropped.verify(&token).is_err());
+    }
+
+    #[test]
+    fn 改ざんや期限切れのトークンは拒否する() {
+        let tokens = service(key("k1", "secret"), Vec::new(), 3600);
+        let token = tokens
+            .issue(UserId::new(), AccessTokenId::generate())
+            .expect("発行が成功する");
+        let forged = service(key("k1", "other-secret"), Vec::new(), 3600)
+            .issue(UserId::new(), AccessTokenId::generate())
+            .expect("発行が成功する");
+        let expired = service(key("k1", "secret"), Vec::new(), 0)
+            .issue(UserId::new(), AccessTokenId::generate())
+            .expect("発行が成功する");
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+
+        for token in [
+            forged,
+            expired,
+            AccessToken(format!("{}x", token.0)),
+            AccessToken("not-a-jwt".to_string()),
+        ] {
+            let err = tokens.verify(&token).expect_err("検証は失敗する");
+            assert!(matches!(err, AppError::Unauthorized(_)), "{token:?}");
+        }
+    }
+}
@@ -1,38 +1,140 @@
 use std::sync::Arc;
 
 use adapter::{
-    database::ConnectionPool,
+    database::{ConnectionPool, connect_database_with},
+    file::{
+        FileStore,
+        repository::{
+            auth::FileAuthRepositoryImpl, completion::FileCompletionRepositoryImpl,
+            dependency::FileDependencyRepositoryImpl, health::FileHealthCheckRepositoryImpl,
+            personal_access_token::FilePersonalAccessTokenRepositoryImpl,
+            project::FileProjectRepositoryImpl, tag::FileTagRepositoryImpl,
+            todo::FileTodoRepositoryImpl, user::FileUserRepositoryImpl,
+        },
+    },
     redis::RedisClient,
     repository::{
-        auth::AuthRepositoryImpl, health::HealthCheckRepositoryImpl, user::UserRepositoryImpl,
+        auth::AuthRepositoryImpl, completion::CompletionRepositoryImpl,
+        dependency::DependencyRepositoryImpl, health::HealthCheckRepositoryImpl,
+        personal_access_token::PersonalAccessTokenRepositoryImpl, project::ProjectRepositoryImpl,
+        tag::TagRepositoryImpl, todo::TodoRepositoryImpl, user::UserRepositoryImpl,
     },
 };
-use kernel::repository::{
-    auth::AuthRepository, health::HealthCheckRepository, user::UserRepository,
+use kernel::{
+    repository::{
+        auth::AuthRepository, completion::CompletionRepository, dependency::DependencyRepository,
+        health::HealthCheckRepository, personal_access_token::PersonalAccessTokenRepository,
+        project::ProjectRepository, tag::TagRepository, todo::TodoRepository, user::UserRepository,
+    },
+    service::token::TokenService,
 };
-use shared::config::AppConfig;
+use shared::config::{AppConfig, LocalConfig, StorageBackend};
 
 #[derive(Clone)]
 pub struct AppRegistryImpl {
     pub health_check_repository: Arc<dyn HealthCheckRepository>,
     pub user_repository: Arc<dyn UserRepository>,
     pub auth_repository: Arc<dyn AuthRepository>,
+    pub todo_repository: Arc<dyn TodoRepository>,
+    pub completion_repository: Arc<dyn CompletionRepository>,
+    pub tag_repository: Arc<dyn TagRepository>,
+    pub project_repository: Arc<dyn ProjectRepository>,
+    pub dependency_repository: Arc<dyn DependencyRepository>,
+    pub personal_access_token_repository: Arc<dyn PersonalAccessTokenRepository>,
+    pub token_service: Arc<TokenService>,
 }
 
 impl AppRegistryImpl {
     pub fn new(pool: ConnectionPool, kv_store: Arc<RedisClient>, app_config: AppConfig) -> Self {
         let health_check_repository = Arc::new(HealthCheckRepositoryImpl::new(pool.clone()));
         let user_repository = Arc::new(UserRepositoryImpl::new(pool.clone()));
+        let token_service = Arc::new(TokenService::new(
+            &app_config.auth.jwt_keys,
+            app_config.auth.ttl,
+        ));
         let auth_repository = Arc::new(AuthRepositoryImpl::new(
             pool.clone(),
             kv_store,
             app_config.auth.ttl,
+            app_config.auth.refresh_ttl,
         ));
+        let todo_repository = Arc::new(TodoRepositoryImpl::new(pool.clone()));
+        let completion_repository = Arc::new(CompletionRepositoryImpl::new(pool.clone()));
+        let tag_repository = Arc::new(TagRepositoryImpl::new(pool.clone()));
+        let project_repository = Arc::new(ProjectRepositoryImpl::new(pool.clone()));
+        let dependency_repository = Arc::new(DependencyRepositoryImpl::new(pool.clone()));
+        let personal_access_token_repository =
+            Arc::new(PersonalAccessTokenRepositoryImpl::new(pool.clone()));
 
         Self {
             health_check_repository,
             user_repository,
             auth_repository,
+            todo_repository,
+            completion_repository,
+            tag_repository,
+            project_repository,
+            dependency_repository,
+            personal_access_token_repository,
+            token_service,
+        }
+    }
+
+    // Postgres/Redis を使わず、ローカルの JSON ファイルに保存する構成
+    pub fn new_with_file_store(local_config: LocalConfig) -> Self {
+        let store = FileStore::new(&local_config.file);
+        let health_check_repository = Arc::new(FileHealthCheckRepositoryImpl::new(store.clone()));
+        let user_repository = Arc::new(FileUserRepositoryImpl::new(store.clone()));
+        let token_service = Arc::new(TokenService::new(
+            &local_config.auth.jwt_keys,
+            local_config.auth.ttl,
+        ));
+        let auth_repository = Arc::new(FileAuthRepositoryImpl::new(
+            store.clone(),
+            local_config.auth.ttl,
+            local_config.auth.refresh_ttl,
+        ));
+        let todo_repository = Arc::new(FileTodoRepositoryImpl::new(store.clone()));
+        let completion_repository = Arc::new(FileCompletionRepositoryImpl::new(store.clone()));
+        let tag_repository = Arc::new(FileTagRepositoryImpl::new(store.clone()));
+        let project_repository = Arc::new(FileProjectRepositoryImpl::new(store.clone()));
+        let dependency_repository = Arc::new(FileDependencyRepositoryImpl::new(store.clone()));
+        let personal_access_token_repository =
+            Arc::new(FilePersonalAccessTokenRepositoryImpl::new(store));
+
+        Self {
+            health_check_repository,
+            user_repository,
+            auth_repository,
+            todo_repository,
+            completion_repository,
+            tag_repository,
+            project_repository,
+            dependency_repository,
+            personal_access_token_repository,
+            token_service,
+        }
+    }
+
+    // STORAGE_BACKEND に応じて Postgres/Redis 構成かファイル構成かを選ぶ
+    pub fn from_env() -> anyhow::Result<Self> {
+        Self::from_env_with(LocalConfig::new)
+    }
+
+    // CLI のローカルモード用。ファイル構成では JWT_SECRET がなくても起動できる
+    pub fn from_env_for_cli() -> anyhow::Result<Self> {
+        Self::from_env_with(LocalConfig::for_cli)
+    }
+
+    fn from_env_with(local_config: fn() -> anyhow::Result<LocalConfig>) -> anyhow::Result<Self> {
+        match StorageBackend::from_env()? {
+            StorageBackend::Postgres => {
+                let app_config = AppConfig::new()?;
+                let pool = connect_database_with(&app_config.database);
+                let kv_store = Arc::new(RedisClient::new(&app_config.redis)?);
+                Ok(Self::new(pool, kv_store, app_config))
+            }
+            StorageBackend::File => Ok(Self::new_with_file_store(local_config()?)),
         }
     }
 
@@ -47,6 +149,34 @@ impl AppRegistryImpl {
     pub fn auth_repository(&self) -> Arc<dyn AuthRepository> {
         self.auth_repository.clone()
     }
+
+    pub fn todo_repository(&self) -> Arc<dyn TodoRepository> {
+        self.todo_repository.clone()
+    }
+
+    pub fn completion_repository(&self) -> Arc<dyn CompletionRepository> {
+        self.completion_repository.clone()
+    }
+
+    pub fn tag_repository(&self) -> Arc<dyn TagRepository> {
+        self.tag_repository.clone()
+    }
+
+    pub fn project_repository(&self) -> Arc<dyn ProjectRepository> {
+        self.project_repository.clone()
+    }
+
+    pub fn dependency_repository(&self) -> Arc<dyn DependencyRepository> {
+        self.dependency_repository.clone()
+    }
+
+    pub fn personal_access_token_repository(&self) -> Arc<dyn PersonalAccessTokenRepository> {
+        self.personal_access_token_repository.clone()
+    }
+
+    pub fn token_service(&self) -> Arc<TokenService> {
+        self.token_service.clone()
+    }
 }
 
 #[mockall::automock]
@@ -54,6 +184,13 @@ pub trait AppRegistryExt {
     fn health_check_repository(&self) -> Arc<dyn HealthCheckRepository>;
     fn user_repository(&self) -> Arc<dyn UserRepository>;
     fn auth_repository(&self) -> Arc<dyn AuthRepository>;
+    fn todo_repository(&self) -> Arc<dyn TodoRepository>;
+    fn completion_repository(&self) -> Arc<dyn CompletionRepository>;
+    fn tag_repository(&self) -> Arc<dyn TagRepository>;
+    fn project_repository(&self) -> Arc<dyn ProjectRepository>;
+    fn dependency_repository(&self) -> Arc<dyn DependencyRepository>;
+    fn personal_access_token_repository(&self) -> Arc<dyn PersonalAccessTokenRepository>;
+    fn token_service(&self) -> Arc<TokenService>;
 }
 
 impl AppRegistryExt for AppRegistryImpl {
@@ -68,6 +205,34 @@ impl AppRegistryExt for AppRegistryImpl {
     fn auth_repository(&self) -> Arc<dyn AuthRepository> {
         self.auth_repository.clone()
     }
+
+    fn todo_repository(&self) -> Arc<dyn TodoRepository> {
+        self.todo_repository.clone()
+    }
+
+    fn completion_repository(&self) -> Arc<dyn CompletionRepository> {
+        self.completion_repository.clone()
+    }
+
+    fn tag_repository(&self) -> Arc<dyn TagRepository> {
+        self.tag_repository.clone()
+    }
+
+    fn project_repository(&self) -> Arc<dyn ProjectRepository> {
+        self.project_repository.clone()
+    }
+
+    fn dependency_repository(&self) -> Arc<dyn DependencyRepository> {
+        self.dependency_repository.clone()
+    }
+
+    fn personal_access_token_repository(&self) -> Arc<dyn PersonalAccessTokenRepository> {
+        self.personal_access_token_repository.clone()
+    }
+
+    fn token_service(&self) -> Arc<TokenService> {
+        self.token_service.clone()
+    }
 }
 
 pub type AppRegistry = Arc<dyn AppRegistryExt + Send + Sync>;
@@ -1,4 +1,13 @@
 use anyhow::Result;
+use std::path::PathBuf;
+use strum::EnumString;
+
+const DEFAULT_DATA_FILE_PATH: &str = "rusty-todo.json";
+const DEFAULT_AUTH_TOKEN_TTL: u64 = 900;
+const DEFAULT_AUTH_REFRESH_TOKEN_TTL: u64 = 2592000;
+const DEFAULT_JWT_KEY_ID: &str = "default";
+// CLI のローカルモードではトークンが手元の CLI とデータファイルの間でしか使われないため、既定の鍵で署名する
+const DEFAULT_LOCAL_JWT_SECRET: &str = "rusty-todo-local";
 
 pub struct AppConfig {
     pub database: DatabaseConfig,
@@ -21,6 +30,8 @@ impl AppConfig {
         };
         let auth = AuthConfig {
             ttl: std::env::var("AUTH_TOKEN_TTL")?.parse::<u64>()?,
+            refresh_ttl: refresh_ttl_from_env()?,
+            jwt_keys: JwtKeys::new(std::env::var("JWT_SECRET")?)?,
         };
         Ok(Self {
             database,
@@ -44,5 +55,197 @@ pub struct RedisConfig {
 }
 
 pub struct AuthConfig {
+    // アクセストークンの有効期限（秒）。短くしておき、期限が切れたらリフレッシュトークンで再発行する
     pub ttl: u64,
+    pub refresh_ttl: u64,
+    pub jwt_keys: JwtKeys,
+}
+
+// アクセストークン（JWT）の署名鍵。鍵を入れ替えるときは、古い鍵を JWT_PREVIOUS_SECRETS に
+// `kid:secret` のカンマ区切りで残しておくと、発行済みのトークンが期限まで使える
+pub struct JwtKeys {
+    pub current: JwtKey,
+    pub previous: Vec<JwtKey>,
+}
+
+pub struct JwtKey {
+    pub id: String,
+    pub secret: String,
+}
+
+impl JwtKeys {
+    fn new(secret: String) -> Result<Self> {
+        let id = std::env::var("JWT_KEY_ID").unwrap_or_else(|_| DEFAULT_JWT_KEY_ID.into());
+        let previous = std::env::var("JWT_PREVIOUS_SECRETS").ok();
+        Self::from_parts(id, secret, previous.as_deref())
+    }
+
+    fn from_parts(id: String, secret: String, previous: Option<&str>) -> Result<Self> {
+        if secret.is_empty() {
+            anyhow::bail!("JWT_SECRET must not be empty");
+        }
+        let current = JwtKey { id, secret };
+        let previous = previous
+            .map(parse_previous_keys)
+            .transpose()?
+            .unwrap_or_default();
+
+        // kid が重なると検証用の鍵が上書きされ、どちらかの鍵で署名したトークンが検証できなくなる
+        let mut ids = std::collections::HashSet::new();
+        if let Some(key) = std::iter::once(&current)
+            .chain(&previous)
+            .find(|key| !ids.insert(key.id.as_str()))
+        {
+            anyhow::bail!("JWT key id `{}` is used more than once", key.id);
+        }
+
+        Ok(Self { current, previous })
+    }
+}
+
+fn parse_previous_keys(value: &str) -> Result<Vec<JwtKey>> {
+    value
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| match entry.trim().split_once(':') {
+            Some((id, secret)) if !id.is_empty() && !secret.is_empty() => Ok(JwtKey {
+                id: id.to_string(),
+                secret: secret.to_string(),
+            }),
+            _ => anyhow::bail!("JWT_PREVIOUS_SECRETS must be a comma-separated list of kid:secret"),
+        })
+        .collect()
+}
+
+fn refresh_ttl_from_env() -> Result<u64> {
+    Ok(match std::env::var("AUTH_REFRESH_TOKEN_TTL") {
+        Ok(ttl) => ttl.parse::<u64>()?,
+        Err(_) => DEFAULT_AUTH_REFRESH_TOKEN_TTL,
+    })
+}
+
+#[derive(Default, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum StorageBackend {
+    #[default]
+    Postgres,
+    File,
+}
+
+impl StorageBackend {
+    // 未設定なら Postgres を使う。綴りの誤りで意図しない構成にならないよう、知らない値は拒否する
+    pub fn from_env() -> Result<Self> {
+        match std::env::var("STORAGE_BACKEND") {
+            Ok(value) => Self::parse(&value),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self> {
+        value.parse().map_err(|_| {
+            anyhow::anyhow!("STORAGE_BACKEND must be one of postgres, file (got `{value}`)")
+        })
+    }
+}
+
+// Postgres/Redis を使わずにファイルへ永続化する場合の設定
+pub struct LocalConfig {
+    pub file: FileConfig,
+    pub auth: AuthConfig,
+}
+
+impl LocalConfig {
+    // HTTP サーバとして公開する場合は、Postgres 構成と同じく JWT_SECRET を必須にする
+    pub fn new() -> Result<Self> {
+        Self::with_jwt_secret(std::env::var("JWT_SECRET")?)
+    }
+
+    // CLI のローカルモード用。JWT_SECRET がなければ既定の鍵を使う
+    pub fn for_cli() -> Result<Self> {
+        Self::with_jwt_secret(
+            std::env::var("JWT_SECRET").unwrap_or_else(|_| DEFAULT_LOCAL_JWT_SECRET.into()),
+        )
+    }
+
+    fn with_jwt_secret(jwt_secret: String) -> Result<Self> {
+        let file = FileConfig {
+            path: std::env::var("DATA_FILE_PATH")
+                .unwrap_or_else(|_| DEFAULT_DATA_FILE_PATH.to_string())
+                .into(),
+        };
+        let auth = AuthConfig {
+            ttl: match std::env::var("AUTH_TOKEN_TTL") {
+                Ok(ttl) => ttl.parse::<u64>()?,
+                Err(_) => DEFAULT_AUTH_TOKEN_TTL,
+            },
+            refresh_ttl: refresh_ttl_from_env()?,
+            jwt_keys: JwtKeys::new(jwt_secret)?,
+        };
+        Ok(Self { file, auth })
+    }
+}
+
+pub struct FileConfig {
+    pub path: PathBuf,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn 保存先の構成を名前で選べる() {
+        assert!(matches!(
+            StorageBackend::parse("postgres"),
+            Ok(StorageBackend::Postgres)
+        ));
+        assert!(matches!(
+            StorageBackend::parse("file"),
+            Ok(StorageBackend::File)
+        ));
+    }
+
+    #[test]
+    fn 知らない保存先の構成は受け付けない() {
+        let err = StorageBackend::parse("flie")
+            .err()
+            .expect("知らない値は失敗する");
+
+        assert!(err.to_string().contains("postgres, file"));
+    }
+
+    #[test]
+    fn 以前の鍵をkidごとに読み込める() {
+        let keys = JwtKeys::from_parts(
+            "2026-10".into(),
+            "current-secret".into(),
+            Some("2026-09:old-secret, 2026-08:older-secret"),
+        )
+        .expect("正しい設定");
+
+        assert_eq!(keys.current.id, "2026-10");
+        let previous = keys
+            .previous
+            .iter()
+            .map(|key| (key.id.as_str(), key.secret.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            previous,
+            vec![("2026-09", "old-secret"), ("2026-08", "older-secret")]
+        );
+    }
+
+    #[test]
+    fn 空の署名鍵は受け付けない() {
+        assert!(JwtKeys::from_parts("default".into(), String::new(), None).is_err());
+        assert!(JwtKeys::from_parts("default".into(), "secret".into(), Some("old:")).is_err());
+    }
+
+    #[test]
+    fn kidが重なる鍵は受け付けない() {
+        assert!(
+            JwtKeys::from_parts("default".into(), "secret".into(), Some("default:old")).is_err()
+        );
+        assert!(JwtKeys::from_parts("new".into(), "secret".into(), Some("old:a,old:b")).is_err());
+    }
 }
@@ -1,6 +1,13 @@
-use axum::{http::StatusCode, response::IntoResponse};
+use axum::{
+    Json,
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use serde::Serialize;
 use thiserror::Error;
 
+use crate::env::{Environment, which};
+
 #[derive(Error, Debug)]
 pub enum AppError {
     #[error("{0}")]
@@ -9,35 +16,224 @@ pub enum AppError {
     ConvertToUuidError(#[from] uuid::Error),
     #[error("{0}")]
     HashPasswordError(#[from] bcrypt::BcryptError),
+    #[error("Failed to issue a token.")]
+    IssueTokenError(#[source] jsonwebtoken::errors::Error),
     #[error("{0}")]
     Unauthorized(String),
     #[error("{0}")]
+    ForbiddenOperation(String),
+    #[error("{0}")]
     EntityNotFoundError(String),
+    #[error("{0}")]
+    UnprocessableEntity(String),
+    #[error("The {0} has already been taken")]
+    Conflict(String),
+    // 重複ではなく、リソースの現在の状態と両立しない操作
+    #[error("{0}")]
+    StateConflict(String),
     #[error("SQL execution failed.")]
     SqlExecuteError(#[source] sqlx::Error),
+    #[error("Transaction failed.")]
+    TransactionError(#[source] sqlx::Error),
     #[error("No rows affected: {0}")]
     NoRowsAffectedError(String),
     #[error("{0}")]
     KeyValueStoreError(#[from] redis::RedisError),
+    #[error("File store operation failed.")]
+    FileStoreError(#[from] std::io::Error),
     #[error("{0}")]
     ConversionEntityError(String),
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> axum::response::Response {
-        let status_code = match self {
+impl AppError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
             AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::ForbiddenOperation(_) => StatusCode::FORBIDDEN,
             AppError::EntityNotFoundError(_) => StatusCode::NOT_FOUND,
+            AppError::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Conflict(_) | AppError::StateConflict(_) => StatusCode::CONFLICT,
             AppError::ConvertToUuidError(_) => StatusCode::BAD_REQUEST,
             AppError::ValidationError(_) => StatusCode::BAD_REQUEST,
             AppError::HashPasswordError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::IssueTokenError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppError::SqlExecuteError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::TransactionError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppError::NoRowsAffectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppError::KeyValueStoreError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::FileStoreError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppError::ConversionEntityError(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    // クライアントが分岐に使う安定したエラーコード。内部エラーは種類を区別しない
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::ForbiddenOperation(_) => "forbidden",
+            AppError::EntityNotFoundError(_) => "not_found",
+            AppError::UnprocessableEntity(_) => "unprocessable_entity",
+            AppError::Conflict(_) => "conflict",
+            AppError::StateConflict(_) => "state_conflict",
+            AppError::ConvertToUuidError(_) => "invalid_id",
+            AppError::ValidationError(_) => "validation_failed",
+            AppError::ConversionEntityError(_) => "invalid_entity",
+            AppError::HashPasswordError(_)
+            | AppError::IssueTokenError(_)
+            | AppError::SqlExecuteError(_)
+            | AppError::TransactionError(_)
+            | AppError::NoRowsAffectedError(_)
+            | AppError::KeyValueStoreError(_)
+            | AppError::FileStoreError(_) => "internal_error",
+        }
+    }
+
+    fn into_problem(self, env: Environment) -> ProblemDetails {
+        let status = self.status_code();
+        let errors = match &self {
+            AppError::ValidationError(report) => report
+                .iter()
+                .map(|(path, error)| FieldError {
+                    field: path.to_string(),
+                    message: error.message().to_string(),
+                })
+                .collect(),
+            AppError::Conflict(field) => vec![FieldError {
+                field: field.clone(),
+                message: "has already been taken".to_string(),
+            }],
+            _ => Vec::new(),
+        };
+        // SQL や KVS の内部情報は本番環境ではクライアントに返さない
+        let detail = match (status.is_server_error(), env) {
+            (true, Environment::Production) => "An internal error occurred.".to_string(),
+            (true, Environment::Development) => match std::error::Error::source(&self) {
+                Some(source) => format!("{self} {source}"),
+                None => self.to_string(),
+            },
+            (false, _) => self.to_string(),
         };
-        status_code.into_response()
+
+        ProblemDetails {
+            r#type: "about:blank",
+            title: status.canonical_reason().unwrap_or_default(),
+            status: status.as_u16(),
+            code: self.code(),
+            detail,
+            errors,
+        }
+    }
+}
+
+// RFC 7807 (application/problem+json) 形式のエラーレスポンス
+#[derive(Debug, Serialize)]
+struct ProblemDetails {
+    r#type: &'static str,
+    title: &'static str,
+    status: u16,
+    code: &'static str,
+    detail: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<FieldError>,
+}
+
+#[derive(Debug, Serialize)]
+struct FieldError {
+    field: String,
+    message: String,
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> axum::response::Response {
+        let status_code = self.status_code();
+        let problem = self.into_problem(which());
+        (
+            status_code,
+            [(header::CONTENT_TYPE, "application/problem+json")],
+            Json(problem),
+        )
+            .into_response()
     }
 }
 
 pub type AppResult<T> = Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use garde::Validate;
+
+    #[derive(Validate)]
+    struct Request {
+        #[garde(length(min = 1))]
+        title: String,
+    }
+
+    #[test]
+    fn バリデーションエラーは項目ごとのエラーを含む() {
+        let report = Request {
+            title: String::new(),
+        }
+        .validate()
+        .expect_err("空文字は失敗する");
+
+        let problem = AppError::from(report).into_problem(Environment::Production);
+
+        assert_eq!(problem.status, 400);
+        assert_eq!(problem.code, "validation_failed");
+        assert_eq!(problem.title, "Bad Request");
+        assert_eq!(problem.errors.len(), 1);
+        assert_eq!(problem.errors[0].field, "title");
+    }
+
+    #[test]
+    fn 重複エラーは409と重複した項目を返す() {
+        let problem = AppError::Conflict("email".into()).into_problem(Environment::Production);
+
+        assert_eq!(problem.status, 409);
+        assert_eq!(problem.code, "conflict");
+        assert_eq!(problem.errors.len(), 1);
+        assert_eq!(problem.errors[0].field, "email");
+    }
+
+    #[test]
+    fn 状態の競合は409と理由を返す() {
+        let problem = AppError::StateConflict("The todo is blocked by open todos".into())
+            .into_problem(Environment::Production);
+
+        assert_eq!(problem.status, 409);
+        assert_eq!(problem.code, "state_conflict");
+        assert!(problem.errors.is_empty());
+        assert_eq!(problem.detail, "The todo is blocked by open todos");
+    }
+
+    #[test]
+    fn 本番環境では内部エラーの詳細を隠す() {
+        let problem = AppError::SqlExecuteError(sqlx::Error::PoolTimedOut)
+            .into_problem(Environment::Production);
+
+        assert_eq!(problem.status, 500);
+        assert_eq!(problem.code, "internal_error");
+        assert!(!problem.detail.contains("pool"));
+    }
+
+    #[test]
+    fn 開発環境では内部エラーの原因を返す() {
+        let problem = AppError::SqlExecuteError(sqlx::Error::PoolTimedOut)
+            .into_problem(Environment::Development);
+
+        assert!(problem.detail.contains("pool"));
+    }
+
+    #[test]
+    fn レスポンスはproblem_jsonで返す() {
+        let response =
+            AppError::EntityNotFoundError("The todo was not found".into()).into_response();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/problem+json"
+        );
+    }
+}
@@ -1,12 +1,8 @@
-use adapter::{database::connect_database_with, redis::RedisClient};
 use anyhow::{Context, Result};
 use api::route::v1;
 use axum::{Router, routing::get};
 use registry::AppRegistryImpl;
-use shared::{
-    config::AppConfig,
-    env::{Environment, which},
-};
+use shared::env::{Environment, which};
 use std::{
     net::{Ipv4Addr, SocketAddr},
     sync::Arc,
@@ -18,11 +14,7 @@ use tracing_subscriber::{EnvFilter, Registry, layer::SubscriberExt, util::Subscr
 async fn main() -> Result<()> {
     init_telemetry()?;
 
-    let app_config = AppConfig::new()?;
-
-    let pool = connect_database_with(&app_config.database);
-    let kv_store = Arc::new(RedisClient::new(&app_config.redis)?);
-    let registry = Arc::new(AppRegistryImpl::new(pool, kv_store, app_config));
+    let registry = Arc::new(AppRegistryImpl::from_env()?);
 
     let app = Router::new()
         .merge(v1::routes())
@@ -33,16 +25,20 @@ async fn main() -> Result<()> {
     let listener = TcpListener::bind(addr).await?;
     tracing::info!("Listening on {}", addr);
 
-    axum::serve(listener, app)
-        .await
-        .context("Failed to bind to address")
-        .inspect_err(|e| {
-            tracing::error!(
-                error.cause_chain = ?e,
-                error.message = %e,
-                "Failed to bind to address"
-            );
-        })
+    // セッション一覧に接続元のアドレスを出すため、接続情報をハンドラに渡す
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .context("Failed to bind to address")
+    .inspect_err(|e| {
+        tracing::error!(
+            error.cause_chain = ?e,
+            error.message = %e,
+            "Failed to bind to address"
+        );
+    })
 }
 
 fn init_telemetry() -> Result<()> {
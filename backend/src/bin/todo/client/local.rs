@@ -0,0 +1,251 @@
+use anyhow::{Context, Result, bail};
+use api::model::{
+    auth::LoginRequest,
+    todo::{
+        CreateTodoRequest, CreateTodoRequestWithUserId, TodoFilterParams, UpdateTodoRequest,
+        UpdateTodoRequestWithIds,
+    },
+};
+use async_trait::async_trait;
+use garde::Validate;
+use kernel::model::{
+    auth::{
+        AccessTokenId, ClientInfo, RefreshToken,
+        event::{RotateToken, StoreToken},
+    },
+    completion::{
+        Completion,
+        event::{CreateCompletion, UpdateReopened},
+    },
+    id::{CompletionId, TodoId, UserId},
+    list::{ListQuery, MAX_LIMIT},
+    todo::{Todo, event::DeleteTodo, filter::TodoFilter},
+};
+use registry::{AppRegistry, AppRegistryImpl};
+use std::sync::Arc;
+
+use crate::{
+    client::{TodoClient, USER_AGENT},
+    session::Session,
+};
+
+fn client_info() -> ClientInfo {
+    ClientInfo {
+        user_agent: Some(USER_AGENT.to_string()),
+        ip_address: None,
+    }
+}
+
+// HTTP サーバを介さず、API と同じリポジトリを直接呼び出す
+pub struct LocalClient {
+    registry: AppRegistry,
+}
+
+impl LocalClient {
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            registry: Arc::new(AppRegistryImpl::from_env_for_cli()?),
+        })
+    }
+
+    // トークンが失効していないかを毎回確かめ、API の AuthorizedUser と同じ扱いにする
+    async fn user_id(&self, session: &Session) -> Result<UserId> {
+        let access_token_id = self.access_token_id(session)?;
+        match self
+            .registry
+            .auth_repository()
+            .fetch_user_id_from_token(&access_token_id)
+            .await?
+        {
+            Some(user_id) if user_id == session.user_id => Ok(user_id),
+            _ => bail!("the session has expired; run `todo login` again"),
+        }
+    }
+
+    fn access_token_id(&self, session: &Session) -> Result<AccessTokenId> {
+        let claims = self
+            .registry
+            .token_service()
+            .verify(&session.access_token())
+            .ok()
+            .filter(|claims| claims.sub == session.user_id)
+            .context("the session has expired; run `todo login` again")?;
+        Ok(claims.jti)
+    }
+}
+
+#[async_trait]
+impl TodoClient for LocalClient {
+    async fn login(&self, email: String, password: String) -> Result<Session> {
+        let req = LoginRequest::new(email, password);
+        req.validate()?;
+
+        let credential = self
+            .registry
+            .auth_repository()
+            .find_by_email(req.email)
+            .await?
+            .filter(|credential| credential.verify_password(&req.password).unwrap_or(false))
+            .context("invalid email or password")?;
+
+        let auth_repository = self.registry.auth_repository();
+        let access_token_id = AccessTokenId::generate();
+        let access_token = self
+            .registry
+            .token_service()
+            .issue(credential.id, access_token_id.clone())?;
+        let refresh_token = RefreshToken::generate();
+        auth_repository
+            .store_token(StoreToken {
+                user_id: credential.id,
+                access_token_id,
+                refresh_token: refresh_token.clone(),
+                client: client_info(),
+            })
+            .await?;
+
+        Ok(Session::new(
+            credential.id,
+            access_token,
+            refresh_token,
+            auth_repository.token_ttl(),
+        ))
+    }
+
+    async fn logout(&self, session: &Session) -> Result<()> {
+        self.registry
+            .auth_repository()
+            .delete_token(self.access_token_id(session)?)
+            .await?;
+        Ok(())
+    }
+
+    async fn refresh(&self, session: &Session) -> Result<Session> {
+        let auth_repository = self.registry.auth_repository();
+        let access_token_id = AccessTokenId::generate();
+        let refresh_token = RefreshToken::generate();
+        let user_id = auth_repository
+            .rotate_token(RotateToken {
+                refresh_token: session.refresh_token()?,
+                access_token_id: access_token_id.clone(),
+                new_refresh_token: refresh_token.clone(),
+                client: client_info(),
+            })
+            .await?;
+        let access_token = self
+            .registry
+            .token_service()
+            .issue(user_id, access_token_id)?;
+
+        Ok(Session::new(
+            user_id,
+            access_token,
+            refresh_token,
+            auth_repository.token_ttl(),
+        ))
+    }
+
+    async fn add(&self, session: &Session, req: CreateTodoRequest) -> Result<Todo> {
+        let user_id = self.user_id(session).await?;
+        req.validate()?;
+
+        let todo = self
+            .registry
+            .todo_repository()
+            .create(CreateTodoRequestWithUserId::new(user_id, req).into())
+            .await?;
+        Ok(todo)
+    }
+
+    async fn list(&self, session: &Session, filter: TodoFilterParams) -> Result<Vec<Todo>> {
+        let user_id = self.user_id(session).await?;
+        let filter: TodoFilter = filter.try_into()?;
+        let mut todos = Vec::new();
+        let mut cursor: Option<String> = None;
+        // CLI では全件を表示したいので、最後のページまで辿る
+        loop {
+            let query = ListQuery::new(Some(MAX_LIMIT), cursor.as_deref(), None, None)?;
+            let page = self
+                .registry
+                .todo_repository()
+                .find_all(user_id, filter.clone(), query)
+                .await?;
+            todos.extend(page.items);
+            match page.next_cursor {
+                Some(next) => cursor = Some(next.encode()),
+                None => return Ok(todos),
+            }
+        }
+    }
+
+    async fn show(&self, session: &Session, id: TodoId) -> Result<Todo> {
+        let user_id = self.user_id(session).await?;
+        self.registry
+            .todo_repository()
+            .find_by_id(id, user_id)
+            .await?
+            .with_context(|| format!("todo {id} was not found"))
+    }
+
+    async fn edit(&self, session: &Session, id: TodoId, req: UpdateTodoRequest) -> Result<()> {
+        let user_id = self.user_id(session).await?;
+        req.validate()?;
+
+        self.registry
+            .todo_repository()
+            .update(UpdateTodoRequestWithIds::new(id, user_id, req).into())
+            .await?;
+        Ok(())
+    }
+
+    async fn remove(&self, session: &Session, id: TodoId) -> Result<()> {
+        let user_id = self.user_id(session).await?;
+        self.registry
+            .todo_repository()
+            .delete(DeleteTodo { id, user_id })
+            .await?;
+        Ok(())
+    }
+
+    async fn complete(&self, session: &Session, id: TodoId, force: bool) -> Result<Completion> {
+        let user_id = self.user_id(session).await?;
+        let completion = self
+            .registry
+            .completion_repository()
+            .create(CreateCompletion {
+                todo_id: id,
+                user_id,
+                include_subtasks: false,
+                force,
+            })
+            .await?;
+        Ok(completion)
+    }
+
+    async fn history(&self, session: &Session, id: TodoId) -> Result<Vec<Completion>> {
+        let user_id = self.user_id(session).await?;
+        Ok(self
+            .registry
+            .completion_repository()
+            .find_history_by_todo_id(id, user_id)
+            .await?)
+    }
+
+    async fn reopen(
+        &self,
+        session: &Session,
+        id: TodoId,
+        completion_id: CompletionId,
+    ) -> Result<()> {
+        let user_id = self.user_id(session).await?;
+        self.registry
+            .completion_repository()
+            .update_reopened(UpdateReopened {
+                completion_id,
+                todo_id: id,
+                user_id,
+            })
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use anyhow::Result;
+use api::model::todo::{CreateTodoRequest, TodoFilterParams, UpdateTodoRequest};
+use async_trait::async_trait;
+use kernel::model::{
+    completion::Completion,
+    id::{CompletionId, TodoId},
+    todo::Todo,
+};
+
+use crate::session::Session;
+
+pub mod local;
+pub mod remote;
+
+// セッション一覧で CLI からのログインと分かるよう、端末の情報として送る
+pub const USER_AGENT: &str = concat!("todo-cli/", env!("CARGO_PKG_VERSION"));
+
+// ローカル（レジストリを直接呼ぶ）とリモート（HTTP API を呼ぶ）の差を吸収する
+#[async_trait]
+pub trait TodoClient: Send + Sync {
+    async fn login(&self, email: String, password: String) -> Result<Session>;
+    async fn logout(&self, session: &Session) -> Result<()>;
+    // リフレッシュトークンでアクセストークンを更新した新しいセッションを返す
+    async fn refresh(&self, session: &Session) -> Result<Session>;
+    async fn add(&self, session: &Session, req: CreateTodoRequest) -> Result<Todo>;
+    async fn list(&self, session: &Session, filter: TodoFilterParams) -> Result<Vec<Todo>>;
+    async fn show(&self, session: &Session, id: TodoId) -> Result<Todo>;
+    async fn edit(&self, session: &Session, id: TodoId, req: UpdateTodoRequest) -> Result<()>;
+    async fn remove(&self, session: &Session, id: TodoId) -> Result<()>;
+    async fn complete(&self, session: &Session, id: TodoId, force: bool) -> Result<Completion>;
+    async fn history(&self, session: &Session, id: TodoId) -> Result<Vec<Completion>>;
+    async fn reopen(
+        &self,
+        session: &Session,
+        id: TodoId,
+        completion_id: CompletionId,
+    ) -> Result<()>;
+}
@@ -0,0 +1,234 @@
+use anyhow::{Result, bail};
+use api::model::{
+    auth::{AccessTokenResponse, LoginRequest, RefreshTokenRequest},
+    completion::{CompletionResponse, CompletionsResponse},
+    todo::{CreateTodoRequest, TodoFilterParams, TodoResponse, TodosResponse, UpdateTodoRequest},
+};
+use async_trait::async_trait;
+use kernel::model::{
+    auth::{AccessToken, RefreshToken},
+    completion::{Completion, CompletionTodo},
+    id::{CompletionId, TodoId, UserId},
+    list::MAX_LIMIT,
+    todo::Todo,
+};
+use reqwest::{Method, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    client::{TodoClient, USER_AGENT},
+    session::Session,
+};
+
+// `/api/v1` 配下の HTTP API を呼び出す
+pub struct RemoteClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl RemoteClient {
+    pub fn new(base_url: String) -> Self {
+        Self {
+            http: reqwest::Client::builder()
+                .user_agent(USER_AGENT)
+                .build()
+                .unwrap_or_default(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn request(&self, method: Method, path: &str, session: Option<&Session>) -> RequestBuilder {
+        let req = self
+            .http
+            .request(method, format!("{}/api/v1{}", self.base_url, path));
+        match session {
+            Some(session) => req.bearer_auth(session.access_token().0),
+            None => req,
+        }
+    }
+
+    async fn send(&self, req: RequestBuilder) -> Result<Response> {
+        let res = req.send().await?;
+        let status = res.status();
+        if !status.is_success() {
+            bail!("request failed with status {status}");
+        }
+        Ok(res)
+    }
+
+    async fn send_json<T: DeserializeOwned>(&self, req: RequestBuilder) -> Result<T> {
+        Ok(self.send(req).await?.json().await?)
+    }
+}
+
+#[async_trait]
+impl TodoClient for RemoteClient {
+    async fn login(&self, email: String, password: String) -> Result<Session> {
+        let req = self
+            .request(Method::POST, "/auth/login", None)
+            .json(&LoginRequest::new(email, password));
+        let res: AccessTokenResponse = self.send_json(req).await?;
+        Ok(into_session(res))
+    }
+
+    async fn logout(&self, session: &Session) -> Result<()> {
+        self.send(self.request(Method::POST, "/auth/logout", Some(session)))
+            .await?;
+        Ok(())
+    }
+
+    async fn refresh(&self, session: &Session) -> Result<Session> {
+        let req = self
+            .request(Method::POST, "/auth/refresh", None)
+            .json(&RefreshTokenRequest::new(session.refresh_token()?.0));
+        let res: AccessTokenResponse = self.send_json(req).await?;
+        Ok(into_session(res))
+    }
+
+    async fn add(&self, session: &Session, req: CreateTodoRequest) -> Result<Todo> {
+        let req = self
+            .request(Method::POST, "/todos", Some(session))
+            .json(&req);
+        let res: TodoResponse = self.send_json(req).await?;
+        Ok(into_todo(res, session.user_id))
+    }
+
+    async fn list(&self, session: &Session, filter: TodoFilterParams) -> Result<Vec<Todo>> {
+        let mut todos = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut req = self
+                .request(Method::GET, "/todos", Some(session))
+                .query(&filter)
+                .query(&[("limit", MAX_LIMIT.to_string())]);
+            if let Some(cursor) = &cursor {
+                req = req.query(&[("cursor", cursor)]);
+            }
+            let res: TodosResponse = self.send_json(req).await?;
+            todos.extend(
+                res.items
+                    .into_iter()
+                    .map(|todo| into_todo(todo, session.user_id)),
+            );
+            match res.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return Ok(todos),
+            }
+        }
+    }
+
+    async fn show(&self, session: &Session, id: TodoId) -> Result<Todo> {
+        let req = self.request(Method::GET, &format!("/todos/{id}"), Some(session));
+        let res: TodoResponse = self.send_json(req).await?;
+        Ok(into_todo(res, session.user_id))
+    }
+
+    async fn edit(&self, session: &Session, id: TodoId, req: UpdateTodoRequest) -> Result<()> {
+        let req = self
+            .request(Method::PUT, &format!("/todos/{id}"), Some(session))
+            .json(&req);
+        self.send(req).await?;
+        Ok(())
+    }
+
+    async fn remove(&self, session: &Session, id: TodoId) -> Result<()> {
+        let req = self.request(Method::DELETE, &format!("/todos/{id}"), Some(session));
+        self.send(req).await?;
+        Ok(())
+    }
+
+    async fn complete(&self, session: &Session, id: TodoId, force: bool) -> Result<Completion> {
+        let mut req = self.request(
+            Method::POST,
+            &format!("/todos/{id}/complete"),
+            Some(session),
+        );
+        if force {
+            req = req.query(&[("force", "true")]);
+        }
+        let res: CompletionResponse = self.send_json(req).await?;
+        Ok(into_completion(res))
+    }
+
+    async fn history(&self, session: &Session, id: TodoId) -> Result<Vec<Completion>> {
+        let req = self.request(Method::GET, &format!("/todos/{id}/history"), Some(session));
+        let res: CompletionsResponse = self.send_json(req).await?;
+        Ok(res.items.into_iter().map(into_completion).collect())
+    }
+
+    async fn reopen(
+        &self,
+        session: &Session,
+        id: TodoId,
+        completion_id: CompletionId,
+    ) -> Result<()> {
+        let req = self.request(
+            Method::PUT,
+            &format!("/todos/{id}/complete/{completion_id}/reopen"),
+            Some(session),
+        );
+        self.send(req).await?;
+        Ok(())
+    }
+}
+
+// レスポンスには所有者が含まれないため、セッションのユーザを補う
+fn into_session(res: AccessTokenResponse) -> Session {
+    Session::new(
+        res.user_id,
+        AccessToken(res.access_token),
+        RefreshToken(res.refresh_token),
+        res.expires_in,
+    )
+}
+
+fn into_todo(value: TodoResponse, user_id: UserId) -> Todo {
+    let TodoResponse {
+        id,
+        project_id,
+        parent_id,
+        title,
+        description,
+        priority,
+        completed,
+        due_at,
+        recurrence,
+        position,
+        created_at,
+        updated_at,
+        ..
+    } = value;
+    Todo {
+        id,
+        user_id,
+        project_id,
+        parent_id,
+        title,
+        description,
+        priority,
+        completed,
+        due_at,
+        recurrence,
+        position,
+        created_at,
+        updated_at,
+    }
+}
+
+fn into_completion(value: CompletionResponse) -> Completion {
+    let CompletionResponse {
+        id,
+        todo,
+        completed_at,
+        reopened_at,
+    } = value;
+    Completion {
+        id,
+        todo: CompletionTodo {
+            id: todo.id,
+            title: todo.title,
+        },
+        completed_at,
+        reopened_at,
+    }
+}
@@ -0,0 +1,265 @@
+mod client;
+mod output;
+mod session;
+
+use anyhow::{Context, Result, bail};
+use api::model::todo::{CreateTodoRequest, TodoFilterParams, UpdateTodoRequest};
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand};
+use kernel::model::{
+    id::TodoId,
+    todo::{Priority, recurrence::Recurrence},
+};
+use std::io::{self, BufRead, Write};
+
+use crate::{
+    client::{TodoClient, local::LocalClient, remote::RemoteClient},
+    output::{OutputFormat, Printer},
+    session::Session,
+};
+
+/// rusty-todo のコマンドラインクライアント
+#[derive(Parser)]
+#[command(name = "todo", version)]
+struct Cli {
+    /// API サーバの URL。指定すると HTTP API 経由で操作する（未指定ならローカルで直接操作する）
+    #[arg(long, env = "TODO_API_URL", global = true)]
+    remote: Option<String>,
+
+    /// 出力形式
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table, global = true)]
+    output: OutputFormat,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// todo を追加する
+    Add {
+        title: String,
+        /// 期限（RFC 3339 形式。例: 2026-10-18T09:00:00Z）
+        #[arg(long)]
+        due: Option<DateTime<Utc>>,
+        /// 重要度（none / low / medium / high / urgent）
+        #[arg(long, default_value_t = Priority::None)]
+        priority: Priority,
+        /// Markdown で書いた説明
+        #[arg(long)]
+        description: Option<String>,
+        /// サブタスクとして追加するときの親 todo の ID
+        #[arg(long)]
+        parent: Option<TodoId>,
+        /// 繰り返しの規則（RRULE 形式。例: FREQ=WEEKLY;BYDAY=MO,TH）
+        #[arg(long)]
+        repeat: Option<Recurrence>,
+    },
+    /// todo を一覧表示する
+    List {
+        /// 未完了の todo だけを表示する
+        #[arg(long)]
+        open: bool,
+        /// 未完了で期限を過ぎた todo だけを表示する
+        #[arg(long)]
+        overdue: bool,
+        /// タイトルに指定した文字列を含む todo だけを表示する
+        #[arg(long)]
+        title: Option<String>,
+        /// 期限がこの日時より前の todo だけを表示する（RFC 3339 形式）
+        #[arg(long)]
+        due_before: Option<DateTime<Utc>>,
+        /// 期限がこの日時以降の todo だけを表示する（RFC 3339 形式）
+        #[arg(long)]
+        due_after: Option<DateTime<Utc>>,
+    },
+    /// todo を完了にする
+    Done {
+        id: TodoId,
+        /// 未完了の blocker が残っていても完了にする
+        #[arg(long)]
+        force: bool,
+    },
+    /// 完了した todo を未完了に戻す
+    Reopen { id: TodoId },
+    /// todo を削除する
+    Rm { id: TodoId },
+    /// todo のタイトル・期限・重要度・説明を変更する
+    Edit {
+        id: TodoId,
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(long, conflicts_with = "clear_due")]
+        due: Option<DateTime<Utc>>,
+        /// 期限を外す
+        #[arg(long)]
+        clear_due: bool,
+        #[arg(long)]
+        priority: Option<Priority>,
+        #[arg(long, conflicts_with = "clear_description")]
+        description: Option<String>,
+        /// 説明を外す
+        #[arg(long)]
+        clear_description: bool,
+    },
+    /// ログインしてセッションを保存する
+    Login {
+        #[arg(long)]
+        email: String,
+        /// 未指定なら標準入力から読み込む
+        #[arg(long, env = "TODO_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+    },
+    /// ログアウトしてセッションを破棄する
+    Logout,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let client: Box<dyn TodoClient> = match cli.remote {
+        Some(base_url) => Box::new(RemoteClient::new(base_url)),
+        None => Box::new(LocalClient::from_env()?),
+    };
+    let printer = Printer::new(cli.output);
+
+    match cli.command {
+        Command::Login { email, password } => {
+            let password = match password {
+                Some(password) => password,
+                None => read_password()?,
+            };
+            let session = client.login(email, password).await?;
+            session.save()?;
+            printer.logged_in(&session)
+        }
+        Command::Logout => {
+            let session = load_session(client.as_ref()).await?;
+            client.logout(&session).await?;
+            Session::clear()?;
+            printer.logged_out()
+        }
+        Command::Add {
+            title,
+            due,
+            priority,
+            description,
+            parent,
+            repeat,
+        } => {
+            let session = load_session(client.as_ref()).await?;
+            let req =
+                CreateTodoRequest::new(title, due, priority, description, None, parent, repeat);
+            let todo = client.add(&session, req).await?;
+            printer.todo(todo)
+        }
+        Command::List {
+            open,
+            overdue,
+            title,
+            due_before,
+            due_after,
+        } => {
+            let session = load_session(client.as_ref()).await?;
+            let filter = TodoFilterParams {
+                completed: open.then_some(false),
+                overdue: overdue.then_some(true),
+                due_before,
+                due_after,
+                title,
+                ..Default::default()
+            };
+            let todos = client.list(&session, filter).await?;
+            printer.todos(todos)
+        }
+        Command::Done { id, force } => {
+            let session = load_session(client.as_ref()).await?;
+            let completion = client.complete(&session, id, force).await?;
+            printer.completion(completion)
+        }
+        Command::Reopen { id } => {
+            let session = load_session(client.as_ref()).await?;
+            // 再オープンの対象は、まだ再オープンされていない最新の完了記録
+            let completion = client
+                .history(&session, id)
+                .await?
+                .into_iter()
+                .find(|completion| completion.reopened_at.is_none())
+                .with_context(|| format!("todo {id} is not completed"))?;
+            client.reopen(&session, id, completion.id).await?;
+            let todo = client.show(&session, id).await?;
+            printer.todo(todo)
+        }
+        Command::Rm { id } => {
+            let session = load_session(client.as_ref()).await?;
+            client.remove(&session, id).await?;
+            printer.removed(id)
+        }
+        Command::Edit {
+            id,
+            title,
+            due,
+            clear_due,
+            priority,
+            description,
+            clear_description,
+        } => {
+            if title.is_none()
+                && due.is_none()
+                && !clear_due
+                && priority.is_none()
+                && description.is_none()
+                && !clear_description
+            {
+                bail!(
+                    "nothing to edit: specify --title, --due, --clear-due, --priority, \
+                     --description or --clear-description"
+                );
+            }
+            let session = load_session(client.as_ref()).await?;
+            // PUT は全項目を置き換えるため、指定されなかった項目は現在の値を引き継ぐ
+            let current = client.show(&session, id).await?;
+            let due = if clear_due {
+                None
+            } else {
+                due.or(current.due_at)
+            };
+            let description = if clear_description {
+                None
+            } else {
+                description.or(current.description)
+            };
+            let req = UpdateTodoRequest::new(
+                title.unwrap_or(current.title),
+                due,
+                priority.unwrap_or(current.priority),
+                description,
+                current.project_id,
+                current.parent_id,
+                current.recurrence,
+            );
+            client.edit(&session, id, req).await?;
+            let todo = client.show(&session, id).await?;
+            printer.todo(todo)
+        }
+    }
+}
+
+// アクセストークンの期限が切れていれば、リフレッシュトークンで更新したセッションを保存し直す
+async fn load_session(client: &dyn TodoClient) -> Result<Session> {
+    let session = Session::load()?;
+    if !session.is_expired() {
+        return Ok(session);
+    }
+    let session = client.refresh(&session).await?;
+    session.save()?;
+    Ok(session)
+}
+
+fn read_password() -> Result<String> {
+    eprint!("Password: ");
+    io::stderr().flush()?;
+    let mut password = String::new();
+    io::stdin().lock().read_line(&mut password)?;
+    Ok(password.trim_end_matches(['\r', '\n']).to_string())
+}
@@ -0,0 +1,185 @@
+use anyhow::Result;
+use api::model::{
+    completion::CompletionResponse,
+    todo::{TodoResponse, TodosResponse},
+};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use kernel::model::{
+    completion::Completion,
+    id::TodoId,
+    todo::{Priority, Todo},
+};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::session::Session;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+}
+
+// JSON 出力は HTTP API のレスポンスと同じ形にそろえる
+pub struct Printer {
+    format: OutputFormat,
+}
+
+impl Printer {
+    pub fn new(format: OutputFormat) -> Self {
+        Self { format }
+    }
+
+    pub fn todo(&self, todo: Todo) -> Result<()> {
+        match self.format {
+            OutputFormat::Table => print!("{}", render_todos(std::slice::from_ref(&todo))),
+            OutputFormat::Json => print_json(&TodoResponse::from(todo))?,
+        }
+        Ok(())
+    }
+
+    pub fn todos(&self, todos: Vec<Todo>) -> Result<()> {
+        match self.format {
+            OutputFormat::Table => print!("{}", render_todos(&todos)),
+            OutputFormat::Json => print_json(&TodosResponse {
+                items: todos.into_iter().map(TodoResponse::from).collect(),
+                next_cursor: None,
+            })?,
+        }
+        Ok(())
+    }
+
+    pub fn completion(&self, completion: Completion) -> Result<()> {
+        match self.format {
+            OutputFormat::Table => println!(
+                "Completed {} ({}) at {}",
+                completion.todo.id,
+                completion.todo.title,
+                format_datetime(completion.completed_at)
+            ),
+            OutputFormat::Json => print_json(&CompletionResponse::from(completion))?,
+        }
+        Ok(())
+    }
+
+    pub fn removed(&self, id: TodoId) -> Result<()> {
+        match self.format {
+            OutputFormat::Table => println!("Deleted {id}"),
+            OutputFormat::Json => print_json(&json!({ "id": id }))?,
+        }
+        Ok(())
+    }
+
+    pub fn logged_in(&self, session: &Session) -> Result<()> {
+        match self.format {
+            OutputFormat::Table => println!("Logged in as {}", session.user_id),
+            OutputFormat::Json => print_json(&json!({ "userId": session.user_id }))?,
+        }
+        Ok(())
+    }
+
+    pub fn logged_out(&self) -> Result<()> {
+        match self.format {
+            OutputFormat::Table => println!("Logged out"),
+            OutputFormat::Json => print_json(&json!({}))?,
+        }
+        Ok(())
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn format_datetime(value: DateTime<Utc>) -> String {
+    value.format("%Y-%m-%d %H:%M").to_string()
+}
+
+fn render_todos(todos: &[Todo]) -> String {
+    let header = ["ID", "DONE", "PRIORITY", "TITLE", "DUE", "CREATED"].map(String::from);
+    let rows: Vec<[String; 6]> = todos
+        .iter()
+        .map(|todo| {
+            [
+                todo.id.to_string(),
+                if todo.completed { "x" } else { "" }.to_string(),
+                match todo.priority {
+                    Priority::None => String::new(),
+                    priority => priority.to_string(),
+                },
+                todo.title.clone(),
+                todo.due_at.map(format_datetime).unwrap_or_default(),
+                format_datetime(todo.created_at),
+            ]
+        })
+        .collect();
+
+    let mut widths = header.clone().map(|cell| cell.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    std::iter::once(&header)
+        .chain(&rows)
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            format!("{}\n", line.trim_end())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kernel::model::{id::UserId, todo::position::Position};
+
+    #[test]
+    fn todo一覧は列をそろえて表示する() {
+        let created_at = "2026-10-18T09:00:00Z".parse().expect("日時");
+        let todo = Todo {
+            id: TodoId::new(),
+            user_id: UserId::new(),
+            project_id: None,
+            parent_id: None,
+            title: "buy milk".to_string(),
+            description: None,
+            priority: Priority::High,
+            completed: true,
+            due_at: None,
+            recurrence: None,
+            position: Position::first(),
+            created_at,
+            updated_at: created_at,
+        };
+
+        let table = render_todos(std::slice::from_ref(&todo));
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("ID"));
+        assert!(lines[1].starts_with(&todo.id.to_string()));
+        assert!(lines[1].ends_with("high      buy milk       2026-10-18 09:00"));
+        assert_eq!(
+            lines[0].find("TITLE"),
+            lines[1].find("buy milk"),
+            "TITLE 列の位置がそろう"
+        );
+    }
+
+    #[test]
+    fn todoがなければヘッダだけを表示する() {
+        assert_eq!(
+            render_todos(&[]),
+            "ID  DONE  PRIORITY  TITLE  DUE  CREATED\n"
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use kernel::model::{
+    auth::{AccessToken, RefreshToken},
+    id::UserId,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+const SESSION_FILE_NAME: &str = "session.json";
+// 期限の少し前から切れたものとして扱い、コマンドの途中で失効しないようにする
+const EXPIRY_MARGIN_SECS: i64 = 30;
+
+// ログイン中のトークンを保持する。既定では ~/.rusty-todo/session.json に保存する
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub user_id: UserId,
+    access_token: String,
+    // リフレッシュトークン導入前のセッションファイルにはないため省略できる
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl Session {
+    pub fn new(
+        user_id: UserId,
+        access_token: AccessToken,
+        refresh_token: RefreshToken,
+        expires_in: u64,
+    ) -> Self {
+        Self {
+            user_id,
+            access_token: access_token.0,
+            refresh_token: Some(refresh_token.0),
+            expires_at: Some(Utc::now() + Duration::seconds(expires_in as i64)),
+        }
+    }
+
+    pub fn access_token(&self) -> AccessToken {
+        AccessToken(self.access_token.clone())
+    }
+
+    pub fn refresh_token(&self) -> Result<RefreshToken> {
+        self.refresh_token
+            .clone()
+            .map(RefreshToken)
+            .context("the session has expired; run `todo login` again")
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|at| at - Duration::seconds(EXPIRY_MARGIN_SECS) <= Utc::now())
+    }
+
+    pub fn load() -> Result<Self> {
+        load_from(&session_path()?)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        save_to(self, &session_path()?)
+    }
+
+    pub fn clear() -> Result<()> {
+        match fs::remove_file(session_path()?) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn session_path() -> Result<PathBuf> {
+    if let Ok(path) = std::env::var("TODO_SESSION_PATH") {
+        return Ok(path.into());
+    }
+    let home = std::env::var("HOME").context("HOME is not set; set TODO_SESSION_PATH instead")?;
+    Ok(Path::new(&home).join(".rusty-todo").join(SESSION_FILE_NAME))
+}
+
+fn load_from(path: &Path) -> Result<Session> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            anyhow::bail!("not logged in; run `todo login` first")
+        }
+        Err(e) => return Err(e.into()),
+    };
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn save_to(session: &Session, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    // トークンを含むため、所有者だけが読める一時ファイルに書いてから rename で置き換える。
+    // 前回の書き込みが途中で止まって残った一時ファイルは権限が違うかもしれないので作り直す
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+    match fs::remove_file(&tmp_path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+        _ => {}
+    }
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&tmp_path)?;
+    file.write_all(serde_json::to_string_pretty(session)?.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn 保存したセッションを読み込める() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let path = dir.path().join("nested").join(SESSION_FILE_NAME);
+        let user_id = UserId::new();
+
+        save_to(
+            &Session::new(
+                user_id,
+                AccessToken("test-token".to_string()),
+                RefreshToken("refresh-token".to_string()),
+                3600,
+            ),
+            &path,
+        )
+        .expect("保存が成功する");
+        let session = load_from(&path).expect("読み込みが成功する");
+
+        assert_eq!(session.user_id, user_id);
+        assert_eq!(
+            session.access_token(),
+            AccessToken("test-token".to_string())
+        );
+        assert!(!session.is_expired());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn セッションファイルは所有者だけが読める() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let path = dir.path().join(SESSION_FILE_NAME);
+        // 以前のバージョンが作った、他人からも読めるファイルを置き換える
+        fs::write(&path, "{}").expect("書き込みが成功する");
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).expect("権限を変えられる");
+
+        save_to(
+            &Session::new(
+                UserId::new(),
+                AccessToken("test-token".to_string()),
+                RefreshToken("refresh-token".to_string()),
+                3600,
+            ),
+            &path,
+        )
+        .expect("保存が成功する");
+
+        let mode = fs::metadata(&path)
+            .expect("メタデータ")
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(load_from(&path).is_ok());
+    }
+
+    #[test]
+    fn 期限のない古いセッションも読み込める() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+        let path = dir.path().join(SESSION_FILE_NAME);
+        let user_id = UserId::new();
+        fs::write(
+            &path,
+            format!(r#"{{"userId":"{user_id}","accessToken":"test-token"}}"#),
+        )
+        .expect("書き込みが成功する");
+
+        let session = load_from(&path).expect("読み込みが成功する");
+
+        assert!(!session.is_expired());
+        assert!(session.refresh_token().is_err());
+    }
+
+    #[test]
+    fn 期限の直前のセッションは期限切れとして扱う() {
+        let session = Session::new(
+            UserId::new(),
+            AccessToken("test-token".to_string()),
+            RefreshToken("refresh-token".to_string()),
+            10,
+        );
+
+        assert!(session.is_expired());
+    }
+
+    #[test]
+    fn セッションがなければログインを促す() {
+        let dir = tempfile::tempdir().expect("一時ディレクトリ");
+
+        let err = load_from(&dir.path().join(SESSION_FILE_NAME)).expect_err("未ログインは失敗する");
+
+        assert!(err.to_string().contains("todo login"));
+    }
+}